@@ -6,13 +6,16 @@
 //! * Optionally: Add [`TnuaAvian2dSensorShape`] to the sensor entities. This means the entity of
 //!   the characters controlled by Tnua, but also other things like the entity generated by
 //!   `TnuaCrouchEnforcer`, that can be affected with a closure.
+//! * Optionally: Add [`TnuaAvian2dSensorCollisionLayers`] to sensor entities that should use
+//!   different collision layers than their owner entity.
 use avian2d::math::{AdjustPrecision, AsF32};
 use avian2d::{prelude::*, schedule::PhysicsStepSet};
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
 use bevy_tnua_physics_integration_layer::data_for_backends::{
     TnuaGhostPlatform, TnuaGhostSensor, TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput,
-    TnuaRigidBodyTracker, TnuaToggle,
+    TnuaRigidBodyTracker, TnuaSensorFilter, TnuaSleepState, TnuaSurfaceUserData, TnuaToggle,
+    TnuaUpdateTimer,
 };
 use bevy_tnua_physics_integration_layer::math::*;
 use bevy_tnua_physics_integration_layer::subservient_sensors::TnuaSubservientSensor;
@@ -59,9 +62,30 @@ impl Plugin for TnuaAvian2dPlugin {
 }
 
 /// Add this component to make [`TnuaProximitySensor`] cast a shape instead of a ray.
+///
+/// A single ray can miss the ground when the character stands half-off a ledge or on a platform
+/// narrower than its collider, making Tnua think the character is airborne. A flat shape about as
+/// wide as the collider fixes that. Make it a little bit smaller than the collider, so that when
+/// the character presses against a wall the casted shape won't hit that wall:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use avian2d::prelude::*;
+/// # use bevy_tnua_avian2d::*;
+/// # let mut commands: Commands = panic!();
+/// # let mut cmd = commands.spawn_empty();
+/// // A capsule with a radius of 0.5:
+/// cmd.insert(Collider::capsule(0.5, 1.0));
+/// cmd.insert(TnuaAvian2dSensorShape(Collider::rectangle(0.98, 0.0)));
+/// ```
 #[derive(Component)]
 pub struct TnuaAvian2dSensorShape(pub Collider);
 
+/// Add this component to make [`TnuaProximitySensor`] use these collision layers instead of the
+/// ones of its owner entity.
+#[derive(Component)]
+pub struct TnuaAvian2dSensorCollisionLayers(pub CollisionLayers);
+
 fn update_rigid_body_trackers_system(
     gravity: Res<Gravity>,
     mut query: Query<(
@@ -72,22 +96,23 @@ fn update_rigid_body_trackers_system(
         Option<&TnuaToggle>,
     )>,
 ) {
-    for (transform, linaer_velocity, angular_velocity, mut tracker, tnua_toggle) in query.iter_mut()
-    {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled => continue,
-            TnuaToggle::SenseOnly => {}
-            TnuaToggle::Enabled => {}
-        }
-        let (_, rotation, translation) = transform.to_scale_rotation_translation();
-        *tracker = TnuaRigidBodyTracker {
-            translation: translation.adjust_precision(),
-            rotation: rotation.adjust_precision(),
-            velocity: linaer_velocity.0.extend(0.0),
-            angvel: Vector3::new(0.0, 0.0, angular_velocity.0),
-            gravity: gravity.0.extend(0.0),
-        };
-    }
+    query.par_iter_mut().for_each(
+        |(transform, linaer_velocity, angular_velocity, mut tracker, tnua_toggle)| {
+            match tnua_toggle.copied().unwrap_or_default() {
+                TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => return,
+                TnuaToggle::SenseOnly => {}
+                TnuaToggle::Enabled => {}
+            }
+            let (_, rotation, translation) = transform.to_scale_rotation_translation();
+            *tracker = TnuaRigidBodyTracker {
+                translation: translation.adjust_precision(),
+                rotation: rotation.adjust_precision(),
+                velocity: linaer_velocity.0.extend(0.0),
+                angvel: Vector3::new(0.0, 0.0, angular_velocity.0),
+                gravity: gravity.0.extend(0.0),
+            };
+        },
+    );
 }
 
 #[allow(clippy::type_complexity)]
@@ -99,14 +124,20 @@ fn update_proximity_sensors_system(
         &GlobalTransform,
         &mut TnuaProximitySensor,
         Option<&TnuaAvian2dSensorShape>,
+        Option<&TnuaSensorFilter>,
+        Option<&TnuaAvian2dSensorCollisionLayers>,
         Option<&mut TnuaGhostSensor>,
         Option<&TnuaSubservientSensor>,
         Option<&TnuaToggle>,
+        Option<&TnuaSleepState>,
+        Option<&TnuaUpdateTimer>,
     )>,
     collision_layers_entity: Query<&CollisionLayers>,
     other_object_query: Query<(
         Option<(&GlobalTransform, &LinearVelocity, &AngularVelocity)>,
         Option<&CollisionLayers>,
+        Option<&Friction>,
+        Option<&TnuaSurfaceUserData>,
         Has<TnuaGhostPlatform>,
         Has<Sensor>,
     )>,
@@ -117,15 +148,24 @@ fn update_proximity_sensors_system(
             transform,
             mut sensor,
             shape,
+            filter,
+            collision_layers_override,
             mut ghost_sensor,
             subservient,
             tnua_toggle,
+            sleep_state,
+            update_timer,
         )| {
             match tnua_toggle.copied().unwrap_or_default() {
-                TnuaToggle::Disabled => return,
+                TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => return,
                 TnuaToggle::SenseOnly => {}
                 TnuaToggle::Enabled => {}
             }
+            if sleep_state.copied().unwrap_or_default() == TnuaSleepState::Asleep
+                || update_timer.is_some_and(|update_timer| !update_timer.is_update_frame())
+            {
+                return;
+            }
             let cast_origin = transform.transform_point(sensor.cast_origin.f32());
             let cast_direction = sensor.cast_direction;
             let cast_direction_2d = Dir2::new(cast_direction.truncate())
@@ -146,7 +186,12 @@ fn update_proximity_sensors_system(
                 owner_entity
             };
 
-            let collision_layers = collision_layers_entity.get(owner_entity).ok();
+            let collision_layers =
+                if let Some(collision_layers_override) = collision_layers_override {
+                    Some(&collision_layers_override.0)
+                } else {
+                    collision_layers_entity.get(owner_entity).ok()
+                };
 
             let mut final_sensor_output = None;
             if let Some(ghost_sensor) = ghost_sensor.as_mut() {
@@ -160,6 +205,12 @@ fn update_proximity_sensors_system(
                     normal,
                 } = cast_result;
 
+                // This is checked here instead of being excluded in the spatial query filter, because
+                // the filter would have to allocate a set for it every frame.
+                if entity == owner_entity || filter.is_some_and(|filter| !filter.allows(entity)) {
+                    return true;
+                }
+
                 // This fixes https://github.com/idanarye/bevy-tnua/issues/14
                 if let Some(contacts) = collisions.get(owner_entity, entity) {
                     let same_order = owner_entity == contacts.entity1;
@@ -186,6 +237,8 @@ fn update_proximity_sensors_system(
                 let Ok((
                     entity_kinematic_data,
                     entity_collision_layers,
+                    entity_friction,
+                    entity_user_data,
                     entity_is_ghost,
                     entity_is_sensor,
                 )) = other_object_query.get(entity)
@@ -220,6 +273,14 @@ fn update_proximity_sensors_system(
                     normal,
                     entity_linvel,
                     entity_angvel,
+                    entity_friction: Some(
+                        entity_friction
+                            .copied()
+                            .unwrap_or_default()
+                            .dynamic_coefficient
+                            .adjust_precision(),
+                    ),
+                    entity_user_data: entity_user_data.map(|data| data.0),
                 };
 
                 let excluded_by_collision_layers = || {
@@ -242,7 +303,7 @@ fn update_proximity_sensors_system(
                 }
             };
 
-            let query_filter = SpatialQueryFilter::from_excluded_entities([owner_entity]);
+            let query_filter = SpatialQueryFilter::default();
             if let Some(TnuaAvian2dSensorShape(shape)) = shape {
                 spatial_query_pipeline.shape_hits_callback(
                     shape,
@@ -302,41 +363,54 @@ fn apply_motors_system(
         &mut ExternalForce,
         &mut ExternalTorque,
         Option<&TnuaToggle>,
+        Option<&TnuaSleepState>,
+        Option<&TnuaUpdateTimer>,
     )>,
 ) {
-    for (
-        motor,
-        mut linare_velocity,
-        mut angular_velocity,
-        mass,
-        inertia,
-        mut external_force,
-        mut external_torque,
-        tnua_toggle,
-    ) in query.iter_mut()
-    {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
-                *external_force = Default::default();
+    query.par_iter_mut().for_each(
+        |(
+            motor,
+            mut linare_velocity,
+            mut angular_velocity,
+            mass,
+            inertia,
+            mut external_force,
+            mut external_torque,
+            tnua_toggle,
+            sleep_state,
+            update_timer,
+        )| {
+            match tnua_toggle.copied().unwrap_or_default() {
+                TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
+                    *external_force = Default::default();
+                    return;
+                }
+                // Leave the forces from the last time the motor was applied in place.
+                TnuaToggle::SuspendedKeepState => return,
+                TnuaToggle::Enabled => {}
+            }
+            // Leave the forces from the last time the motor was applied in place.
+            if sleep_state.copied().unwrap_or_default() != TnuaSleepState::Awake
+                || update_timer.is_some_and(|update_timer| !update_timer.is_update_frame())
+            {
                 return;
             }
-            TnuaToggle::Enabled => {}
-        }
-        if motor.lin.boost.is_finite() {
-            linare_velocity.0 += motor.lin.boost.truncate();
-        }
-        if motor.lin.acceleration.is_finite() {
-            external_force.set_force(motor.lin.acceleration.truncate() * mass.value());
-        }
-        if motor.ang.boost.is_finite() {
-            angular_velocity.0 += motor.ang.boost.z;
-        }
-        if motor.ang.acceleration.is_finite() {
-            external_torque.set_torque(
-                // NOTE: I did not actually verify that this is the correct formula. Nothing uses
-                // angular acceleration yet - only angular impulses.
-                inertia.value() * motor.ang.acceleration.z,
-            );
-        }
-    }
+            if motor.lin.boost.is_finite() {
+                linare_velocity.0 += motor.lin.boost.truncate();
+            }
+            if motor.lin.acceleration.is_finite() {
+                external_force.set_force(motor.lin.acceleration.truncate() * mass.value());
+            }
+            if motor.ang.boost.is_finite() {
+                angular_velocity.0 += motor.ang.boost.z;
+            }
+            if motor.ang.acceleration.is_finite() {
+                external_torque.set_torque(
+                    // NOTE: I did not actually verify that this is the correct formula. Nothing uses
+                    // angular acceleration yet - only angular impulses.
+                    inertia.value() * motor.ang.acceleration.z,
+                );
+            }
+        },
+    );
 }
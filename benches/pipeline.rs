@@ -0,0 +1,150 @@
+//! Measures the time the Tnua pipeline stages take for different numbers of characters.
+//!
+//! Run with `cargo bench --bench pipeline`. The app is headless and its time is advanced manually,
+//! so the results only depend on the machine and not on the frame rate.
+
+use std::time::{Duration, Instant};
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use avian3d::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use bevy_tnua::prelude::*;
+use bevy_tnua::{TnuaPipelineStages, TnuaSystemSet};
+use bevy_tnua_avian3d::*;
+
+const CHARACTER_COUNTS: &[usize] = &[10, 100, 1000];
+const WARMUP_FRAMES: u32 = 120;
+
+const STAGES: [&str; 3] = ["Sensors", "Logic", "Motors"];
+
+#[derive(Resource)]
+struct StageClock {
+    last: Instant,
+    totals: [Duration; STAGES.len()],
+}
+
+impl StageClock {
+    fn lap(&mut self, stage: Option<usize>) {
+        let now = Instant::now();
+        if let Some(stage) = stage {
+            self.totals[stage] += now - self.last;
+        }
+        self.last = now;
+    }
+}
+
+fn bench_pipeline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pipeline");
+    // A single frame with many characters is slow, so the default 100 samples would take too long.
+    group.sample_size(10);
+    for &character_count in CHARACTER_COUNTS {
+        let mut app = create_app(character_count);
+        for (stage_index, stage) in STAGES.iter().enumerate() {
+            group.bench_function(BenchmarkId::new(*stage, character_count), |b| {
+                // Each iteration is a single frame, and only the time of the stage is measured.
+                b.iter_custom(|iters| {
+                    app.world_mut().resource_mut::<StageClock>().totals = Default::default();
+                    for _ in 0..iters {
+                        app.update();
+                    }
+                    app.world().resource::<StageClock>().totals[stage_index]
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_pipeline);
+criterion_main!(benches);
+
+fn create_app(character_count: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins.set(ScheduleRunnerPlugin::run_once()),
+        TransformPlugin,
+        HierarchyPlugin,
+        // Avian only needs these when Bevy's `bevy_render` and `bevy_scene` features are enabled -
+        // which is the case here because the examples need them.
+        AssetPlugin::default(),
+        bevy::render::mesh::MeshPlugin,
+        bevy::scene::ScenePlugin,
+        PhysicsPlugins::default(),
+        TnuaControllerPlugin::new(FixedUpdate),
+        TnuaAvian3dPlugin::new(FixedUpdate),
+    ));
+    // One fixed update per frame.
+    app.insert_resource(Time::<Fixed>::from_hz(60.0));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / 60.0,
+    )));
+    app.insert_resource(StageClock {
+        last: Instant::now(),
+        totals: Default::default(),
+    });
+    app.add_systems(
+        FixedUpdate,
+        (
+            (|mut clock: ResMut<StageClock>| clock.lap(None))
+                .in_set(TnuaSystemSet)
+                .before(TnuaPipelineStages::Sensors),
+            (|mut clock: ResMut<StageClock>| clock.lap(Some(0)))
+                .after(TnuaPipelineStages::Sensors)
+                .before(TnuaPipelineStages::SubservientSensors),
+            // The user controls are not measured.
+            (|mut clock: ResMut<StageClock>| clock.lap(None))
+                .after(TnuaUserControlsSystemSet)
+                .before(TnuaPipelineStages::Logic),
+            (|mut clock: ResMut<StageClock>| clock.lap(Some(1)))
+                .after(TnuaPipelineStages::Logic)
+                .before(TnuaPipelineStages::Motors),
+            (|mut clock: ResMut<StageClock>| clock.lap(Some(2)))
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Motors),
+        ),
+    );
+    app.add_systems(
+        FixedUpdate,
+        apply_controls.in_set(TnuaUserControlsSystemSet),
+    );
+
+    let world = app.world_mut();
+    world.spawn((RigidBody::Static, Collider::half_space(Vec3::Y)));
+    let columns = (character_count as f32).sqrt().ceil() as usize;
+    for i in 0..character_count {
+        world.spawn((
+            Transform::from_xyz((i % columns) as f32 * 3.0, 2.0, (i / columns) as f32 * 3.0),
+            RigidBody::Dynamic,
+            Collider::capsule(0.5, 1.0),
+            TnuaController::default(),
+            TnuaAvian3dSensorShape(Collider::cylinder(0.49, 0.0)),
+            LockedAxes::ROTATION_LOCKED,
+        ));
+    }
+
+    // Let the characters land before measuring.
+    for _ in 0..WARMUP_FRAMES {
+        app.update();
+    }
+    app
+}
+
+// All the characters walk back and forth together, so that they do not collide with each other.
+fn apply_controls(time: Res<Time>, mut query: Query<&mut TnuaController>) {
+    let direction = if time.elapsed_secs() % 4.0 < 2.0 {
+        Vec3::X
+    } else {
+        Vec3::NEG_X
+    };
+    for mut controller in query.iter_mut() {
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: direction * 2.0,
+            float_height: 1.5,
+            ..Default::default()
+        });
+    }
+}
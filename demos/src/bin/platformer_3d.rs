@@ -25,7 +25,7 @@ use tnua_demos_crate::character_animating_systems::platformer_animating_systems:
 #[cfg(feature = "egui")]
 use tnua_demos_crate::character_control_systems::info_dumpeing_systems::character_control_info_dumping_system;
 use tnua_demos_crate::character_control_systems::platformer_control_systems::{
-    apply_platformer_controls, CharacterMotionConfigForPlatformerDemo, FallingThroughControlScheme,
+    apply_platformer_controls, CharacterMotionConfigForPlatformerDemo,
 };
 use tnua_demos_crate::character_control_systems::Dimensionality;
 use tnua_demos_crate::level_mechanics::LevelMechanicsPlugin;
@@ -209,7 +209,7 @@ fn setup_player(mut commands: Commands, asset_server: Res<AssetServer>) {
         dash_distance: 10.0,
         dash: Default::default(),
         one_way_platforms_min_proximity: 1.0,
-        falling_through: FallingThroughControlScheme::SingleFall,
+        falling_through: Default::default(),
         knockback: Default::default(),
     });
 
@@ -1,7 +1,7 @@
 pub mod info_dumpeing_systems;
 pub mod platformer_control_systems;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Dimensionality {
     Dim2,
     Dim3,
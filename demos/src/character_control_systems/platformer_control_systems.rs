@@ -5,13 +5,19 @@ use bevy_tnua::builtins::{
     TnuaBuiltinCrouch, TnuaBuiltinCrouchState, TnuaBuiltinDash, TnuaBuiltinKnockback,
 };
 use bevy_tnua::control_helpers::{
-    TnuaCrouchEnforcer, TnuaSimpleAirActionsCounter, TnuaSimpleFallThroughPlatformsHelper,
+    TnuaCrouchEnforcer, TnuaFallThroughControlScheme, TnuaSimpleAirActionsCounter,
+    TnuaSimpleFallThroughPlatformsHelper,
 };
-use bevy_tnua::math::{AdjustPrecision, AsF32, Float, Vector3};
+use bevy_tnua::math::{AdjustPrecision, AsF32, Float, Vector2, Vector3};
 use bevy_tnua::prelude::*;
 use bevy_tnua::{TnuaGhostSensor, TnuaProximitySensor};
+use serde::{Deserialize, Serialize};
 
+use crate::ui::touch_controls::DemoTouchControls;
 use crate::ui::tuning::UiTunable;
+#[cfg(feature = "egui")]
+use crate::ui::tuning::{reset_button, slider};
+use crate::ui::DemoUiGamepadSettings;
 
 use super::Dimensionality;
 
@@ -20,6 +26,9 @@ use super::Dimensionality;
 pub fn apply_platformer_controls(
     #[cfg(feature = "egui")] mut egui_context: EguiContexts,
     keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    gamepad_settings: Res<DemoUiGamepadSettings>,
+    touch_controls: Res<DemoTouchControls>,
     mut query: Query<(
         &CharacterMotionConfigForPlatformerDemo,
         // This is the main component used for interacting with Tnua. It is used for both issuing
@@ -74,8 +83,8 @@ pub fn apply_platformer_controls(
         forward_from_camera,
     ) in query.iter_mut()
     {
-        // This part is just keyboard input processing. In a real game this would probably be done
-        // with a third party plugin.
+        // This part is just keyboard, gamepad and touch input processing. In a real game this would
+        // probably be done with a third party plugin.
         let mut direction = Vector3::ZERO;
 
         if config.dimensionality == Dimensionality::Dim3 {
@@ -93,6 +102,21 @@ pub fn apply_platformer_controls(
             direction += Vector3::X;
         }
 
+        // The stick's deflection (after the dead zone) is used as is, so that a partially
+        // deflected stick moves the character at a fraction of its full speed. The virtual stick
+        // of the touch controls is treated like a gamepad's stick.
+        let stick = gamepads
+            .iter()
+            .map(|gamepad| gamepad.left_stick().adjust_precision())
+            .chain([touch_controls.stick])
+            .map(|stick| gamepad_settings.apply_dead_zone(stick))
+            .find(|stick| *stick != Vector2::ZERO)
+            .unwrap_or_default();
+        direction.x += stick.x;
+        if config.dimensionality == Dimensionality::Dim3 {
+            direction.z -= stick.y;
+        }
+
         direction = direction.clamp_length_max(1.0);
 
         if let Some(forward_from_camera) = forward_from_camera {
@@ -102,16 +126,24 @@ pub fn apply_platformer_controls(
                 .adjust_precision();
         }
 
+        let gamepad_pressed =
+            |button: GamepadButton| gamepads.iter().any(|gamepad| gamepad.pressed(button));
+        let gamepad_just_pressed =
+            |button: GamepadButton| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+
         let jump = match config.dimensionality {
             Dimensionality::Dim2 => {
                 keyboard.any_pressed([KeyCode::Space, KeyCode::ArrowUp, KeyCode::KeyW])
             }
             Dimensionality::Dim3 => keyboard.any_pressed([KeyCode::Space]),
-        };
-        let dash = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        } || gamepad_pressed(GamepadButton::South)
+            || touch_controls.jump;
+        let dash = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+            || gamepad_pressed(GamepadButton::RightTrigger);
 
         let turn_in_place = forward_from_camera.is_none()
-            && keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+            && (keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+                || gamepad_pressed(GamepadButton::LeftTrigger));
 
         let crouch_pressed: bool;
         let crouch_just_pressed: bool;
@@ -132,6 +164,11 @@ pub fn apply_platformer_controls(
                 crouch_just_pressed = keyboard.any_just_pressed(crouch_buttons);
             }
         }
+        let crouch_pressed =
+            crouch_pressed || gamepad_pressed(GamepadButton::East) || touch_controls.crouch;
+        let crouch_just_pressed = crouch_just_pressed
+            || gamepad_just_pressed(GamepadButton::East)
+            || touch_controls.crouch_just_pressed;
 
         // This needs to be called once per frame. It lets the air actions counter know about the
         // air status of the character. Specifically:
@@ -141,137 +178,28 @@ pub fn apply_platformer_controls(
         // * Is any air action currently ongoing?
         air_actions_counter.update(controller.as_mut());
 
-        // Here we will handle one-way platforms. It looks long and complex, but it's actual
-        // several schemes with observable changes in behavior, and each implementation is rather
-        // short and simple.
-        let crouch;
-        match config.falling_through {
-            // With this scheme, the player cannot make their character fall through by pressing
-            // the crouch button - the platforms are jump-through only.
-            FallingThroughControlScheme::JumpThroughOnly => {
-                crouch = crouch_pressed;
-                // To achieve this, we simply take the first platform detected by the ghost sensor,
-                // and treat it like a "real" platform.
-                for ghost_platform in ghost_sensor.iter() {
-                    // Because the ghots platforms don't interact with the character through the
-                    // physics engine, and because the ray that detected them starts from the
-                    // center of the character, we usually want to only look at platforms that
-                    // are at least a certain distance lower than that - to limit the point from
-                    // which the character climbs when they collide with the platform.
-                    if config.one_way_platforms_min_proximity <= ghost_platform.proximity {
-                        // By overriding the sensor's output, we make it pretend the ghost platform
-                        // is a real one - which makes Tnua make the character stand on it even
-                        // though the physics engine will not consider them colliding with each
-                        // other.
-                        sensor.output = Some(ghost_platform.clone());
-                        break;
-                    }
-                }
-            }
-            // With this scheme, the player can drop down one-way platforms by pressing the crouch
-            // button. Because it does not use `TnuaSimpleFallThroughPlatformsHelper`, it has
-            // certain limitations:
-            //
-            // 1. If the player releases the crouch button before the character has passed a
-            //    certain distance it'll climb back up on the platform.
-            // 2. If a ghost platform is too close above another platform (either ghost or solid),
-            //    such that when the character floats above the lower platform the higher platform
-            //    is detected at above-minimal proximity, the character will climb up to the higher
-            //    platform - even after explicitly dropping down from it to the lower one.
-            //
-            // Both limitations are greatly affected by the min proximity, but setting it tightly
-            // to minimize them may cause the character to sometimes fall through a ghost platform
-            // without explicitly being told to. To properly overcome these limitations - use
-            // `TnuaSimpleFallThroughPlatformsHelper`.
-            FallingThroughControlScheme::WithoutHelper => {
-                // With this scheme we only care about the first ghost platform the ghost sensor
-                // finds with a proximity higher than the defined minimum. We either treat it as a
-                // real platform, or ignore it and any other platform the sensor has found.
-                let relevant_platform = ghost_sensor.iter().find(|ghost_platform| {
-                    config.one_way_platforms_min_proximity <= ghost_platform.proximity
-                });
-                if crouch_pressed {
-                    // If there is a ghost platform, it means the player wants to fall through it -
-                    // so we "cancel" the crouch, and we don't pass any ghots platform to the
-                    // proximity sensor (because we want to character to fall through)
-                    //
-                    // If there is no ghost platform, it means the character is standing on a real
-                    // platform - so we make it crouch. We don't pass any ghost platform to the
-                    // proximity sensor here either - because there aren't any.
-                    crouch = relevant_platform.is_none();
-                } else {
-                    crouch = false;
-                    if let Some(ghost_platform) = relevant_platform {
-                        // Ghost platforms can only be detected _before_ fully solid platforms, so
-                        // if we detect one we can safely replace the proximity sensor's output
-                        // with it.
-                        //
-                        // Do take care to only do this when there is a ghost platform though -
-                        // otherwise it could replace an actual solid platform detection with a
-                        // `None`.
-                        sensor.output = Some(ghost_platform.clone());
-                    }
-                }
-            }
-            // This scheme uses `TnuaSimpleFallThroughPlatformsHelper` to properly handle fall
-            // through:
-            //
-            // * Pressing the crouch button while standing on a ghost platform will make the
-            //   character fall through it.
-            // * Even if the button is released immediately, the character will not climb back up.
-            //   It'll continue the fall.
-            // * Even if the button is held and there is another ghost platform below, the
-            //   character will only drop one "layer" of ghost platforms.
-            // * If the player drops from a ghost platform to a platform too close to it - the
-            //   character will not climb back up. The player can still climb back up by jumping,
-            //   of course.
-            FallingThroughControlScheme::SingleFall => {
-                // The fall through helper is operated by creating an handler.
-                let mut handler = fall_through_helper.with(
-                    &mut sensor,
-                    ghost_sensor,
-                    config.one_way_platforms_min_proximity,
-                );
-                if crouch_pressed {
-                    // Use `try_falling` to fall through the first ghost platform. It'll return
-                    // `true` if there really was a ghost platform to fall through - in which case
-                    // we want to cancel the crouch. If there was no ghost platform to fall
-                    // through, it returns `false` - in which case we do want to crouch.
-                    //
-                    // The boolean argument to `try_falling` determines if the character should
-                    // fall through "new" ghost platforms. When the player have just pressed the
-                    // crouch button, we pass `true` so that the fall can begin. But in the
-                    // following frames we pass `false` so that if there are more ghost platforms
-                    // below the character will not fall through them.
-                    crouch = !handler.try_falling(crouch_just_pressed);
-                } else {
-                    crouch = false;
-                    // Use `dont_fall` to not fall. If there are platforms that the character
-                    // already stared falling through, it'll continue the fall through and not
-                    // climb back up (like it would with the `WithoutHelper` scheme). Otherwise, it
-                    // will just copy the first ghost platform (above the min proximity) from the
-                    // ghost sensor to the proximity sensor.
-                    handler.dont_fall();
-                }
-            }
-            // This scheme is similar to `SingleFall`, with the exception that as long as the
-            // crouch button is pressed the character will keep falling through ghost platforms.
-            FallingThroughControlScheme::KeepFalling => {
-                let mut handler = fall_through_helper.with(
-                    &mut sensor,
-                    ghost_sensor,
-                    config.one_way_platforms_min_proximity,
-                );
-                if crouch_pressed {
-                    // This is done by passing `true` to `try_falling`, allowing it to keep falling
-                    // through new platforms even if the button was not _just_ pressed.
-                    crouch = !handler.try_falling(true);
-                } else {
-                    crouch = false;
-                    handler.dont_fall();
-                }
-            }
-        };
+        // Here we will handle one-way platforms. `TnuaSimpleFallThroughPlatformsHelper` takes
+        // care of copying the ghost platforms detected by the ghost sensor to the proximity
+        // sensor, and of ignoring the ones the character is falling through. The scheme determines
+        // how the crouch button is used for falling through them:
+        //
+        // * `JumpThroughOnly` - the platforms can only be jumped through from below.
+        // * `SingleFall` - pressing the crouch button while standing on a ghost platform will make
+        //   the character fall through it. Even if the button is released immediately, the
+        //   character will not climb back up - and even if it is held, the character will only
+        //   drop through one "layer" of ghost platforms.
+        // * `KeepFalling` - as long as the crouch button is held, the character will keep falling
+        //   through ghost platforms.
+        let mut handler = fall_through_helper.with(
+            &mut sensor,
+            ghost_sensor,
+            config.one_way_platforms_min_proximity,
+        );
+        // `apply_scheme` returns `true` if there really was a ghost platform to fall through - in
+        // which case we want to cancel the crouch. Otherwise we crouch if the button is pressed.
+        let falling_through =
+            handler.apply_scheme(&config.falling_through, crouch_pressed, crouch_just_pressed);
+        let crouch = crouch_pressed && !falling_through;
 
         let speed_factor =
             // `TnuaController::concrete_action` can be used to determine if an action is currently
@@ -371,7 +299,7 @@ pub fn apply_platformer_controls(
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct CharacterMotionConfigForPlatformerDemo {
     pub dimensionality: Dimensionality,
     pub speed: Float,
@@ -382,70 +310,96 @@ pub struct CharacterMotionConfigForPlatformerDemo {
     pub dash_distance: Float,
     pub dash: TnuaBuiltinDash,
     pub one_way_platforms_min_proximity: Float,
-    pub falling_through: FallingThroughControlScheme,
+    pub falling_through: TnuaFallThroughControlScheme,
     pub knockback: TnuaBuiltinKnockback,
 }
 
 impl UiTunable for CharacterMotionConfigForPlatformerDemo {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui) {
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self) {
         ui.collapsing("Walking:", |ui| {
-            ui.add(egui::Slider::new(&mut self.speed, 0.0..=60.0).text("Speed"));
-            self.walk.tune(ui);
+            slider(ui, "Speed", &mut self.speed, 0.0..=60.0, defaults.speed);
+            self.walk.tune(ui, &defaults.walk);
         });
-        ui.add(egui::Slider::new(&mut self.actions_in_air, 0..=8).text("Max Actions in Air"));
+        slider(
+            ui,
+            "Max Actions in Air",
+            &mut self.actions_in_air,
+            0..=8,
+            defaults.actions_in_air,
+        );
         ui.collapsing("Jumping:", |ui| {
-            self.jump.tune(ui);
+            self.jump.tune(ui, &defaults.jump);
         });
         ui.collapsing("Dashing:", |ui| {
-            ui.add(egui::Slider::new(&mut self.dash_distance, 0.0..=40.0).text("Dash Distance"));
-            self.dash.tune(ui);
+            slider(
+                ui,
+                "Dash Distance",
+                &mut self.dash_distance,
+                0.0..=40.0,
+                defaults.dash_distance,
+            );
+            self.dash.tune(ui, &defaults.dash);
         });
         ui.collapsing("Crouching:", |ui| {
-            self.crouch.tune(ui);
+            self.crouch.tune(ui, &defaults.crouch);
         });
         ui.collapsing("One-way Platforms", |ui| {
-            ui.add(
-                egui::Slider::new(&mut self.one_way_platforms_min_proximity, 0.0..=2.0)
-                    .text("Min Proximity"),
+            slider(
+                ui,
+                "Min Proximity",
+                &mut self.one_way_platforms_min_proximity,
+                0.0..=2.0,
+                defaults.one_way_platforms_min_proximity,
             );
-            self.falling_through.tune(ui);
+            self.falling_through.tune(ui, &defaults.falling_through);
         });
         ui.collapsing("Knockback:", |ui| {
-            self.knockback.tune(ui);
+            self.knockback.tune(ui, &defaults.knockback);
         });
     }
 }
 
-#[derive(Component, Debug, PartialEq, Default)]
-pub enum FallingThroughControlScheme {
-    JumpThroughOnly,
-    WithoutHelper,
-    #[default]
-    SingleFall,
-    KeepFalling,
-}
-
-impl UiTunable for FallingThroughControlScheme {
+impl UiTunable for TnuaFallThroughControlScheme {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui) {
-        egui::ComboBox::from_label("Falling Through Control Scheme")
-            .selected_text(format!("{:?}", self))
-            .show_ui(ui, |ui| {
-                for variant in [
-                    FallingThroughControlScheme::JumpThroughOnly,
-                    FallingThroughControlScheme::WithoutHelper,
-                    FallingThroughControlScheme::SingleFall,
-                    FallingThroughControlScheme::KeepFalling,
-                ] {
-                    if ui
-                        .selectable_label(*self == variant, format!("{:?}", variant))
-                        .clicked()
-                    {
-                        *self = variant;
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self) {
+        let variant_name = |scheme: &TnuaFallThroughControlScheme| match scheme {
+            TnuaFallThroughControlScheme::JumpThroughOnly => "JumpThroughOnly",
+            TnuaFallThroughControlScheme::SingleFall { .. } => "SingleFall",
+            TnuaFallThroughControlScheme::KeepFalling => "KeepFalling",
+        };
+        ui.horizontal(|ui| {
+            reset_button(ui, self, defaults);
+            egui::ComboBox::from_label("Falling Through Control Scheme")
+                .selected_text(variant_name(self))
+                .show_ui(ui, |ui| {
+                    for variant in [
+                        TnuaFallThroughControlScheme::JumpThroughOnly,
+                        TnuaFallThroughControlScheme::default(),
+                        TnuaFallThroughControlScheme::KeepFalling,
+                    ] {
+                        if ui
+                            .selectable_label(
+                                variant_name(self) == variant_name(&variant),
+                                variant_name(&variant),
+                            )
+                            .clicked()
+                        {
+                            *self = variant;
+                        }
                     }
-                }
-            });
+                });
+        });
+        if let TnuaFallThroughControlScheme::SingleFall { max_platforms } = self {
+            let mut limited = max_platforms.is_some();
+            ui.checkbox(&mut limited, "Limit Platforms Per Fall");
+            if limited {
+                let max_platforms = max_platforms.get_or_insert(1);
+                ui.add(egui::Slider::new(max_platforms, 1..=4).text("Max Platforms Per Fall"));
+            } else {
+                *max_platforms = None;
+            }
+        }
     }
 }
 
@@ -2,6 +2,7 @@ use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 #[cfg(feature = "egui")]
 use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
 
 #[derive(Component, Default)]
 pub struct CommandAlteringSelectors(Vec<CommandAlteringSelector>);
@@ -87,6 +88,77 @@ impl CommandAlteringSelectors {
         }
     }
 
+    /// The current choices of the selectors, for saving them along with the tuned config.
+    pub fn saved_state(&self) -> Vec<SavedSelector> {
+        self.0
+            .iter()
+            .map(|selector| match selector {
+                CommandAlteringSelector::Combo {
+                    chosen,
+                    caption,
+                    options,
+                    set_to,
+                } => SavedSelector::Combo {
+                    caption: caption.clone(),
+                    chosen: options[set_to.unwrap_or(*chosen)].0.clone(),
+                },
+                CommandAlteringSelector::Checkbox {
+                    checked,
+                    caption,
+                    applier: _,
+                    set_to,
+                } => SavedSelector::Checkbox {
+                    caption: caption.clone(),
+                    checked: set_to.unwrap_or(*checked),
+                },
+            })
+            .collect()
+    }
+
+    /// Restore choices saved with [`saved_state`](Self::saved_state). Selectors are matched by
+    /// their captions, and the choices are applied on the next frame.
+    pub fn load_saved_state(&mut self, saved: &[SavedSelector]) {
+        for selector in self.0.iter_mut() {
+            match selector {
+                CommandAlteringSelector::Combo {
+                    chosen: _,
+                    caption,
+                    options,
+                    set_to,
+                } => {
+                    let saved_choice = saved.iter().find_map(|saved| match saved {
+                        SavedSelector::Combo {
+                            caption: saved_caption,
+                            chosen,
+                        } if saved_caption == caption => Some(chosen),
+                        _ => None,
+                    });
+                    if let Some(idx) = saved_choice
+                        .and_then(|chosen| options.iter().position(|(name, _)| name == chosen))
+                    {
+                        *set_to = Some(idx);
+                    }
+                }
+                CommandAlteringSelector::Checkbox {
+                    checked: _,
+                    caption,
+                    applier: _,
+                    set_to,
+                } => {
+                    if let Some(checked) = saved.iter().find_map(|saved| match saved {
+                        SavedSelector::Checkbox {
+                            caption: saved_caption,
+                            checked,
+                        } if saved_caption == caption => Some(*checked),
+                        _ => None,
+                    }) {
+                        *set_to = Some(checked);
+                    }
+                }
+            }
+        }
+    }
+
     #[cfg(feature = "egui")]
     pub fn show_ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, entity: Entity) {
         for selector in self.0.iter_mut() {
@@ -124,3 +196,9 @@ impl CommandAlteringSelectors {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum SavedSelector {
+    Combo { caption: String, chosen: String },
+    Checkbox { caption: String, checked: bool },
+}
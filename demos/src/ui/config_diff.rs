@@ -0,0 +1,82 @@
+use bevy::utils::HashMap;
+use bevy_egui::egui;
+use serde::Serialize;
+
+/// Show the fields of the config that differ from the defaults, with the default value shown
+/// next to the current one.
+pub fn show_config_diff<C: Serialize>(ui: &mut egui::Ui, config: &C, defaults: &C) {
+    let defaults = flatten_config(defaults)
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let changed = flatten_config(config)
+        .into_iter()
+        .filter(|(path, value)| defaults.get(path) != Some(value))
+        .collect::<Vec<_>>();
+    let header = egui::CollapsingHeader::new(format!("Changed from default ({})", changed.len()));
+    // The count in the caption changes, so the ID must not depend on it.
+    header.id_salt("config-diff").show(ui, |ui| {
+        if changed.is_empty() {
+            ui.label("Nothing changed");
+            return;
+        }
+        egui::Grid::new("config-diff").striped(true).show(ui, |ui| {
+            ui.strong("Field");
+            ui.strong("Current");
+            ui.strong("Default");
+            ui.end_row();
+            for (path, value) in changed.iter() {
+                ui.label(path);
+                ui.colored_label(egui::Color32::YELLOW, value);
+                ui.label(defaults.get(path).map(String::as_str).unwrap_or("-"));
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// Flatten a config into `(path, value)` pairs, one for each leaf field, by reading its pretty
+/// printed RON line by line.
+fn flatten_config<C: Serialize>(config: &C) -> Vec<(String, String)> {
+    let Ok(ron) = ron::ser::to_string_pretty(config, ron::ser::PrettyConfig::default()) else {
+        return Vec::new();
+    };
+    let mut result = Vec::new();
+    // Each nested struct, tuple or sequence gets a level with its name and the index of its next
+    // unnamed item.
+    let mut levels: Vec<(String, usize)> = Vec::new();
+    for line in ron.lines() {
+        let line = line.trim().trim_end_matches(',');
+        if line.starts_with([')', ']', '}']) {
+            levels.pop();
+            continue;
+        }
+        let (key, value) = match line.split_once(": ") {
+            Some((key, value)) if key.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                (key.to_owned(), value)
+            }
+            _ => {
+                let index = levels.last_mut().map(|(_, next_index)| {
+                    *next_index += 1;
+                    *next_index - 1
+                });
+                (
+                    index.map(|index| index.to_string()).unwrap_or_default(),
+                    line,
+                )
+            }
+        };
+        if value.ends_with(['(', '[', '{']) {
+            levels.push((key, 0));
+        } else {
+            let path = levels
+                .iter()
+                .skip(1)
+                .map(|(name, _)| name.as_str())
+                .chain([key.as_str()])
+                .collect::<Vec<_>>()
+                .join(".");
+            result.push((path, value.to_owned()));
+        }
+    }
+    result
+}
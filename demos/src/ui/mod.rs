@@ -1,11 +1,19 @@
 pub mod component_alterbation;
 #[cfg(feature = "egui")]
+mod config_diff;
+#[cfg(feature = "egui")]
 mod framerate;
 pub mod info;
 mod level_selection;
 #[cfg(feature = "egui")]
 pub mod plotting;
+#[cfg(feature = "egui")]
+pub mod rust_literal;
+pub mod saved_config;
+pub mod touch_controls;
 pub mod tuning;
+#[cfg(feature = "egui")]
+pub mod tuning_history;
 
 use std::marker::PhantomData;
 
@@ -24,6 +32,8 @@ use self::component_alterbation::CommandAlteringSelectors;
 #[cfg(feature = "egui")]
 use self::plotting::{make_update_plot_data_system, plot_source_rolling_update};
 
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use tuning::UiTunable;
 
 #[derive(SystemSet, Clone, PartialEq, Eq, Debug, Hash)]
@@ -43,7 +53,7 @@ impl<C: Component + UiTunable> Default for DemoUi<C> {
 
 const GRAVITY_MAGNITUDE: Float = 9.81;
 
-impl<C: Component + UiTunable> Plugin for DemoUi<C> {
+impl<C: Component + UiTunable + Clone + Serialize + DeserializeOwned> Plugin for DemoUi<C> {
     fn build(&self, app: &mut App) {
         #[cfg(feature = "egui")]
         app.add_plugins(EguiPlugin);
@@ -51,12 +61,16 @@ impl<C: Component + UiTunable> Plugin for DemoUi<C> {
             active: true,
             gravity: Vector3::NEG_Y * GRAVITY_MAGNITUDE,
         });
+        app.init_resource::<DemoUiGamepadSettings>();
+        app.add_plugins(touch_controls::DemoTouchControlsPlugin);
         app.configure_sets(
             Update,
             DemoInfoUpdateSystemSet.after(bevy_tnua::TnuaUserControlsSystemSet),
         );
         app.add_systems(Update, apply_selectors);
         #[cfg(feature = "egui")]
+        app.init_resource::<DemoUiCharacterWindows>();
+        #[cfg(feature = "egui")]
         app.add_systems(Update, ui_system::<C>.after(DemoInfoUpdateSystemSet));
         #[cfg(feature = "egui")]
         app.add_systems(Update, plot_source_rolling_update);
@@ -109,9 +123,45 @@ pub struct DemoUiPhysicsBackendSettings {
     pub gravity: Vector3,
 }
 
+// NOTE: The control systems are responsible for applying the dead zone
+#[derive(Resource)]
+pub struct DemoUiGamepadSettings {
+    /// Stick deflections below this are ignored. Deflections above it are rescaled so that the
+    /// movement starts from zero speed at the edge of the dead zone.
+    pub dead_zone: Float,
+}
+
+impl Default for DemoUiGamepadSettings {
+    fn default() -> Self {
+        Self { dead_zone: 0.15 }
+    }
+}
+
+impl DemoUiGamepadSettings {
+    /// Apply a radial dead zone to a stick input, keeping its direction and mapping its
+    /// magnitude to the `0.0..=1.0` range.
+    pub fn apply_dead_zone(&self, stick: Vector2) -> Vector2 {
+        let magnitude = stick.length();
+        if magnitude <= self.dead_zone {
+            return Vector2::ZERO;
+        }
+        let rescaled = ((magnitude - self.dead_zone) / (1.0 - self.dead_zone)).min(1.0);
+        stick * (rescaled / magnitude)
+    }
+}
+
 #[derive(Component)]
 pub struct TrackedEntity(pub String);
 
+#[cfg(feature = "egui")]
+#[derive(Resource, Default)]
+struct DemoUiCharacterWindows {
+    /// The character shown in the main window. `None` shows all the characters.
+    selected: Option<Entity>,
+    /// Characters shown in their own windows instead of in the main window.
+    detached: bevy::utils::HashSet<Entity>,
+}
+
 fn apply_selectors(
     mut query: Query<(Entity, &mut CommandAlteringSelectors)>,
     mut commands: Commands,
@@ -123,13 +173,20 @@ fn apply_selectors(
 
 #[cfg(feature = "egui")]
 #[allow(clippy::type_complexity)]
-fn ui_system<C: Component + UiTunable>(
+#[allow(clippy::too_many_arguments)]
+fn ui_system<C: Component + UiTunable + Clone + Serialize + DeserializeOwned>(
     mut egui_context: EguiContexts,
+    mut character_windows: ResMut<DemoUiCharacterWindows>,
+    // Starts with the configs the characters had when the UI first showed them, for resetting the
+    // tuning.
+    mut tunings: Local<bevy::utils::HashMap<Entity, tuning_history::CharacterTuning<C>>>,
     mut physics_backend_settings: ResMut<DemoUiPhysicsBackendSettings>,
+    mut gamepad_settings: ResMut<DemoUiGamepadSettings>,
+    mut touch_controls: ResMut<touch_controls::DemoTouchControls>,
     mut query: Query<(
         Entity,
         &TrackedEntity,
-        Option<&plotting::PlotSource>,
+        Option<&mut plotting::PlotSource>,
         Option<&mut info::InfoSource>,
         &mut TnuaToggle,
         Option<&mut C>,
@@ -143,8 +200,6 @@ fn ui_system<C: Component + UiTunable>(
         crate::app_setup_options::AppSetupConfiguration,
     >,
 ) {
-    use std::any::TypeId;
-
     let Ok(mut primary_window) = primary_window_query.get_single_mut() else {
         return;
     };
@@ -155,6 +210,13 @@ fn ui_system<C: Component + UiTunable>(
             .movable(false)
             .resizable(false);
     }
+    let characters: Vec<(Entity, String, bool)> = query
+        .iter()
+        .map(|(entity, TrackedEntity(name), _, _, _, tunable, _)| {
+            (entity, name.clone(), tunable.is_some())
+        })
+        .collect();
+    let mut copy_request = None;
     egui_window.show(egui_context.ctx_mut(), |ui| {
         //if let Some(window) = web_sys::window() {
             //ui.label(format!("URL {:?}", window.location().search()));
@@ -189,7 +251,15 @@ fn ui_system<C: Component + UiTunable>(
                 ui.label("Crouch or fall through pink platforms with Ctrl (Also with the down arrow key in 2D)");
                 ui.label("Turn in place with Alt (only in 3D)");
                 ui.label("Dash with Shift (while moving in a direction)");
+                ui.label("With a gamepad: move with the left stick, jump with South, crouch with East, dash with the right trigger and turn in place with the left trigger");
+                ui.label("With a touch screen: move with the virtual stick and use the on-screen buttons to jump and crouch");
             });
+        ui.collapsing("Gamepad", |ui| {
+            ui.add(egui::Slider::new(&mut gamepad_settings.dead_zone, 0.0..=0.9).text("Stick Dead Zone"));
+        });
+        ui.collapsing("Touch Controls", |ui| {
+            ui.checkbox(&mut touch_controls.enabled, "Show Touch Controls (turns on when the screen is touched)");
+        });
         level_selection.show_in_ui(ui);
         ui.collapsing("Physics Backend", |ui| {
             ui.checkbox(&mut physics_backend_settings.active, "Physics Enabled");
@@ -204,99 +274,309 @@ fn ui_system<C: Component + UiTunable>(
                 }
             });
         });
+        if 1 < characters.len() {
+            let selected_name = character_windows
+                .selected
+                .and_then(|selected| characters.iter().find(|(entity, ..)| *entity == selected))
+                .map_or("All", |(_, name, _)| name.as_str());
+            egui::ComboBox::from_label("Character")
+                .selected_text(selected_name)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut character_windows.selected, None, "All");
+                    for (entity, name, _) in characters.iter() {
+                        ui.selectable_value(&mut character_windows.selected, Some(*entity), name);
+                    }
+                });
+        }
         for (
             entity,
             TrackedEntity(name),
             plot_source,
-            mut info_source,
-            mut tnua_toggle,
-            mut tunable,
+            info_source,
+            tnua_toggle,
+            tunable,
             command_altering_selectors,
         ) in query.iter_mut()
         {
-            let collapse_state = egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), ui.make_persistent_id(("for-character", entity)), false);
-
-            #[derive(Clone, Copy, PartialEq, Default, Debug)]
-            enum ThingToShow {
-                #[default]
-                Settings,
-                Plots,
-                Info,
+            if character_windows.detached.contains(&entity) {
+                continue;
             }
-
-            let thing_to_show_id = ui.make_persistent_id((TypeId::of::<ThingToShow>(), entity));
-            let is_open = collapse_state.is_open();
-            let mut thing_to_show = ui.memory_mut(|mem| *mem.data.get_temp_mut_or_default::<ThingToShow>(thing_to_show_id));
-            let mut set_open = None;
-
-            let mut collapse_state = collapse_state.show_header(ui, |ui| {
-                ui.label(name);
-                for (possible, option, text) in [
-                    (true, ThingToShow::Settings, "settings"),
-                    (plot_source.is_some(), ThingToShow::Plots, "plots"),
-                    (info_source.is_some(), ThingToShow::Info, "info"),
-                ] {
-                    let mut selected = is_open && option == thing_to_show;
-                    ui.add_enabled_ui(possible, |ui| {
-                        if ui.toggle_value(&mut selected, text).changed() {
-                            set_open = Some(selected);
-                            if selected {
-                                thing_to_show = option;
-                                ui.memory_mut(|mem| *mem.data.get_temp_mut_or_default::<ThingToShow>(thing_to_show_id) = option);
-                            }
-                        }
-                    });
+            if character_windows.selected.is_some_and(|selected| selected != entity) {
+                if let Some(mut info_source) = info_source {
+                    info_source.set_active(false);
                 }
+                continue;
+            }
+            let mut detach = false;
+            character_ui(
+                ui,
+                entity,
+                name,
+                plot_source,
+                info_source,
+                tnua_toggle,
+                tunable,
+                command_altering_selectors,
+                &mut commands,
+                &characters,
+                &mut copy_request,
+                &mut tunings,
+                Some(&mut detach),
+            );
+            if detach {
+                character_windows.detached.insert(entity);
+            }
+        }
+    });
+    for (
+        entity,
+        TrackedEntity(name),
+        plot_source,
+        info_source,
+        tnua_toggle,
+        tunable,
+        command_altering_selectors,
+    ) in query.iter_mut()
+    {
+        if !character_windows.detached.contains(&entity) {
+            continue;
+        }
+        let mut open = true;
+        egui::Window::new(name.as_str())
+            .id(egui::Id::new(("character-window", entity)))
+            .open(&mut open)
+            .interactable(primary_window.cursor_options.visible)
+            .show(egui_context.ctx_mut(), |ui| {
+                character_ui(
+                    ui,
+                    entity,
+                    name,
+                    plot_source,
+                    info_source,
+                    tnua_toggle,
+                    tunable,
+                    command_altering_selectors,
+                    &mut commands,
+                    &characters,
+                    &mut copy_request,
+                    &mut tunings,
+                    None,
+                );
             });
-            if let Some(set_open) = set_open {
-                collapse_state.set_open(set_open);
+        if !open {
+            character_windows.detached.remove(&entity);
+        }
+    }
+    if let Some((source, target)) = copy_request {
+        if let Ok((.., Some(config), _)) = query.get(source) {
+            let config = config.clone();
+            if let Ok((.., Some(mut target_config), _)) = query.get_mut(target) {
+                *target_config = config;
             }
+        }
+    }
+}
 
-            if let Some(info_source) = info_source.as_mut() {
-                info_source.set_active(collapse_state.is_open() && thing_to_show == ThingToShow::Info);
+#[cfg(feature = "egui")]
+#[allow(clippy::too_many_arguments)]
+fn character_ui<C: Component + UiTunable + Clone + Serialize + DeserializeOwned>(
+    ui: &mut egui::Ui,
+    entity: Entity,
+    name: &str,
+    plot_source: Option<Mut<plotting::PlotSource>>,
+    mut info_source: Option<Mut<info::InfoSource>>,
+    mut tnua_toggle: Mut<TnuaToggle>,
+    mut tunable: Option<Mut<C>>,
+    mut command_altering_selectors: Option<Mut<CommandAlteringSelectors>>,
+    commands: &mut Commands,
+    characters: &[(Entity, String, bool)],
+    copy_request: &mut Option<(Entity, Entity)>,
+    tunings: &mut bevy::utils::HashMap<Entity, tuning_history::CharacterTuning<C>>,
+    detach: Option<&mut bool>,
+) {
+    use std::any::TypeId;
+
+    let collapse_state = egui::collapsing_header::CollapsingState::load_with_default_open(
+        ui.ctx(),
+        ui.make_persistent_id(("for-character", entity)),
+        false,
+    );
+
+    #[derive(Clone, Copy, PartialEq, Default, Debug)]
+    enum ThingToShow {
+        #[default]
+        Settings,
+        Plots,
+        Info,
+    }
+
+    let thing_to_show_id = ui.make_persistent_id((TypeId::of::<ThingToShow>(), entity));
+    let is_open = collapse_state.is_open();
+    let mut thing_to_show = ui.memory_mut(|mem| {
+        *mem.data
+            .get_temp_mut_or_default::<ThingToShow>(thing_to_show_id)
+    });
+    let mut set_open = None;
+
+    let mut collapse_state = collapse_state.show_header(ui, |ui| {
+        ui.label(name);
+        for (possible, option, text) in [
+            (true, ThingToShow::Settings, "settings"),
+            (plot_source.is_some(), ThingToShow::Plots, "plots"),
+            (info_source.is_some(), ThingToShow::Info, "info"),
+        ] {
+            let mut selected = is_open && option == thing_to_show;
+            ui.add_enabled_ui(possible, |ui| {
+                if ui.toggle_value(&mut selected, text).changed() {
+                    set_open = Some(selected);
+                    if selected {
+                        thing_to_show = option;
+                        ui.memory_mut(|mem| {
+                            *mem.data
+                                .get_temp_mut_or_default::<ThingToShow>(thing_to_show_id) = option
+                        });
+                    }
+                }
+            });
+        }
+        if let Some(detach) = detach {
+            if ui
+                .button("window")
+                .on_hover_text("Show in a separate window")
+                .clicked()
+            {
+                *detach = true;
             }
+        }
+    });
+    if let Some(set_open) = set_open {
+        collapse_state.set_open(set_open);
+    }
 
-            collapse_state.body(|ui| {
-                match thing_to_show {
-                    ThingToShow::Settings => {
-                        egui::ComboBox::from_label("Toggle Tnua")
-                            .selected_text(format!("{:?}", tnua_toggle.as_ref()))
-                            .show_ui(ui, |ui| {
-                                for option in [
-                                    TnuaToggle::Disabled,
-                                    TnuaToggle::SenseOnly,
-                                    TnuaToggle::Enabled,
-                                ] {
-                                    let label = format!("{:?}", option);
-                                    ui.selectable_value(tnua_toggle.as_mut(), option, label);
-                                }
-                            });
+    if let Some(info_source) = info_source.as_mut() {
+        info_source.set_active(collapse_state.is_open() && thing_to_show == ThingToShow::Info);
+    }
 
-                        if let Some(tunable) = tunable.as_mut() {
-                            tunable.tune(ui);
-                        }
+    collapse_state.body(|ui| match thing_to_show {
+        ThingToShow::Settings => {
+            egui::ComboBox::from_label("Toggle Tnua")
+                .selected_text(format!("{:?}", tnua_toggle.as_ref()))
+                .show_ui(ui, |ui| {
+                    for option in [
+                        TnuaToggle::Disabled,
+                        TnuaToggle::SuspendedKeepState,
+                        TnuaToggle::SenseOnly,
+                        TnuaToggle::Enabled,
+                    ] {
+                        let label = format!("{:?}", option);
+                        ui.selectable_value(tnua_toggle.as_mut(), option, label);
+                    }
+                });
 
-                        if let Some(mut command_altering_selectors) = command_altering_selectors
-                        {
-                            command_altering_selectors.show_ui(ui, &mut commands, entity);
+            if let Some(tunable) = tunable.as_mut() {
+                let tuning = tunings
+                    .entry(entity)
+                    .or_insert_with(|| tuning_history::CharacterTuning::new(C::clone(tunable)));
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(tuning.can_undo(), egui::Button::new("Undo"))
+                        .clicked()
+                    {
+                        tuning.undo(tunable);
+                    }
+                    if ui
+                        .add_enabled(tuning.can_redo(), egui::Button::new("Redo"))
+                        .clicked()
+                    {
+                        tuning.redo(tunable);
+                    }
+                });
+                let before = C::clone(tunable);
+                let sources = characters
+                    .iter()
+                    .filter(|(other, _, has_tunable)| *other != entity && *has_tunable)
+                    .collect::<Vec<_>>();
+                if !sources.is_empty() {
+                    ui.menu_button("Copy settings from...", |ui| {
+                        for (source, source_name, _) in sources {
+                            if ui.button(source_name).clicked() {
+                                *copy_request = Some((*source, entity));
+                                ui.close_menu();
+                            }
                         }
+                    });
+                }
+                if ui
+                    .button("Copy as Rust Code")
+                    .on_hover_text("Copy the config to the clipboard as a Rust struct literal")
+                    .clicked()
+                {
+                    match rust_literal::to_rust_literal(&**tunable) {
+                        Ok(code) => ui.ctx().copy_text(code),
+                        Err(err) => error!("Failed formatting config as Rust code: {err}"),
                     }
-                    ThingToShow::Plots => {
-                        if let Some(plot_source) = plot_source {
-                            plot_source.show(entity, ui);
-                        } else {
-                            ui.colored_label(egui::Color32::DARK_RED, "No plotting configured for this entity");
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    let path = format!("tnua-config-{name}.ron");
+                    if ui.button("Save to RON").on_hover_text(&path).clicked() {
+                        let saved = saved_config::SavedCharacterConfig {
+                            config: C::clone(tunable),
+                            selectors: command_altering_selectors
+                                .as_ref()
+                                .map(|selectors| selectors.saved_state())
+                                .unwrap_or_default(),
+                        };
+                        match saved.save(&path) {
+                            Ok(()) => info!("Saved config to {path}"),
+                            Err(err) => error!("Failed saving config to {path}: {err}"),
                         }
                     }
-                    ThingToShow::Info => {
-                        if let Some(info_source) = info_source.as_mut() {
-                            info_source.show(entity, ui);
-                        } else {
-                            ui.colored_label(egui::Color32::DARK_RED, "No info configured for this entity");
+                    if ui.button("Load from RON").on_hover_text(&path).clicked() {
+                        match saved_config::SavedCharacterConfig::<C>::load(&path) {
+                            Ok(saved) => {
+                                **tunable = saved.config;
+                                if let Some(selectors) = command_altering_selectors.as_mut() {
+                                    selectors.load_saved_state(&saved.selectors);
+                                }
+                                info!("Loaded config from {path}");
+                            }
+                            Err(err) => error!("Failed loading config from {path}: {err}"),
                         }
                     }
+                });
+                if ui.button("Reset All to Defaults").clicked() {
+                    **tunable = tuning.defaults().clone();
                 }
-            });
+                config_diff::show_config_diff(ui, &**tunable, tuning.defaults());
+                tunable.tune(ui, tuning.defaults());
+                let pointer_down = ui.input(|input| input.pointer.any_down());
+                tuning.track(before, tunable, pointer_down);
+            }
+
+            if let Some(mut command_altering_selectors) = command_altering_selectors {
+                command_altering_selectors.show_ui(ui, commands, entity);
+            }
+        }
+        ThingToShow::Plots => {
+            if let Some(mut plot_source) = plot_source {
+                plot_source.show(entity, ui);
+            } else {
+                ui.colored_label(
+                    egui::Color32::DARK_RED,
+                    "No plotting configured for this entity",
+                );
+            }
+        }
+        ThingToShow::Info => {
+            if let Some(info_source) = info_source.as_mut() {
+                info_source.show(entity, ui);
+            } else {
+                ui.colored_label(
+                    egui::Color32::DARK_RED,
+                    "No info configured for this entity",
+                );
+            }
         }
     });
 }
@@ -9,9 +9,23 @@ pub struct PlotSource {
     input: Vec<Vec<(&'static str, f32)>>,
     fields: Vec<Vec<&'static str>>,
     rolling: VecDeque<f32>,
+    channels: Vec<PlotChannel>,
     last_update: f32,
     update_every: f32,
     keep: f32,
+    /// Stop recording new samples, so that the recorded ones can be examined.
+    pub paused: bool,
+    /// How many seconds (back from the last sample) are shown in the plots.
+    pub visible_duration: f32,
+    /// The time of the scrubbing cursor, if shown.
+    pub cursor: Option<f64>,
+}
+
+#[derive(Debug)]
+struct PlotChannel {
+    name: &'static str,
+    value: f32,
+    rolling: VecDeque<[f64; 2]>,
 }
 
 impl Default for PlotSource {
@@ -20,9 +34,13 @@ impl Default for PlotSource {
             input: Default::default(),
             fields: Default::default(),
             rolling: Default::default(),
+            channels: Default::default(),
             last_update: f32::NEG_INFINITY,
             update_every: 1.0 / 24.0,
             keep: 5.0,
+            paused: false,
+            visible_duration: 5.0,
+            cursor: None,
         }
     }
 }
@@ -40,7 +58,182 @@ impl PlotSource {
         }
     }
 
-    pub fn show(&self, entity: Entity, ui: &mut egui::Ui) {
+    /// Set the current value of a user-defined channel, which will be plotted (in its own plot)
+    /// after the built-in plots.
+    ///
+    /// Channels do not need to be registered in advance - the first call for a name creates the
+    /// channel. Call this every frame (or whenever the value changes) from gameplay code to plot
+    /// things like stamina or the phase of an action.
+    pub fn set_channel(&mut self, name: &'static str, value: f32) {
+        if let Some(channel) = self
+            .channels
+            .iter_mut()
+            .find(|channel| channel.name == name)
+        {
+            channel.value = value;
+        } else {
+            self.channels.push(PlotChannel {
+                name,
+                value,
+                rolling: Default::default(),
+            });
+        }
+    }
+
+    /// Dump the rolling buffers - the built-in plots and the user-defined channels - as CSV, with
+    /// a row for each sample and a column for each curve.
+    ///
+    /// Channels that did not exist yet at a sample's time have empty cells in its row.
+    pub fn to_csv(&self) -> String {
+        let fields_count = self.fields.iter().map(|plot| plot.len()).sum::<usize>();
+        let rolling = self.rolling.iter().copied().collect::<Vec<_>>();
+        // Each record is a timestamp followed by the values of all the fields.
+        let records = if 0 < fields_count {
+            rolling.chunks_exact(fields_count + 1).collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+
+        let mut timestamps = records
+            .iter()
+            .map(|record| record[0] as f64)
+            .chain(
+                self.channels
+                    .iter()
+                    .flat_map(|channel| channel.rolling.iter().map(|[timestamp, _]| *timestamp)),
+            )
+            .collect::<Vec<_>>();
+        timestamps.sort_by(|a, b| a.total_cmp(b));
+        timestamps.dedup();
+
+        let mut csv = String::from("time");
+        for field in self.fields.iter().flatten() {
+            csv.push(',');
+            csv.push_str(field);
+        }
+        for channel in self.channels.iter() {
+            csv.push(',');
+            csv.push_str(channel.name);
+        }
+        csv.push('\n');
+
+        for timestamp in timestamps {
+            csv.push_str(&timestamp.to_string());
+            let values = records
+                .binary_search_by(|record| (record[0] as f64).total_cmp(&timestamp))
+                .ok()
+                .map(|index| &records[index][1..]);
+            for i in 0..fields_count {
+                csv.push(',');
+                if let Some(values) = values {
+                    csv.push_str(&values[i].to_string());
+                }
+            }
+            for channel in self.channels.iter() {
+                csv.push(',');
+                if let Ok(index) = channel
+                    .rolling
+                    .binary_search_by(|[sample_time, _]| sample_time.total_cmp(&timestamp))
+                {
+                    csv.push_str(&channel.rolling[index][1].to_string());
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Write the rolling buffers to a CSV file. See [`to_csv`](Self::to_csv).
+    pub fn export_csv(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_csv())
+    }
+
+    /// The times of the first and last samples.
+    fn time_range(&self) -> Option<(f64, f64)> {
+        let builtin = self
+            .rolling
+            .front()
+            .map(|_| (self.rolling[0] as f64, self.last_record_time()));
+        self.channels
+            .iter()
+            .filter_map(|channel| Some((channel.rolling.front()?[0], channel.rolling.back()?[0])))
+            .chain(builtin)
+            .reduce(|(start_a, end_a), (start_b, end_b)| (start_a.min(start_b), end_a.max(end_b)))
+    }
+
+    fn last_record_time(&self) -> f64 {
+        let record_width = 1 + self.fields.iter().map(|plot| plot.len()).sum::<usize>();
+        self.rolling
+            .get(self.rolling.len().saturating_sub(record_width))
+            .map_or(f64::NEG_INFINITY, |timestamp| *timestamp as f64)
+    }
+
+    /// The values of all the curves at the last sample taken at or before `time`.
+    pub fn values_at(&self, time: f64) -> Vec<(&'static str, f32)> {
+        let fields_count = self.fields.iter().map(|plot| plot.len()).sum::<usize>();
+        let mut result = Vec::new();
+        if 0 < fields_count {
+            let mut found = None;
+            let mut it = self.rolling.iter();
+            while let Some(timestamp) = it.next() {
+                let values = it.by_ref().take(fields_count).copied().collect::<Vec<_>>();
+                if time < *timestamp as f64 {
+                    break;
+                }
+                found = Some(values);
+            }
+            if let Some(values) = found {
+                result.extend(self.fields.iter().flatten().copied().zip(values));
+            }
+        }
+        for channel in self.channels.iter() {
+            if let Some([_, value]) = channel
+                .rolling
+                .iter()
+                .take_while(|[timestamp, _]| *timestamp <= time)
+                .last()
+            {
+                result.push((channel.name, *value as f32));
+            }
+        }
+        result
+    }
+
+    pub fn show(&mut self, entity: Entity, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.toggle_value(&mut self.paused, "pause");
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button("Export to CSV").clicked() {
+                let path = format!("tnua-plot-{}-{:.0}.csv", entity, self.last_update);
+                match self.export_csv(&path) {
+                    Ok(()) => info!("Exported plot data to {path}"),
+                    Err(err) => error!("Failed exporting plot data to {path}: {err}"),
+                }
+            }
+        });
+        ui.add(egui::Slider::new(&mut self.visible_duration, 0.1..=self.keep).text("Time Window"));
+
+        let Some((first_time, last_time)) = self.time_range() else {
+            return;
+        };
+        let visible_from = first_time.max(last_time - self.visible_duration as f64);
+
+        let mut show_cursor = self.cursor.is_some();
+        ui.checkbox(&mut show_cursor, "Cursor");
+        if show_cursor {
+            let cursor = self.cursor.get_or_insert(last_time);
+            *cursor = cursor.clamp(visible_from, last_time);
+            ui.add(egui::Slider::new(cursor, visible_from..=last_time).text("Cursor Time"));
+            let cursor = *cursor;
+            ui.horizontal_wrapped(|ui| {
+                for (name, value) in self.values_at(cursor) {
+                    ui.monospace(format!("{name}: {value:.3}"));
+                }
+            });
+        } else {
+            self.cursor = None;
+        }
+
         let mut plots_data = self
             .fields
             .iter()
@@ -52,12 +245,17 @@ impl PlotSource {
             .collect::<Vec<_>>();
         let mut it = self.rolling.iter();
         while let Some(timestamp) = it.next() {
+            let visible = visible_from <= *timestamp as f64;
             for plot_data in plots_data.iter_mut() {
                 for curve in plot_data.iter_mut() {
-                    curve.push([*timestamp as f64, *it.next().unwrap() as f64]);
+                    let value = *it.next().unwrap();
+                    if visible {
+                        curve.push([*timestamp as f64, value as f64]);
+                    }
                 }
             }
         }
+        let cursor = self.cursor;
         for (i, (plot_fields, plot_data)) in self.fields.iter().zip(plots_data).enumerate() {
             let plot = Plot::new((entity, i))
                 .legend(Legend::default().position(Corner::LeftBottom))
@@ -70,6 +268,32 @@ impl PlotSource {
                 for (field, curve) in plot_fields.iter().zip(plot_data) {
                     plot_ui.line(egui_plot::Line::new(curve).name(field));
                 }
+                if let Some(cursor) = cursor {
+                    plot_ui.vline(egui_plot::VLine::new(cursor));
+                }
+            });
+        }
+        for channel in self.channels.iter() {
+            let plot = Plot::new((entity, channel.name))
+                .legend(Legend::default().position(Corner::LeftBottom))
+                .width(280.0)
+                .height(180.0)
+                .show_axes([false, true]);
+            plot.show(ui, |plot_ui| {
+                plot_ui.line(
+                    egui_plot::Line::new(
+                        channel
+                            .rolling
+                            .iter()
+                            .filter(|[timestamp, _]| visible_from <= *timestamp)
+                            .copied()
+                            .collect::<Vec<_>>(),
+                    )
+                    .name(channel.name),
+                );
+                if let Some(cursor) = cursor {
+                    plot_ui.vline(egui_plot::VLine::new(cursor));
+                }
             });
         }
     }
@@ -78,7 +302,7 @@ impl PlotSource {
 pub fn plot_source_rolling_update(time: Res<Time>, mut query: Query<&mut PlotSource>) {
     let time = time.elapsed_secs();
     for mut plot_source in query.iter_mut() {
-        if plot_source.input.is_empty() {
+        if plot_source.paused || (plot_source.input.is_empty() && plot_source.channels.is_empty()) {
             continue;
         }
         if time - plot_source.last_update < plot_source.update_every {
@@ -86,6 +310,22 @@ pub fn plot_source_rolling_update(time: Res<Time>, mut query: Query<&mut PlotSou
         }
         let keep_from = time - plot_source.keep;
         plot_source.last_update = time;
+
+        for channel in plot_source.channels.iter_mut() {
+            while let Some([timestamp, _]) = channel.rolling.front() {
+                if keep_from as f64 <= *timestamp {
+                    break;
+                }
+                channel.rolling.pop_front();
+            }
+            channel
+                .rolling
+                .push_back([time as f64, channel.value as f64]);
+        }
+
+        if plot_source.input.is_empty() {
+            continue;
+        }
         if plot_source.fields.is_empty() {
             plot_source.fields = plot_source
                 .input
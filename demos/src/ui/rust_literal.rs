@@ -0,0 +1,447 @@
+use std::fmt::{Display, Write};
+
+use serde::ser::{self, Impossible, Serialize};
+
+/// Format a value as a Rust expression that can be pasted into the game's code.
+///
+/// This uses the value's [`Serialize`] implementation, so it works for any config the UI can
+/// save - as long as the serialized form matches the Rust form (no renamed or skipped fields).
+pub fn to_rust_literal<T: Serialize + ?Sized>(value: &T) -> Result<String, RustLiteralError> {
+    let literal = value.serialize(RustLiteralSerializer)?;
+    let mut output = String::new();
+    literal.write(&mut output, 0);
+    Ok(output)
+}
+
+#[derive(Debug)]
+pub struct RustLiteralError(String);
+
+impl Display for RustLiteralError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for RustLiteralError {}
+
+impl ser::Error for RustLiteralError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+enum Literal {
+    Atom(String),
+    Struct {
+        path: String,
+        fields: Vec<(&'static str, Literal)>,
+    },
+    /// Anything with a comma separated list of items - function calls, tuples and `vec!`.
+    List {
+        open: String,
+        close: &'static str,
+        items: Vec<Literal>,
+    },
+}
+
+impl Literal {
+    fn call(callee: impl Display, items: Vec<Literal>) -> Self {
+        Self::List {
+            open: format!("{callee}("),
+            close: ")",
+            items,
+        }
+    }
+
+    fn is_atom(&self) -> bool {
+        matches!(self, Self::Atom(_))
+    }
+
+    fn write(&self, output: &mut String, indent: usize) {
+        let inner_indent = "    ".repeat(indent + 1);
+        match self {
+            Literal::Atom(atom) => output.push_str(atom),
+            Literal::Struct { path, fields } => {
+                if fields.is_empty() {
+                    write!(output, "{path} {{}}").unwrap();
+                    return;
+                }
+                writeln!(output, "{path} {{").unwrap();
+                for (name, value) in fields.iter() {
+                    write!(output, "{inner_indent}{name}: ").unwrap();
+                    value.write(output, indent + 1);
+                    output.push_str(",\n");
+                }
+                write!(output, "{}}}", "    ".repeat(indent)).unwrap();
+            }
+            Literal::List { open, close, items } => {
+                output.push_str(open);
+                if items.iter().all(Literal::is_atom) {
+                    for (i, item) in items.iter().enumerate() {
+                        if 0 < i {
+                            output.push_str(", ");
+                        }
+                        item.write(output, indent);
+                    }
+                } else {
+                    output.push('\n');
+                    for item in items.iter() {
+                        output.push_str(&inner_indent);
+                        item.write(output, indent + 1);
+                        output.push_str(",\n");
+                    }
+                    output.push_str(&"    ".repeat(indent));
+                }
+                output.push_str(close);
+            }
+        }
+    }
+}
+
+fn float_literal(value: impl Copy + std::fmt::Debug + Into<f64>, type_name: &str) -> Literal {
+    let as_f64: f64 = value.into();
+    Literal::Atom(if as_f64.is_nan() {
+        format!("{type_name}::NAN")
+    } else if as_f64 == f64::INFINITY {
+        format!("{type_name}::INFINITY")
+    } else if as_f64 == f64::NEG_INFINITY {
+        format!("{type_name}::NEG_INFINITY")
+    } else {
+        // Debug always adds a fraction (or an exponent), so the literal stays a float.
+        format!("{value:?}")
+    })
+}
+
+struct RustLiteralSerializer;
+
+impl ser::Serializer for RustLiteralSerializer {
+    type Ok = Literal;
+    type Error = RustLiteralError;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = Impossible<Literal, RustLiteralError>;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = StructSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(v.to_string()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Literal, RustLiteralError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Literal, RustLiteralError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Literal, RustLiteralError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Literal, RustLiteralError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Literal, RustLiteralError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Literal, RustLiteralError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Literal, RustLiteralError> {
+        Ok(float_literal(v, "f32"))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Literal, RustLiteralError> {
+        Ok(float_literal(v, "f64"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(format!("{v:?}")))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(format!("{v:?}.to_owned()")))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(format!("vec!{v:?}")))
+    }
+
+    fn serialize_none(self) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom("None".to_owned()))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::call("Some", vec![value.serialize(self)?]))
+    }
+
+    fn serialize_unit(self) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom("()".to_owned()))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(name.to_owned()))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::Atom(format!("{name}::{variant}")))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Literal, RustLiteralError> {
+        let callee = match name {
+            // The vector inside the direction types is private.
+            "Dir2" | "Dir3" | "Dir3A" => format!("{name}::new_unchecked"),
+            _ => name.to_owned(),
+        };
+        Ok(Literal::call(callee, vec![value.serialize(self)?]))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Literal, RustLiteralError> {
+        Ok(Literal::call(
+            format!("{name}::{variant}"),
+            vec![value.serialize(self)?],
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer, RustLiteralError> {
+        Ok(ListSerializer::new("vec![", "]", len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer, RustLiteralError> {
+        Ok(ListSerializer::new("(", ")", len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<ListSerializer, RustLiteralError> {
+        // glam serializes its vectors and quaternions as tuple structs, but their fields are
+        // named.
+        let open = match name {
+            "Vec2" | "Vec3" | "Vec3A" | "Vec4" | "DVec2" | "DVec3" | "DVec4" => {
+                format!("{name}::new(")
+            }
+            "Quat" | "DQuat" => format!("{name}::from_xyzw("),
+            _ => format!("{name}("),
+        };
+        Ok(ListSerializer::new(open, ")", len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ListSerializer, RustLiteralError> {
+        Ok(ListSerializer::new(format!("{name}::{variant}("), ")", len))
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Impossible<Literal, RustLiteralError>, RustLiteralError> {
+        Err(RustLiteralError("maps are not supported".to_owned()))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer, RustLiteralError> {
+        Ok(StructSerializer::new(name.to_owned(), len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer, RustLiteralError> {
+        Ok(StructSerializer::new(format!("{name}::{variant}"), len))
+    }
+}
+
+struct ListSerializer {
+    open: String,
+    close: &'static str,
+    items: Vec<Literal>,
+}
+
+impl ListSerializer {
+    fn new(open: impl Into<String>, close: &'static str, len: usize) -> Self {
+        Self {
+            open: open.into(),
+            close,
+            items: Vec::with_capacity(len),
+        }
+    }
+
+    fn add<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RustLiteralError> {
+        self.items.push(value.serialize(RustLiteralSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Literal {
+        Literal::List {
+            open: self.open,
+            close: self.close,
+            items: self.items,
+        }
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Literal;
+    type Error = RustLiteralError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Literal, RustLiteralError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Literal;
+    type Error = RustLiteralError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Literal, RustLiteralError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Literal;
+    type Error = RustLiteralError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Literal, RustLiteralError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for ListSerializer {
+    type Ok = Literal;
+    type Error = RustLiteralError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Literal, RustLiteralError> {
+        Ok(self.finish())
+    }
+}
+
+struct StructSerializer {
+    path: String,
+    fields: Vec<(&'static str, Literal)>,
+}
+
+impl StructSerializer {
+    fn new(path: String, len: usize) -> Self {
+        Self {
+            path,
+            fields: Vec::with_capacity(len),
+        }
+    }
+
+    fn add<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), RustLiteralError> {
+        self.fields
+            .push((key, value.serialize(RustLiteralSerializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> Literal {
+        match (self.path.as_str(), self.fields.as_slice()) {
+            // The points of a curve are private, and set with its constructor.
+            ("TnuaCurve", [("points", _)]) => {
+                let (_, points) = self.fields.into_iter().next().unwrap();
+                Literal::call("TnuaCurve::new", vec![points])
+            }
+            _ => Literal::Struct {
+                path: self.path,
+                fields: self.fields,
+            },
+        }
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Literal;
+    type Error = RustLiteralError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.add(key, value)
+    }
+
+    fn end(self) -> Result<Literal, RustLiteralError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for StructSerializer {
+    type Ok = Literal;
+    type Error = RustLiteralError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.add(key, value)
+    }
+
+    fn end(self) -> Result<Literal, RustLiteralError> {
+        Ok(self.finish())
+    }
+}
@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::component_alterbation::SavedSelector;
+
+/// A character's tuned config, along with the state of its selectors, as saved to a RON file.
+#[derive(Serialize, Deserialize)]
+pub struct SavedCharacterConfig<C> {
+    pub config: C,
+    #[serde(default)]
+    pub selectors: Vec<SavedSelector>,
+}
+
+impl<C: Serialize + DeserializeOwned> SavedCharacterConfig<C> {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let ron = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, ron)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let ron = std::fs::read_to_string(path)?;
+        Ok(ron::from_str(&ron)?)
+    }
+}
@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+#[cfg(feature = "egui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_tnua::math::{AdjustPrecision, Float, Vector2};
+use bevy_tnua::TnuaUserControlsSystemSet;
+
+const MARGIN: Float = 30.0;
+const STICK_RADIUS: Float = 70.0;
+const BUTTON_RADIUS: Float = 40.0;
+
+pub struct DemoTouchControlsPlugin;
+
+impl Plugin for DemoTouchControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DemoTouchControls>();
+        app.add_systems(
+            Update,
+            update_touch_controls.before(TnuaUserControlsSystemSet),
+        );
+        #[cfg(feature = "egui")]
+        app.add_systems(Update, draw_touch_controls);
+    }
+}
+
+/// The state of the on-screen virtual stick and buttons, for the control systems to read.
+///
+/// The input is read directly from Bevy's [`Touches`] (and not from egui, which only draws the
+/// controls) so that the stick and the buttons can be used at the same time.
+#[derive(Resource, Default)]
+pub struct DemoTouchControls {
+    /// Whether the controls are shown and used. Turns on automatically on the first touch.
+    pub enabled: bool,
+    /// The virtual stick's deflection, with up being positive Y.
+    pub stick: Vector2,
+    pub jump: bool,
+    pub crouch: bool,
+    pub crouch_just_pressed: bool,
+    stick_touch: Option<u64>,
+}
+
+struct TouchControlsLayout {
+    stick_center: Vector2,
+    jump_center: Vector2,
+    crouch_center: Vector2,
+}
+
+impl TouchControlsLayout {
+    fn new(window: &Window) -> Self {
+        let (width, height) = (window.width() as Float, window.height() as Float);
+        let jump_center = Vector2::new(
+            width - MARGIN - BUTTON_RADIUS,
+            height - MARGIN - BUTTON_RADIUS,
+        );
+        Self {
+            stick_center: Vector2::new(MARGIN + STICK_RADIUS, height - MARGIN - STICK_RADIUS),
+            jump_center,
+            crouch_center: jump_center - Vector2::new(MARGIN + 2.0 * BUTTON_RADIUS, 0.0),
+        }
+    }
+}
+
+fn update_touch_controls(
+    touches: Res<Touches>,
+    primary_window_query: Query<&Window, With<PrimaryWindow>>,
+    mut touch_controls: ResMut<DemoTouchControls>,
+) {
+    let touch_controls = touch_controls.as_mut();
+    if touches.any_just_pressed() {
+        touch_controls.enabled = true;
+    }
+    let (true, Ok(window)) = (touch_controls.enabled, primary_window_query.get_single()) else {
+        touch_controls.stick = Vector2::ZERO;
+        touch_controls.jump = false;
+        touch_controls.crouch = false;
+        touch_controls.crouch_just_pressed = false;
+        touch_controls.stick_touch = None;
+        return;
+    };
+    let layout = TouchControlsLayout::new(window);
+    let in_button = |position: Vec2, center: Vector2| {
+        position.adjust_precision().distance(center) <= BUTTON_RADIUS
+    };
+
+    if touch_controls
+        .stick_touch
+        .is_some_and(|id| touches.get_pressed(id).is_none())
+    {
+        touch_controls.stick_touch = None;
+    }
+    if touch_controls.stick_touch.is_none() {
+        // Grabbing the stick is a little lenient, but it can only be grabbed by a new touch.
+        touch_controls.stick_touch = touches
+            .iter_just_pressed()
+            .find(|touch| {
+                touch
+                    .position()
+                    .adjust_precision()
+                    .distance(layout.stick_center)
+                    <= 1.5 * STICK_RADIUS
+            })
+            .map(|touch| touch.id());
+    }
+    touch_controls.stick = touch_controls
+        .stick_touch
+        .and_then(|id| touches.get_pressed(id))
+        .map(|touch| {
+            let offset = (touch.position().adjust_precision() - layout.stick_center) / STICK_RADIUS;
+            // Screen coordinates go down, but the stick's Y should go up.
+            Vector2::new(offset.x, -offset.y).clamp_length_max(1.0)
+        })
+        .unwrap_or_default();
+
+    touch_controls.jump = touches
+        .iter()
+        .any(|touch| in_button(touch.position(), layout.jump_center));
+    touch_controls.crouch = touches
+        .iter()
+        .any(|touch| in_button(touch.position(), layout.crouch_center));
+    touch_controls.crouch_just_pressed = touches
+        .iter_just_pressed()
+        .any(|touch| in_button(touch.position(), layout.crouch_center));
+}
+
+#[cfg(feature = "egui")]
+fn draw_touch_controls(
+    mut egui_context: EguiContexts,
+    primary_window_query: Query<&Window, With<PrimaryWindow>>,
+    touch_controls: Res<DemoTouchControls>,
+) {
+    use bevy_tnua::math::AsF32;
+
+    let (true, Ok(window)) = (touch_controls.enabled, primary_window_query.get_single()) else {
+        return;
+    };
+    let layout = TouchControlsLayout::new(window);
+    let painter = egui_context.ctx_mut().layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("touch-controls"),
+    ));
+    let to_pos = |point: Vector2| {
+        let point = point.f32();
+        egui::pos2(point.x, point.y)
+    };
+    let stroke = egui::Stroke::new(2.0, egui::Color32::from_white_alpha(160));
+    let fill = |pressed: bool| egui::Color32::from_white_alpha(if pressed { 120 } else { 40 });
+
+    painter.circle_stroke(to_pos(layout.stick_center), STICK_RADIUS.f32(), stroke);
+    let knob = layout.stick_center
+        + Vector2::new(touch_controls.stick.x, -touch_controls.stick.y) * STICK_RADIUS;
+    painter.circle(
+        to_pos(knob),
+        0.4 * STICK_RADIUS.f32(),
+        fill(touch_controls.stick_touch.is_some()),
+        stroke,
+    );
+
+    for (center, pressed, text) in [
+        (layout.jump_center, touch_controls.jump, "Jump"),
+        (layout.crouch_center, touch_controls.crouch, "Crouch"),
+    ] {
+        painter.circle(to_pos(center), BUTTON_RADIUS.f32(), fill(pressed), stroke);
+        painter.text(
+            to_pos(center),
+            egui::Align2::CENTER_CENTER,
+            text,
+            egui::FontId::proportional(16.0),
+            egui::Color32::WHITE,
+        );
+    }
+}
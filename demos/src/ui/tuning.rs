@@ -11,7 +11,41 @@ use bevy_egui::egui;
 
 pub trait UiTunable {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui);
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self);
+}
+
+/// A small button for restoring a tuned field to its default value.
+#[cfg(feature = "egui")]
+pub fn reset_button<T: PartialEq + Clone>(ui: &mut egui::Ui, value: &mut T, default: &T) {
+    if ui
+        .add_enabled(value != default, egui::Button::new("\u{27f2}").small())
+        .on_hover_text("Reset to default")
+        .clicked()
+    {
+        *value = default.clone();
+    }
+}
+
+#[cfg(feature = "egui")]
+pub fn slider<N: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    caption: &str,
+    value: &mut N,
+    range: RangeInclusive<N>,
+    default: N,
+) {
+    ui.horizontal(|ui| {
+        reset_button(ui, value, &default);
+        ui.add(egui::Slider::new(value, range).text(caption));
+    });
+}
+
+#[cfg(feature = "egui")]
+pub fn checkbox(ui: &mut egui::Ui, caption: &str, value: &mut bool, default: bool) {
+    ui.horizontal(|ui| {
+        reset_button(ui, value, &default);
+        ui.checkbox(value, caption);
+    });
 }
 
 #[cfg(feature = "egui")]
@@ -20,11 +54,13 @@ fn slider_or_infinity(
     caption: &str,
     value: &mut Float,
     range: RangeInclusive<Float>,
+    default: Float,
 ) {
     #[derive(Clone)]
     struct CachedValue(Float);
 
     ui.horizontal(|ui| {
+        reset_button(ui, value, &default);
         let mut infinite = !value.is_finite();
         let resp = ui.toggle_value(&mut infinite, "\u{221e}");
         if resp.clicked() {
@@ -62,11 +98,13 @@ fn slider_or_none(
     caption: &str,
     value: &mut Option<Float>,
     range: RangeInclusive<Float>,
+    default: Option<Float>,
 ) {
     #[derive(Clone)]
     struct CachedValue(Float);
 
     ui.horizontal(|ui| {
+        reset_button(ui, value, &default);
         let mut is_none = value.is_none();
         let resp = ui.toggle_value(&mut is_none, "\u{d8}");
         if resp.clicked() {
@@ -100,24 +138,64 @@ fn slider_or_none(
 
 impl UiTunable for TnuaBuiltinWalk {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui) {
-        ui.add(egui::Slider::new(&mut self.float_height, 0.0..=10.0).text("Float At"));
-        ui.add(egui::Slider::new(&mut self.cling_distance, 0.0..=10.0).text("Cling Distance"));
-        ui.add(egui::Slider::new(&mut self.spring_strength, 0.0..=4000.0).text("Spring Strength"));
-        ui.add(egui::Slider::new(&mut self.spring_dampening, 0.0..=1.9).text("Spring Dampening"));
-        slider_or_infinity(ui, "Acceleration", &mut self.acceleration, 0.0..=200.0);
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self) {
+        slider(
+            ui,
+            "Float At",
+            &mut self.float_height,
+            0.0..=10.0,
+            defaults.float_height,
+        );
+        slider(
+            ui,
+            "Cling Distance",
+            &mut self.cling_distance,
+            0.0..=10.0,
+            defaults.cling_distance,
+        );
+        slider(
+            ui,
+            "Spring Strength",
+            &mut self.spring_strength,
+            0.0..=4000.0,
+            defaults.spring_strength,
+        );
+        slider(
+            ui,
+            "Spring Dampening",
+            &mut self.spring_dampening,
+            0.0..=1.9,
+            defaults.spring_dampening,
+        );
+        slider_or_infinity(
+            ui,
+            "Acceleration",
+            &mut self.acceleration,
+            0.0..=200.0,
+            defaults.acceleration,
+        );
         slider_or_infinity(
             ui,
             "Air Acceleration",
             &mut self.air_acceleration,
             0.0..=200.0,
+            defaults.air_acceleration,
         );
 
-        ui.add(egui::Slider::new(&mut self.coyote_time, 0.0..=1.0).text("Coyote Time"));
+        slider(
+            ui,
+            "Coyote Time",
+            &mut self.coyote_time,
+            0.0..=1.0,
+            defaults.coyote_time,
+        );
 
-        ui.add(
-            egui::Slider::new(&mut self.free_fall_extra_gravity, 0.0..=100.0)
-                .text("Free Fall Extra Gravity"),
+        slider(
+            ui,
+            "Free Fall Extra Gravity",
+            &mut self.free_fall_extra_gravity,
+            0.0..=100.0,
+            defaults.free_fall_extra_gravity,
         );
 
         slider_or_infinity(
@@ -125,12 +203,14 @@ impl UiTunable for TnuaBuiltinWalk {
             "Staying Upward Max Angular Velocity",
             &mut self.tilt_offset_angvel,
             0.0..=20.0,
+            defaults.tilt_offset_angvel,
         );
         slider_or_infinity(
             ui,
             "Staying Upward Max Angular Acceleration",
             &mut self.tilt_offset_angacl,
             0.0..=2000.0,
+            defaults.tilt_offset_angacl,
         );
 
         slider_or_infinity(
@@ -138,69 +218,121 @@ impl UiTunable for TnuaBuiltinWalk {
             "Turning Angular Velocity",
             &mut self.turning_angvel,
             0.0..=70.0,
+            defaults.turning_angvel,
+        );
+
+        slider(
+            ui,
+            "Max Slope (in radians)",
+            &mut self.max_slope,
+            0.0..=float_consts::FRAC_PI_2,
+            defaults.max_slope,
         );
 
-        ui.add(
-            egui::Slider::new(&mut self.max_slope, 0.0..=float_consts::FRAC_PI_2)
-                .text("Max Slope (in radians)"),
+        slider(
+            ui,
+            "Max Snap Distance",
+            &mut self.max_snap_distance,
+            0.0..=10.0,
+            defaults.max_snap_distance,
+        );
+
+        slider_or_infinity(
+            ui,
+            "External Velocity Recovery",
+            &mut self.external_velocity_recovery,
+            0.0..=200.0,
+            defaults.external_velocity_recovery,
         );
     }
 }
 
 impl UiTunable for TnuaBuiltinJump {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui) {
-        ui.add(egui::Slider::new(&mut self.height, 0.0..=10.0).text("Jump Height"));
-        ui.add(
-            egui::Slider::new(&mut self.input_buffer_time, 0.0..=1.0)
-                .text("Jump Input Buffer Time"),
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self) {
+        slider(
+            ui,
+            "Jump Height",
+            &mut self.height,
+            0.0..=10.0,
+            defaults.height,
+        );
+        slider(
+            ui,
+            "Jump Input Buffer Time",
+            &mut self.input_buffer_time,
+            0.0..=1.0,
+            defaults.input_buffer_time,
         );
         slider_or_none(
             ui,
             "Held Jump Cooldown",
             &mut self.reschedule_cooldown,
             0.0..=2.0,
+            defaults.reschedule_cooldown,
         );
-        ui.add(
-            egui::Slider::new(&mut self.upslope_extra_gravity, 0.0..=100.0)
-                .text("Upslope Jump Extra Gravity"),
+        slider(
+            ui,
+            "Upslope Jump Extra Gravity",
+            &mut self.upslope_extra_gravity,
+            0.0..=100.0,
+            defaults.upslope_extra_gravity,
         );
-        ui.add(
-            egui::Slider::new(&mut self.takeoff_extra_gravity, 0.0..=100.0)
-                .text("Jump Takeoff Extra Gravity"),
+        slider(
+            ui,
+            "Jump Takeoff Extra Gravity",
+            &mut self.takeoff_extra_gravity,
+            0.0..=100.0,
+            defaults.takeoff_extra_gravity,
         );
         slider_or_infinity(
             ui,
             "Jump Takeoff Above Velocity",
             &mut self.takeoff_above_velocity,
             0.0..=20.0,
+            defaults.takeoff_above_velocity,
         );
-        ui.add(
-            egui::Slider::new(&mut self.fall_extra_gravity, 0.0..=50.0)
-                .text("Jump Fall Extra Gravity"),
+        slider(
+            ui,
+            "Jump Fall Extra Gravity",
+            &mut self.fall_extra_gravity,
+            0.0..=50.0,
+            defaults.fall_extra_gravity,
         );
-        ui.add(
-            egui::Slider::new(&mut self.shorten_extra_gravity, 0.0..=100.0)
-                .text("Jump Shorten Extra Gravity"),
+        slider(
+            ui,
+            "Jump Shorten Extra Gravity",
+            &mut self.shorten_extra_gravity,
+            0.0..=100.0,
+            defaults.shorten_extra_gravity,
         );
 
-        ui.add(
-            egui::Slider::new(&mut self.peak_prevention_at_upward_velocity, 0.0..=20.0)
-                .text("Jump Peak Prevention At Upward Velocity"),
+        slider(
+            ui,
+            "Jump Peak Prevention At Upward Velocity",
+            &mut self.peak_prevention_at_upward_velocity,
+            0.0..=20.0,
+            defaults.peak_prevention_at_upward_velocity,
         );
 
-        ui.add(
-            egui::Slider::new(&mut self.peak_prevention_extra_gravity, 0.0..=100.0)
-                .text("Jump Peak Prevention Extra Gravity"),
+        slider(
+            ui,
+            "Jump Peak Prevention Extra Gravity",
+            &mut self.peak_prevention_extra_gravity,
+            0.0..=100.0,
+            defaults.peak_prevention_extra_gravity,
         );
     }
 }
 impl UiTunable for TnuaBuiltinCrouch {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui) {
-        ui.add(
-            egui::Slider::new(&mut self.height_change_impulse_for_duration, 0.001..=0.2)
-                .text("Height Change Impulse for Duration"),
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self) {
+        slider(
+            ui,
+            "Height Change Impulse for Duration",
+            &mut self.height_change_impulse_for_duration,
+            0.001..=0.2,
+            defaults.height_change_impulse_for_duration,
         );
 
         slider_or_infinity(
@@ -208,54 +340,122 @@ impl UiTunable for TnuaBuiltinCrouch {
             "Height Change Impulse",
             &mut self.height_change_impulse_limit,
             0.0..=40.0,
+            defaults.height_change_impulse_limit,
+        );
+
+        slider(
+            ui,
+            "Walk Speed Factor",
+            &mut self.walk_speed_factor,
+            0.0..=1.0,
+            defaults.walk_speed_factor,
         );
     }
 }
 
 impl UiTunable for TnuaBuiltinDash {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui) {
-        ui.add(egui::Slider::new(&mut self.speed, 0.0..=200.0).text("Dash Speed"));
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self) {
+        slider(
+            ui,
+            "Dash Speed",
+            &mut self.speed,
+            0.0..=200.0,
+            defaults.speed,
+        );
+        slider_or_none(
+            ui,
+            "Dash Duration",
+            &mut self.duration,
+            0.0..=2.0,
+            defaults.duration,
+        );
+        checkbox(
+            ui,
+            "Zero Vertical Velocity During Dash",
+            &mut self.zero_vertical_velocity,
+            defaults.zero_vertical_velocity,
+        );
         slider_or_infinity(
             ui,
             "Brake to Speed After Dash",
             &mut self.brake_to_speed,
             0.0..=80.0,
+            defaults.brake_to_speed,
+        );
+        slider_or_infinity(
+            ui,
+            "Dash Acceleration",
+            &mut self.acceleration,
+            0.0..=800.0,
+            defaults.acceleration,
         );
-        slider_or_infinity(ui, "Dash Acceleration", &mut self.acceleration, 0.0..=800.0);
         slider_or_infinity(
             ui,
             "Dash Brake Acceleration",
             &mut self.brake_acceleration,
             0.0..=800.0,
+            defaults.brake_acceleration,
         );
-        ui.add(
-            egui::Slider::new(&mut self.input_buffer_time, 0.0..=1.0)
-                .text("Dash Input Buffer Time"),
+        slider(
+            ui,
+            "Dash Input Buffer Time",
+            &mut self.input_buffer_time,
+            0.0..=1.0,
+            defaults.input_buffer_time,
         );
     }
 }
 
 impl UiTunable for TnuaBuiltinKnockback {
     #[cfg(feature = "egui")]
-    fn tune(&mut self, ui: &mut egui::Ui) {
-        ui.add(egui::Slider::new(&mut self.no_push_timeout, 0.0..=2.0).text("No Push Timeout"));
-        ui.add(
-            egui::Slider::new(&mut self.barrier_strength_diminishing, 0.01..=100.0)
-                .logarithmic(true)
-                .text("Barrier Strengh Diminishing"),
+    fn tune(&mut self, ui: &mut egui::Ui, defaults: &Self) {
+        slider(
+            ui,
+            "No Push Timeout",
+            &mut self.no_push_timeout,
+            0.0..=2.0,
+            defaults.no_push_timeout,
         );
+        ui.horizontal(|ui| {
+            reset_button(
+                ui,
+                &mut self.barrier_strength_diminishing,
+                &defaults.barrier_strength_diminishing,
+            );
+            ui.add(
+                egui::Slider::new(&mut self.barrier_strength_diminishing, 0.01..=100.0)
+                    .logarithmic(true)
+                    .text("Barrier Strengh Diminishing"),
+            );
+        });
         slider_or_infinity(
             ui,
             "Acceleration Limit",
             &mut self.acceleration_limit,
             0.0..=20.0,
+            defaults.acceleration_limit,
         );
         slider_or_infinity(
             ui,
             "Air Acceleration Limit",
             &mut self.air_acceleration_limit,
             0.0..=20.0,
+            defaults.air_acceleration_limit,
+        );
+        slider(
+            ui,
+            "No Control Duration",
+            &mut self.no_control_duration,
+            0.0..=2.0,
+            defaults.no_control_duration,
+        );
+        slider(
+            ui,
+            "Control Recovery Duration",
+            &mut self.control_recovery_duration,
+            0.0..=2.0,
+            defaults.control_recovery_duration,
         );
     }
 }
@@ -0,0 +1,80 @@
+use serde::Serialize;
+
+const MAX_UNDO_STEPS: usize = 100;
+
+/// The tuning state of a single character: the defaults to reset to, and the undo/redo stacks.
+pub struct CharacterTuning<C> {
+    defaults: C,
+    undo: Vec<C>,
+    redo: Vec<C>,
+    // The config from before the edit that is still in progress (e.g. a slider being dragged)
+    edit_start: Option<C>,
+}
+
+impl<C: Clone + Serialize> CharacterTuning<C> {
+    pub fn new(defaults: C) -> Self {
+        Self {
+            defaults,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            edit_start: None,
+        }
+    }
+
+    pub fn defaults(&self) -> &C {
+        &self.defaults
+    }
+
+    /// Record the changes the UI made to the config this frame.
+    ///
+    /// An edit only becomes an undo step once the pointer is released, so that dragging a slider
+    /// is rolled back as a whole and not frame by frame.
+    pub fn track(&mut self, before: C, after: &C, pointer_down: bool) {
+        if self.edit_start.is_none() && !same_config(&before, after) {
+            self.edit_start = Some(before);
+        }
+        if pointer_down {
+            return;
+        }
+        let Some(edit_start) = self.edit_start.take() else {
+            return;
+        };
+        // The edit may have been dragged back to where it started.
+        if same_config(&edit_start, after) {
+            return;
+        }
+        self.undo.push(edit_start);
+        if MAX_UNDO_STEPS < self.undo.len() {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn undo(&mut self, config: &mut C) {
+        if let Some(previous) = self.undo.pop() {
+            self.redo.push(std::mem::replace(config, previous));
+        }
+    }
+
+    pub fn redo(&mut self, config: &mut C) {
+        if let Some(next) = self.redo.pop() {
+            self.undo.push(std::mem::replace(config, next));
+        }
+    }
+}
+
+// The configs are not `PartialEq`, but they are all `Serialize`.
+fn same_config<C: Serialize>(a: &C, b: &C) -> bool {
+    match (ron::to_string(a), ron::to_string(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
@@ -0,0 +1,88 @@
+//! Runs a Tnua character without rendering, the way a dedicated server would.
+//!
+//! None of the Tnua crates need rendering, so only the plugins required by the physics backend
+//! are added on top of Bevy's `MinimalPlugins`.
+
+use std::time::Duration;
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+
+use avian3d::prelude::*;
+
+use bevy_tnua::prelude::*;
+use bevy_tnua_avian3d::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+                1.0 / 60.0,
+            ))),
+            TransformPlugin,
+            HierarchyPlugin,
+            // Avian only needs these when Bevy's `bevy_render` and `bevy_scene` features are
+            // enabled - which is the case here because the other examples need them.
+            AssetPlugin::default(),
+            bevy::render::mesh::MeshPlugin,
+            bevy::scene::ScenePlugin,
+            PhysicsPlugins::default(),
+            TnuaControllerPlugin::new(FixedUpdate),
+            TnuaAvian3dPlugin::new(FixedUpdate),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(
+            FixedUpdate,
+            apply_controls.in_set(TnuaUserControlsSystemSet),
+        )
+        .add_systems(Update, report_and_exit)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((RigidBody::Static, Collider::half_space(Vec3::Y)));
+
+    commands.spawn((
+        Transform::from_xyz(0.0, 2.0, 0.0),
+        RigidBody::Dynamic,
+        Collider::capsule(0.5, 1.0),
+        TnuaController::default(),
+        TnuaAvian3dSensorShape(Collider::cylinder(0.49, 0.0)),
+        LockedAxes::ROTATION_LOCKED,
+    ));
+}
+
+// With no input devices, the "server" just walks the character in a circle.
+fn apply_controls(time: Res<Time>, mut query: Query<&mut TnuaController>) {
+    let angle = time.elapsed_secs();
+    for mut controller in query.iter_mut() {
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: Vec3::new(angle.cos(), 0.0, angle.sin()) * 5.0,
+            float_height: 1.5,
+            ..Default::default()
+        });
+    }
+}
+
+fn report_and_exit(
+    time: Res<Time>,
+    mut last_report: Local<f32>,
+    query: Query<(&Transform, &TnuaController)>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if time.elapsed_secs() < *last_report + 1.0 {
+        return;
+    }
+    *last_report = time.elapsed_secs();
+    for (transform, controller) in query.iter() {
+        println!(
+            "{:.0}s: character at {:.2}, airborne: {:?}",
+            *last_report,
+            transform.translation,
+            controller.is_airborne().ok(),
+        );
+    }
+    if 5.0 <= *last_report {
+        exit.send(AppExit::Success);
+    }
+}
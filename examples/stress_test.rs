@@ -0,0 +1,199 @@
+//! Spawns a crowd of NPC characters wandering on procedural terrain, for measuring how Tnua's
+//! pipeline scales.
+//!
+//! The number of characters can be passed as an argument (the default is 2000):
+//!
+//! ```text
+//! cargo run --release --example stress_test -- 5000
+//! ```
+//!
+//! NPCs that are far from the camera are updated once every few frames, using `TnuaUpdateRate`.
+
+use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::{color::palettes::css, prelude::*};
+
+use avian3d::prelude::*;
+
+use bevy_tnua::prelude::*;
+use bevy_tnua::TnuaUpdateRate;
+use bevy_tnua_avian3d::*;
+
+const TERRAIN_SIZE: f32 = 200.0;
+const NPC_SPEED: f32 = 4.0;
+// How often each NPC picks a new direction to wander in.
+const WANDER_PERIOD: f32 = 3.0;
+// NPCs farther than this from the camera are updated less often.
+const LOD_DISTANCE: f32 = 150.0;
+
+#[derive(Resource)]
+struct NpcCount(usize);
+
+#[derive(Component)]
+struct Wanderer {
+    seed: f32,
+}
+
+fn main() {
+    let npc_count = std::env::args()
+        .nth(1)
+        .map(|arg| {
+            arg.parse()
+                .expect("the argument must be the number of NPCs")
+        })
+        .unwrap_or(2000);
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PhysicsPlugins::default(),
+            TnuaControllerPlugin::new(FixedUpdate),
+            TnuaAvian3dPlugin::new(FixedUpdate),
+            FrameTimeDiagnosticsPlugin,
+            LogDiagnosticsPlugin::default(),
+        ))
+        .insert_resource(NpcCount(npc_count))
+        .add_systems(Startup, (setup_camera_and_lights, setup_level, setup_npcs))
+        .add_systems(
+            FixedUpdate,
+            apply_wandering.in_set(TnuaUserControlsSystemSet),
+        )
+        .add_systems(Update, update_lod)
+        .run();
+}
+
+fn terrain_height(x: f32, z: f32) -> f32 {
+    3.0 * (x * 0.07).sin() * (z * 0.05).cos() + 1.5 * (x * 0.02 + z * 0.03).sin()
+}
+
+// A cheap deterministic pseudo-random number in `0.0..1.0`, so that the example does not need a
+// dependency for randomness.
+fn hash(value: f32) -> f32 {
+    (value.sin() * 43758.547).fract().abs()
+}
+
+fn setup_camera_and_lights(mut commands: Commands) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 120.0, 160.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 4000.0,
+            ..Default::default()
+        },
+        Transform::default().looking_at(Vec3::new(-1.0, -3.0, -2.0), Vec3::Y),
+    ));
+}
+
+fn setup_level(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut mesh = Plane3d::default()
+        .mesh()
+        .size(TERRAIN_SIZE, TERRAIN_SIZE)
+        .subdivisions(100)
+        .build();
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for position in positions.iter_mut() {
+            position[1] = terrain_height(position[0], position[2]);
+        }
+    }
+    mesh.compute_smooth_normals();
+    let collider = Collider::trimesh_from_mesh(&mesh).expect("the terrain mesh is a triangle list");
+
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(Color::from(css::DARK_OLIVEGREEN))),
+        RigidBody::Static,
+        collider,
+    ));
+}
+
+fn setup_npcs(
+    mut commands: Commands,
+    npc_count: Res<NpcCount>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Capsule3d {
+        radius: 0.5,
+        half_length: 0.5,
+    });
+    let material = materials.add(Color::from(css::DARK_CYAN));
+    // Spread the NPCs on a grid, keeping them away from the terrain's edges.
+    let columns = (npc_count.0 as f32).sqrt().ceil() as usize;
+    let spacing = (0.8 * TERRAIN_SIZE / columns as f32).min(4.0);
+    let offset = 0.5 * spacing * (columns - 1) as f32;
+    for i in 0..npc_count.0 {
+        let x = (i % columns) as f32 * spacing - offset;
+        let z = (i / columns) as f32 * spacing - offset;
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(x, terrain_height(x, z) + 2.0, z),
+            RigidBody::Dynamic,
+            Collider::capsule(0.5, 1.0),
+            TnuaController::default(),
+            TnuaAvian3dSensorShape(Collider::cylinder(0.49, 0.0)),
+            LockedAxes::ROTATION_LOCKED,
+            TnuaUpdateRate::default(),
+            Wanderer { seed: i as f32 },
+        ));
+    }
+}
+
+fn apply_wandering(
+    time: Res<Time>,
+    mut query: Query<(&GlobalTransform, &Wanderer, &mut TnuaController)>,
+) {
+    for (transform, wanderer, mut controller) in query.iter_mut() {
+        let position = transform.translation();
+        // Each NPC changes its direction at a different time.
+        let phase = hash(wanderer.seed);
+        let period = (time.elapsed_secs() / WANDER_PERIOD + phase).floor();
+        let angle = hash(wanderer.seed * 12.9898 + period * 78.233) * std::f32::consts::TAU;
+        let mut direction = Vec3::new(angle.cos(), 0.0, angle.sin());
+        // Turn back before walking off the terrain.
+        if 0.45 * TERRAIN_SIZE < position.xz().length() {
+            direction = Vec3::new(-position.x, 0.0, -position.z).normalize_or_zero();
+        }
+        // Some of the NPCs stand still, so that idle characters are measured as well.
+        let speed = if hash(wanderer.seed + period) < 0.2 {
+            0.0
+        } else {
+            NPC_SPEED
+        };
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: direction * speed,
+            desired_forward: Dir3::new(direction).ok(),
+            float_height: 1.5,
+            ..Default::default()
+        });
+    }
+}
+
+fn update_lod(
+    camera_query: Query<&GlobalTransform, With<Camera>>,
+    mut query: Query<(&GlobalTransform, &mut TnuaUpdateRate)>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    for (transform, mut update_rate) in query.iter_mut() {
+        let distance = camera_transform
+            .translation()
+            .distance(transform.translation());
+        let desired_update_rate = if distance < LOD_DISTANCE {
+            TnuaUpdateRate::EveryFrame
+        } else {
+            TnuaUpdateRate::EveryNFrames(4)
+        };
+        // Avoid triggering change detection when the rate stays the same.
+        update_rate.set_if_neq(desired_update_rate);
+    }
+}
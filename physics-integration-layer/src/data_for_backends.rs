@@ -1,15 +1,21 @@
 use std::ops::{Add, AddAssign};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::math::{Float, Quaternion, Vector3};
+use bevy::ecs::entity::{VisitEntities, VisitEntitiesMut};
+use bevy::ecs::reflect::ReflectMapEntities;
 use bevy::prelude::*;
 
 /// Allows disabling Tnua for a specific entity.
 ///
-/// This can be used to let some other system  temporarily take control over a character.
+/// This can be used to let some other system  temporarily take control over a character, or to
+/// pause characters (e.g. cutscene actors or pooled NPCs) without removing their components.
 ///
 /// This component is not mandatory - if omitted, Tnua will just assume it is enabled for that
 /// entity.
-#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Component)]
 pub enum TnuaToggle {
     /// Do not update the sensors, and do not apply forces from the motor.
     ///
@@ -17,6 +23,12 @@ pub enum TnuaToggle {
     /// stored in the `TnuaController` component. They will retain their last value from before
     /// `TnuaToggle::Disabled` was set.
     Disabled,
+    /// Like [`Disabled`](Self::Disabled), but the forces from the last time the motor was applied
+    /// are left in place.
+    ///
+    /// Nothing in the pipeline runs for the character, and no time passes for it - so when it is
+    /// enabled again it continues exactly from where it was suspended.
+    SuspendedKeepState,
     /// Update the sensors, but do not apply forces from the motor.
     ///
     /// The platformer controller system will still run and still update the motor components and
@@ -28,20 +40,140 @@ pub enum TnuaToggle {
     Enabled,
 }
 
+/// Whether a character is asleep - skipping most of its updates because it stands still.
+///
+/// This is managed by Tnua (see `TnuaIdleSleep` in the main crate) and is not mandatory - if
+/// omitted, the character is always awake.
+#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub enum TnuaSleepState {
+    #[default]
+    /// The backend behaves normally - it updates the sensors and applies forces from the motor.
+    Awake,
+    /// Update the sensors, but do not apply forces from the motor.
+    ///
+    /// Tnua uses this state to check, once in a while, if something changed under a sleeping
+    /// character.
+    Probing,
+    /// Only update the [`TnuaRigidBodyTracker`]. Do not update the [`TnuaProximitySensor`], and do
+    /// not apply forces from the motor.
+    ///
+    /// Unlike with [`TnuaToggle::SenseOnly`], the forces from the last time the motor was applied
+    /// should be left in place, so that the character keeps floating.
+    Asleep,
+}
+
+/// How often Tnua updates a character.
+///
+/// This can be used to simulate characters that are far away from the camera in reduced
+/// fidelity. On the frames a character is not updated, its proximity sensor is not updated, the
+/// controller does not run, and the motor is not applied - the forces from the last time it was
+/// applied stay in place. When the character does get updated, the controller uses the time that
+/// passed since its last update as its frame duration.
+///
+/// The control helpers that sense the surroundings, modify the motor or track time (like the wall
+/// sensor, the footsteps and the path follower) also skip the frames the character is not updated
+/// in, and use the time since its last update. The control helpers that only feed the controller
+/// still run every frame.
+///
+/// This component is not mandatory - if omitted, the character is updated every frame.
+#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[require(TnuaUpdateTimer)]
+pub enum TnuaUpdateRate {
+    #[default]
+    /// Update the character every frame.
+    EveryFrame,
+    /// Update the character once every this many frames.
+    ///
+    /// The frames are staggered between the characters, so that they do not all get updated in
+    /// the same frame.
+    EveryNFrames(u32),
+    /// Do not update the character at all.
+    ///
+    /// Unlike with [`TnuaToggle::Disabled`], the forces from the last time the motor was applied
+    /// stay in place. The time the character is paused does not count toward its next update.
+    Paused,
+}
+
+/// Tracks when a character with a [`TnuaUpdateRate`] should be updated.
+///
+/// This is added automatically with [`TnuaUpdateRate`], and is advanced by Tnua before the
+/// [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors) stage. The physics backend
+/// should skip updating the [`TnuaProximitySensor`] and applying the [`TnuaMotor`] on frames that
+/// are not [update frames](Self::is_update_frame). The [`TnuaRigidBodyTracker`] should still be
+/// updated every frame.
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaUpdateTimer {
+    frames_since_update: Option<u32>,
+    elapsed: Duration,
+    is_update_frame: bool,
+    delta: Duration,
+}
+
+impl TnuaUpdateTimer {
+    /// Advance the timer by one frame.
+    ///
+    /// `stagger` is used to pick the frame of the first update, so that characters that start
+    /// together do not all get updated in the same frames. Tnua uses the entity's index.
+    pub fn advance(&mut self, rate: TnuaUpdateRate, frame_delta: Duration, stagger: u32) {
+        let interval = match rate {
+            TnuaUpdateRate::EveryFrame => 1,
+            TnuaUpdateRate::EveryNFrames(interval) => interval.max(1),
+            TnuaUpdateRate::Paused => {
+                self.is_update_frame = false;
+                self.elapsed = Duration::ZERO;
+                return;
+            }
+        };
+        self.elapsed += frame_delta;
+        let frames_since_update = self.frames_since_update.get_or_insert(stagger % interval);
+        *frames_since_update += 1;
+        self.is_update_frame = interval <= *frames_since_update;
+        if self.is_update_frame {
+            *frames_since_update = 0;
+            self.delta = std::mem::take(&mut self.elapsed);
+        }
+    }
+
+    /// Whether or not the character should be updated in this frame.
+    pub fn is_update_frame(&self) -> bool {
+        self.is_update_frame
+    }
+
+    /// The time passed since the previous update of the character.
+    ///
+    /// Only meaningful in [update frames](Self::is_update_frame).
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+}
+
 /// Newtonian state of the rigid body.
 ///
 /// Tnua takes the position and rotation of the rigid body from its `GlobalTransform`, but things
 /// like velocity are dependent on the physics engine. The physics backend is responsible for
 /// updating this component from the physics engine during
 /// [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors).
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
 pub struct TnuaRigidBodyTracker {
+    /// The position of the rigid body, in world coordinates.
     pub translation: Vector3,
+    /// The rotation of the rigid body, in world coordinates.
     pub rotation: Quaternion,
+    /// The linear velocity of the rigid body, in world units per second.
     pub velocity: Vector3,
     /// Angular velocity as the rotation axis multiplied by the rotation speed in radians per
     /// second. Can be extracted from a quaternion using [`Quaternion::xyz`].
     pub angvel: Vector3,
+    /// The acceleration the physics engine applies to the rigid body due to gravity.
+    ///
+    /// This should include any per-body gravity scaling the physics engine supports. Tnua uses it
+    /// both for compensating for gravity and for determining the up direction (which is opposite
+    /// to the gravity).
     pub gravity: Vector3,
 }
 
@@ -62,7 +194,9 @@ impl Default for TnuaRigidBodyTracker {
 /// The physics backend is responsible for updating this component from the physics engine during
 /// [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors), usually by casting a ray
 /// or a shape in the `cast_direction`.
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
 pub struct TnuaProximitySensor {
     /// The cast origin in the entity's coord system.
     pub cast_origin: Vector3,
@@ -70,6 +204,10 @@ pub struct TnuaProximitySensor {
     pub cast_direction: Dir3,
     /// Tnua will update this field according to its need. The backend only needs to read it.
     pub cast_range: Float,
+    /// The result of the cast, which the backend should write.
+    ///
+    /// This should be the _first_ tangible collider hit within the
+    /// [`cast_range`](Self::cast_range), or `None` if nothing was hit.
     pub output: Option<TnuaProximitySensorOutput>,
 
     /// Used to prevent collision with obstacles the character squeezed into sideways.
@@ -106,7 +244,8 @@ impl Default for TnuaProximitySensor {
 }
 
 /// Information from [`TnuaProximitySensor`] that have detected another collider.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaProximitySensorOutput {
     /// The entity of the collider detected by the ray.
     pub entity: Entity,
@@ -121,19 +260,37 @@ pub struct TnuaProximitySensorOutput {
     /// rotation speed in radians per second. Can be extracted from a quaternion using
     /// [`Quaternion::xyz`].
     pub entity_angvel: Vector3,
+    /// The friction coefficient of the detected collider, if the physics backend provides it.
+    pub entity_friction: Option<Float>,
+    /// The data of a [`TnuaSurfaceUserData`] component on the detected entity, if it has one.
+    pub entity_user_data: Option<u64>,
 }
 
+/// Add this component to an entity with a collider to attach data to it, which proximity sensors
+/// that detect it will copy to their [output](TnuaProximitySensorOutput::entity_user_data).
+///
+/// This allows systems that use the sensor output - e.g. for choosing footstep sounds by the
+/// surface type - to avoid querying the detected entity.
+#[derive(Component, Default, Debug, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct TnuaSurfaceUserData(pub u64);
+
 /// Represents a change to velocity (linear or angular)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaVelChange {
-    // The part of the velocity change that gets multiplied by the frame duration.
-    //
-    // In Rapier, this is applied using `ExternalForce` so that the simulation will apply in
-    // smoothly over time and won't be sensitive to frame rate.
+    /// The part of the velocity change that gets multiplied by the frame duration.
+    ///
+    /// This should be applied by the physics backend as an ongoing force (multiplied by the mass),
+    /// so that the simulation will apply it smoothly over time and won't be sensitive to frame
+    /// rate. In Rapier, for example, this is applied using `ExternalForce`. The backend should
+    /// replace (not accumulate) the force it applied in the previous frame.
     pub acceleration: Vector3,
-    // The part of the velocity change that gets added to the velocity as-is.
-    //
-    // In Rapier, this is added directly to the `Velocity` component.
+    /// The part of the velocity change that gets added to the velocity as-is.
+    ///
+    /// This should be added directly to the velocity of the rigid body, once. In Rapier, this is
+    /// added directly to the `Velocity` component.
     pub boost: Vector3,
 }
 
@@ -194,12 +351,17 @@ impl AddAssign for TnuaVelChange {
 /// Instructions on how to move forces to the rigid body.
 ///
 /// The physics backend is responsible for reading this component during
-/// [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors) and apply the forces to the
-/// rigid body.
+/// [`TnuaPipelineStages::Motors`](crate::TnuaPipelineStages::Motors) and apply the forces to the
+/// rigid body. Non-finite values should be ignored. When the [`TnuaToggle`] is not
+/// [`Enabled`](TnuaToggle::Enabled), the motor should not be applied at all - and unless it is
+/// [`SuspendedKeepState`](TnuaToggle::SuspendedKeepState), the forces from the last time it was
+/// applied should be removed.
 ///
 /// This documentation uses the term "forces", but in fact these numbers ignore mass and are
 /// applied directly to the velocity.
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
 pub struct TnuaMotor {
     /// How much velocity to add to the rigid body in the current frame.
     pub lin: TnuaVelChange,
@@ -219,10 +381,20 @@ pub struct TnuaMotor {
 /// See <https://github.com/idanarye/bevy-tnua/wiki/Jump-fall-Through-Platforms>
 ///
 /// See `TnuaSimpleFallThroughPlatformsHelper`.
-#[derive(Component, Default, Debug)]
+///
+/// The physics backend clears and refills the list every frame, so it only allocates when more
+/// ghost platforms are detected than ever before. In levels with many ghost platforms stacked on
+/// top of each other, use [`with_capacity`](Self::with_capacity) to avoid these allocations too.
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
 pub struct TnuaGhostSensor(pub Vec<TnuaProximitySensorOutput>);
 
 impl TnuaGhostSensor {
+    /// Create a ghost sensor that can hold `capacity` ghost platforms without allocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &TnuaProximitySensorOutput> {
         self.0.iter()
     }
@@ -237,5 +409,56 @@ impl TnuaGhostSensor {
 /// See <https://github.com/idanarye/bevy-tnua/wiki/Jump-fall-Through-Platforms>
 ///
 /// See `TnuaSimpleFallThroughPlatformsHelper`.
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
 pub struct TnuaGhostPlatform;
+
+/// Add this component to a sensor entity to make its [`TnuaProximitySensor`] ignore some of the
+/// entities it would have otherwise detected - e.g. teammates or trigger volumes.
+///
+/// This is applied in addition to the physics backend's own filtering (which is usually based on
+/// collision groups/layers and can be configured with backend specific components). An ignored
+/// entity is treated as if it is not there, so the sensor will detect whatever is behind it.
+#[derive(Component, Default, Clone, Reflect, VisitEntities, VisitEntitiesMut)]
+#[reflect(Component, MapEntities)]
+pub struct TnuaSensorFilter {
+    /// Entities that the sensor should ignore.
+    pub excluded_entities: Vec<Entity>,
+
+    /// A function that gets called for every entity the sensor hits and was not excluded
+    /// otherwise. The sensor will ignore the entity if it returns `false`.
+    #[reflect(ignore)]
+    #[visit_entities(ignore)]
+    pub predicate: Option<Arc<dyn Send + Sync + Fn(Entity) -> bool>>,
+}
+
+impl TnuaSensorFilter {
+    /// A filter that ignores the given entities.
+    pub fn excluding(entities: impl IntoIterator<Item = Entity>) -> Self {
+        Self {
+            excluded_entities: entities.into_iter().collect(),
+            predicate: None,
+        }
+    }
+
+    /// Set the [`predicate`](Self::predicate).
+    pub fn with_predicate(
+        mut self,
+        predicate: impl 'static + Send + Sync + Fn(Entity) -> bool,
+    ) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Check if the sensor is allowed to detect an entity.
+    pub fn allows(&self, entity: Entity) -> bool {
+        if self.excluded_entities.contains(&entity) {
+            return false;
+        }
+        if let Some(predicate) = self.predicate.as_ref() {
+            predicate(entity)
+        } else {
+            true
+        }
+    }
+}
@@ -27,6 +27,9 @@
 //!       [`TnuaSubservientSensor`](subservient_sensors::TnuaSubservientSensor) component, the
 //!       "owner entity" is defined as the `owner_entity` field from that component and not the
 //!       entity the sensor component is attached to.
+//!     * If the sensor has the [`TnuaSensorFilter`](data_for_backends::TnuaSensorFilter)
+//!       component, colliders of entities that the filter does not
+//!       [allow](data_for_backends::TnuaSensorFilter::allows) should be ignored.
 //!     * The detection should be done with a ray cast, unless the sensor is configured to cast a
 //!       shape instead. Such configuration is done with component, defined by the integration
 //!       crate, that specifies the shape to cast in a way the integration crate can pass on to the
@@ -60,6 +63,14 @@
 //! body and a collider, so they should not go in that bundle, but if the crate needs things users
 //! rarely think about - for example, bevy_rapier's `ReadMassProperties` - then these components
 //! should go in that bundle.
+//!
+//! There is no trait to implement - the contract between Tnua and the integration crate is
+//! entirely made of the components in [`data_for_backends`] (and
+//! [`TnuaSubservientSensor`](subservient_sensors::TnuaSubservientSensor)) and the
+//! [`TnuaPipelineStages`] the systems that read and write them are placed in. This means that an
+//! integration for a custom or in-house physics engine can be written in the exact same way as the
+//! official integration crates, and the official crates (e.g. bevy-tnua-rapier3d) can be used as
+//! references.
 use bevy::prelude::*;
 
 pub mod data_for_backends;
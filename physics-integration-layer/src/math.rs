@@ -175,3 +175,31 @@ impl AsF32 for DQuat {
         self.as_quat()
     }
 }
+
+/// Scalar math functions that can give different results on different platforms.
+///
+/// With the `libm` feature these are implemented with [`libm`](https://docs.rs/libm) (which also
+/// gets enabled for Bevy's vector math), so that they give the same results everywhere.
+pub mod ops {
+    use super::Float;
+
+    /// Raise `x` to the power of `y`.
+    pub fn powf(x: Float, y: Float) -> Float {
+        #[cfg(all(feature = "libm", feature = "f64"))]
+        return libm::pow(x, y);
+        #[cfg(all(feature = "libm", not(feature = "f64")))]
+        return libm::powf(x, y);
+        #[cfg(not(feature = "libm"))]
+        return x.powf(y);
+    }
+
+    /// The tangent of `x` (in radians).
+    pub fn tan(x: Float) -> Float {
+        #[cfg(all(feature = "libm", feature = "f64"))]
+        return libm::tan(x);
+        #[cfg(all(feature = "libm", not(feature = "f64")))]
+        return libm::tanf(x);
+        #[cfg(not(feature = "libm"))]
+        return x.tan();
+    }
+}
@@ -1,6 +1,9 @@
+use bevy::ecs::entity::{VisitEntities, VisitEntitiesMut};
+use bevy::ecs::reflect::ReflectMapEntities;
 use bevy::prelude::*;
 
-#[derive(Component)]
+#[derive(Component, Clone, Reflect, VisitEntities, VisitEntitiesMut)]
+#[reflect(Component, MapEntities)]
 pub struct TnuaSubservientSensor {
     pub owner_entity: Entity,
 }
@@ -7,16 +7,22 @@
 //! * Optionally: Add [`TnuaRapier2dSensorShape`] to the sensor entities. This means the entity of
 //!   the characters controlled by Tnua, but also other things like the entity generated by
 //!   `TnuaCrouchEnforcer`, that can be affected with a closure.
+//! * Optionally: Add [`TnuaRapier2dSensorCollisionGroups`] to sensor entities that should use
+//!   different collision groups than their owner entity's collider.
+//! * Optionally: Add [`TnuaRapier2dKinematic`] to characters with kinematic rigid bodies.
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
-use bevy::utils::HashSet;
 use bevy_rapier2d::prelude::*;
 use bevy_rapier2d::rapier;
 use bevy_rapier2d::rapier::prelude::InteractionGroups;
 
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostPlatform;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostSensor;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaSensorFilter;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaSleepState;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaSurfaceUserData;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaToggle;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaUpdateTimer;
 use bevy_tnua_physics_integration_layer::data_for_backends::{
     TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker,
 };
@@ -66,7 +72,7 @@ impl Plugin for TnuaRapier2dPlugin {
         );
         app.add_systems(
             self.schedule,
-            apply_motors_system.in_set(TnuaPipelineStages::Motors),
+            (apply_motors_system, apply_kinematic_motors_system).in_set(TnuaPipelineStages::Motors),
         );
     }
 }
@@ -83,33 +89,115 @@ pub struct TnuaRapier2dIOBundle {
 }
 
 /// Add this component to make [`TnuaProximitySensor`] cast a shape instead of a ray.
+///
+/// A single ray can miss the ground when the character stands half-off a ledge or on a platform
+/// narrower than its collider, making Tnua think the character is airborne. A flat shape about as
+/// wide as the collider fixes that. Make it a little bit smaller than the collider, so that when
+/// the character presses against a wall the casted shape won't hit that wall:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_rapier2d::prelude::*;
+/// # use bevy_tnua_rapier2d::*;
+/// # let mut commands: Commands = panic!();
+/// # let mut cmd = commands.spawn_empty();
+/// // A capsule with a radius of 0.5:
+/// cmd.insert(Collider::capsule_y(0.5, 0.5));
+/// cmd.insert(TnuaRapier2dSensorShape(Collider::cuboid(0.49, 0.0)));
+/// ```
 #[derive(Component)]
 pub struct TnuaRapier2dSensorShape(pub Collider);
 
+/// Add this component to make [`TnuaProximitySensor`] use these collision groups instead of the
+/// ones of its owner entity's collider.
+#[derive(Component)]
+pub struct TnuaRapier2dSensorCollisionGroups(pub CollisionGroups);
+
+/// Add this component to characters with a kinematic rigid body, for games that cannot use
+/// dynamic bodies.
+///
+/// Kinematic bodies are not affected by forces or gravity, so when this component is present Tnua
+/// integrates the character's velocity by itself (applying the gravity from the
+/// `RapierConfiguration`) and uses it to move the character:
+///
+/// * If the entity has a [`KinematicCharacterController`] (with a
+///   `RigidBody::KinematicPositionBased` body), the movement is passed to it as its desired
+///   `translation`, and the velocity is then updated from its
+///   [`KinematicCharacterControllerOutput`] - so that walls and other obstacles can stop the
+///   character. Its `snap_to_ground` should be set to `None`, because it conflicts
+///   with Tnua's floating mechanism. Note that `KinematicCharacterController` does not rotate the
+///   character, so the rotation is applied directly to the [`Transform`].
+/// * Otherwise, the velocity is written to the [`Velocity`] component, which moves
+///   `RigidBody::KinematicVelocityBased` bodies. Such bodies will not be stopped by obstacles.
+///
+/// Moving platforms carry the character the same way they do with dynamic bodies, since the basis
+/// adds the platform's velocity to the character's velocity.
+#[derive(Component, Default, Debug)]
+pub struct TnuaRapier2dKinematic {
+    velocity: Vec3,
+    angvel: Vec3,
+    pending_frame_duration: f32,
+}
+
+impl TnuaRapier2dKinematic {
+    /// The velocity integrated by Tnua for the kinematic body.
+    pub fn velocity(&self) -> Vec3 {
+        self.velocity
+    }
+
+    /// The angular velocity integrated by Tnua for the kinematic body.
+    pub fn angvel(&self) -> Vec3 {
+        self.angvel
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn update_rigid_body_trackers_system(
     rapier_config: Single<&RapierConfiguration>,
     mut query: Query<(
         &GlobalTransform,
         &Velocity,
         &mut TnuaRigidBodyTracker,
+        Option<(
+            &mut TnuaRapier2dKinematic,
+            Option<&KinematicCharacterControllerOutput>,
+        )>,
         Option<&TnuaToggle>,
     )>,
 ) {
-    for (transform, velocity, mut tracker, tnua_toggle) in query.iter_mut() {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled => continue,
-            TnuaToggle::SenseOnly => {}
-            TnuaToggle::Enabled => {}
-        }
-        let (_, rotation, translation) = transform.to_scale_rotation_translation();
-        *tracker = TnuaRigidBodyTracker {
-            translation,
-            rotation,
-            velocity: velocity.linvel.extend(0.0),
-            angvel: Vec3::new(0.0, 0.0, velocity.angvel),
-            gravity: rapier_config.gravity.extend(0.0),
-        };
-    }
+    query.par_iter_mut().for_each(
+        |(transform, velocity, mut tracker, kinematic, tnua_toggle)| {
+            match tnua_toggle.copied().unwrap_or_default() {
+                TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => return,
+                TnuaToggle::SenseOnly => {}
+                TnuaToggle::Enabled => {}
+            }
+            let (_, rotation, translation) = transform.to_scale_rotation_translation();
+            let (linvel, angvel) = if let Some((mut kinematic, output)) = kinematic {
+                if let Some(output) = output {
+                    if 0.0 < kinematic.pending_frame_duration {
+                        kinematic.velocity = (output.effective_translation
+                            / kinematic.pending_frame_duration)
+                            .extend(0.0);
+                        kinematic.pending_frame_duration = 0.0;
+                    }
+                }
+                (kinematic.velocity, kinematic.angvel)
+            } else {
+                (
+                    velocity.linvel.extend(0.0),
+                    Vec3::new(0.0, 0.0, velocity.angvel),
+                )
+            };
+            *tracker = TnuaRigidBodyTracker {
+                translation,
+                rotation,
+                velocity: linvel,
+                angvel,
+                gravity: rapier_config.gravity.extend(0.0),
+            };
+        },
+    );
 }
 
 fn get_collider(
@@ -130,12 +218,17 @@ fn update_proximity_sensors_system(
         &GlobalTransform,
         &mut TnuaProximitySensor,
         Option<&TnuaRapier2dSensorShape>,
+        Option<&TnuaSensorFilter>,
+        Option<&TnuaRapier2dSensorCollisionGroups>,
         Option<&mut TnuaGhostSensor>,
         Option<&TnuaSubservientSensor>,
         Option<&TnuaToggle>,
+        Option<&TnuaSleepState>,
+        Option<&TnuaUpdateTimer>,
     )>,
     ghost_platforms_query: Query<(), With<TnuaGhostPlatform>>,
     other_object_query_query: Query<(&GlobalTransform, &Velocity)>,
+    user_data_query: Query<&TnuaSurfaceUserData>,
 ) {
     query.par_iter_mut().for_each(
         |(
@@ -144,15 +237,24 @@ fn update_proximity_sensors_system(
             transform,
             mut sensor,
             shape,
+            filter,
+            collision_groups,
             mut ghost_sensor,
             subservient,
             tnua_toggle,
+            sleep_state,
+            update_timer,
         )| {
             match tnua_toggle.copied().unwrap_or_default() {
-                TnuaToggle::Disabled => return,
+                TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => return,
                 TnuaToggle::SenseOnly => {}
                 TnuaToggle::Enabled => {}
             }
+            if sleep_state.copied().unwrap_or_default() == TnuaSleepState::Asleep
+                || update_timer.is_some_and(|update_timer| !update_timer.is_update_frame())
+            {
+                return;
+            }
 
             let Some(rapier_context) = rapier_context_query.try_context(rapier_context_entity_link)
             else {
@@ -190,19 +292,28 @@ fn update_proximity_sensors_system(
             } else {
                 owner_solver_groups = InteractionGroups::all();
             }
-
-            let mut already_visited_ghost_entities = HashSet::<Entity>::default();
+            if let Some(TnuaRapier2dSensorCollisionGroups(collision_groups)) = collision_groups {
+                query_filter.groups = Some(*collision_groups);
+            }
 
             let has_ghost_sensor = ghost_sensor.is_some();
 
+            // With a ghost sensor, the ghost platforms that were already visited are the ones it
+            // detected so far - so there is no need to collect them in a separate set.
             let do_cast = |cast_range_skip: f32,
-                           already_visited_ghost_entities: &HashSet<Entity>|
+                           already_visited_ghosts: &[TnuaProximitySensorOutput]|
              -> Option<CastResult> {
                 let predicate = |other_entity: Entity| {
+                    if filter.is_some_and(|filter| !filter.allows(other_entity)) {
+                        return false;
+                    }
                     if let Some(other_collider) = get_collider(rapier_context, other_entity) {
                         if !other_collider.solver_groups().test(owner_solver_groups) {
                             if has_ghost_sensor && ghost_platforms_query.contains(other_entity) {
-                                if already_visited_ghost_entities.contains(&other_entity) {
+                                if already_visited_ghosts
+                                    .iter()
+                                    .any(|ghost| ghost.entity == other_entity)
+                                {
                                     return false;
                                 }
                             } else {
@@ -291,8 +402,12 @@ fn update_proximity_sensors_system(
                     proximity,
                     intersection_point,
                     normal,
-                }) = do_cast(cast_range_skip, &already_visited_ghost_entities)
-                {
+                }) = do_cast(
+                    cast_range_skip,
+                    ghost_sensor
+                        .as_ref()
+                        .map_or(&[][..], |ghost_sensor| &ghost_sensor.0[..]),
+                ) {
                     let entity_linvel;
                     let entity_angvel;
                     if let Ok((entity_transform, entity_velocity)) =
@@ -319,10 +434,12 @@ fn update_proximity_sensors_system(
                         normal,
                         entity_linvel,
                         entity_angvel,
+                        entity_friction: get_collider(rapier_context, entity)
+                            .map(|collider| collider.friction()),
+                        entity_user_data: user_data_query.get(entity).ok().map(|data| data.0),
                     };
                     if ghost_platforms_query.contains(entity) {
                         cast_range_skip = proximity;
-                        already_visited_ghost_entities.insert(entity);
                         if let Some(ghost_sensor) = ghost_sensor.as_mut() {
                             ghost_sensor.0.push(sensor_output);
                         }
@@ -337,36 +454,129 @@ fn update_proximity_sensors_system(
     );
 }
 
+#[allow(clippy::type_complexity)]
 fn apply_motors_system(
+    mut query: Query<
+        (
+            &TnuaMotor,
+            &mut Velocity,
+            &ReadMassProperties,
+            &mut ExternalForce,
+            Option<&TnuaToggle>,
+            Option<&TnuaSleepState>,
+            Option<&TnuaUpdateTimer>,
+        ),
+        Without<TnuaRapier2dKinematic>,
+    >,
+) {
+    query.par_iter_mut().for_each(
+        |(
+            motor,
+            mut velocity,
+            mass_properties,
+            mut external_force,
+            tnua_toggle,
+            sleep_state,
+            update_timer,
+        )| {
+            match tnua_toggle.copied().unwrap_or_default() {
+                TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
+                    *external_force = Default::default();
+                    return;
+                }
+                // Leave the forces from the last time the motor was applied in place.
+                TnuaToggle::SuspendedKeepState => return,
+                TnuaToggle::Enabled => {}
+            }
+            // Leave the forces from the last time the motor was applied in place.
+            if sleep_state.copied().unwrap_or_default() != TnuaSleepState::Awake
+                || update_timer.is_some_and(|update_timer| !update_timer.is_update_frame())
+            {
+                return;
+            }
+            if motor.lin.boost.is_finite() {
+                velocity.linvel += motor.lin.boost.truncate();
+            }
+            if motor.lin.acceleration.is_finite() {
+                external_force.force =
+                    motor.lin.acceleration.truncate() * mass_properties.get().mass;
+            }
+            if motor.ang.boost.is_finite() {
+                velocity.angvel += motor.ang.boost.z;
+            }
+            if motor.ang.acceleration.is_finite() {
+                external_force.torque =
+                    motor.ang.acceleration.z * mass_properties.get().principal_inertia;
+            }
+        },
+    );
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_kinematic_motors_system(
+    time: Res<Time>,
+    rapier_config: Single<&RapierConfiguration>,
     mut query: Query<(
         &TnuaMotor,
+        &mut TnuaRapier2dKinematic,
         &mut Velocity,
-        &ReadMassProperties,
-        &mut ExternalForce,
+        &mut Transform,
+        Option<&mut KinematicCharacterController>,
         Option<&TnuaToggle>,
+        Option<&TnuaSleepState>,
+        Option<&TnuaUpdateTimer>,
     )>,
 ) {
-    for (motor, mut velocity, mass_properties, mut external_force, tnua_toggle) in query.iter_mut()
-    {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
-                *external_force = Default::default();
+    let frame_duration = time.delta_secs();
+    if frame_duration == 0.0 {
+        return;
+    }
+    query.par_iter_mut().for_each(
+        |(
+            motor,
+            mut kinematic,
+            mut velocity,
+            mut transform,
+            character_controller,
+            tnua_toggle,
+            sleep_state,
+            update_timer,
+        )| {
+            match tnua_toggle.copied().unwrap_or_default() {
+                TnuaToggle::Disabled | TnuaToggle::SenseOnly | TnuaToggle::SuspendedKeepState => {
+                    return
+                }
+                TnuaToggle::Enabled => {}
+            }
+            // Keep the velocity from the last time the motor was applied.
+            if sleep_state.copied().unwrap_or_default() != TnuaSleepState::Awake {
                 return;
             }
-            TnuaToggle::Enabled => {}
-        }
-        if motor.lin.boost.is_finite() {
-            velocity.linvel += motor.lin.boost.truncate();
-        }
-        if motor.lin.acceleration.is_finite() {
-            external_force.force = motor.lin.acceleration.truncate() * mass_properties.get().mass;
-        }
-        if motor.ang.boost.is_finite() {
-            velocity.angvel += motor.ang.boost.z;
-        }
-        if motor.ang.acceleration.is_finite() {
-            external_force.torque =
-                motor.ang.acceleration.z * mass_properties.get().principal_inertia;
-        }
-    }
+            // Between updates only the accelerations are applied, just like the forces of dynamic
+            // bodies stay in place.
+            let is_update_frame = update_timer.is_none_or(TnuaUpdateTimer::is_update_frame);
+            if is_update_frame && motor.lin.boost.is_finite() {
+                kinematic.velocity += motor.lin.boost;
+            }
+            if motor.lin.acceleration.is_finite() {
+                kinematic.velocity += motor.lin.acceleration * frame_duration;
+            }
+            kinematic.velocity += rapier_config.gravity.extend(0.0) * frame_duration;
+            if is_update_frame && motor.ang.boost.is_finite() {
+                kinematic.angvel += motor.ang.boost;
+            }
+            if motor.ang.acceleration.is_finite() {
+                kinematic.angvel += motor.ang.acceleration * frame_duration;
+            }
+            if let Some(mut character_controller) = character_controller {
+                character_controller.translation =
+                    Some(kinematic.velocity.truncate() * frame_duration);
+                kinematic.pending_frame_duration = frame_duration;
+                transform.rotate_z(kinematic.angvel.z * frame_duration);
+            } else {
+                velocity.linvel = kinematic.velocity.truncate();
+                velocity.angvel = kinematic.angvel.z;
+            }
+        },
+    );
 }
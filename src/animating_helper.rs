@@ -1,7 +1,10 @@
 use std::mem::discriminant;
+use std::time::Duration;
 
 use bevy::prelude::*;
 
+use crate::math::Float;
+
 /// Utility for deciding which animation to play.
 ///
 /// Add `TnuaAnimatingState<State>` as a component, where `State` is a data type - usually an
@@ -81,8 +84,10 @@ use bevy::prelude::*;
 ///     }
 /// }
 /// ```
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct TnuaAnimatingState<State> {
+    #[reflect(ignore)]
     state: Option<State>,
 }
 
@@ -92,6 +97,71 @@ impl<State> Default for TnuaAnimatingState<State> {
     }
 }
 
+/// Optional information a [`TnuaAnimatingState`]'s `State` can provide for blending animations.
+///
+/// Implementing this trait allows using the
+/// [`transition_duration`](TnuaAnimatingStateDirective::transition_duration) and
+/// [`blend_weights`](TnuaAnimatingStateDirective::blend_weights) methods of
+/// [`TnuaAnimatingStateDirective`], instead of deciding these in the animating system.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use bevy_tnua::TnuaAnimatingStateBlending;
+/// # use bevy_tnua::math::{Float, Vector3};
+/// enum AnimationState {
+///     Standing,
+///     // The velocity relative to the character's forward direction
+///     Running(Vector3),
+///     Jumping,
+/// }
+///
+/// impl TnuaAnimatingStateBlending for AnimationState {
+///     fn transition_duration(&self, old_state: Option<&Self>) -> Duration {
+///         match (old_state, self) {
+///             (None, _) => Duration::ZERO,
+///             (Some(_), Self::Jumping) => Duration::from_millis(50),
+///             (Some(_), _) => Duration::from_millis(200),
+///         }
+///     }
+///
+///     fn blend_weights(&self) -> impl IntoIterator<Item = Float> {
+///         match self {
+///             // Forward, backward, left and right running animations
+///             Self::Running(velocity) => {
+///                 let direction = velocity.normalize_or_zero();
+///                 vec![
+///                     (-direction.z).max(0.0),
+///                     direction.z.max(0.0),
+///                     (-direction.x).max(0.0),
+///                     direction.x.max(0.0),
+///                 ]
+///             }
+///             _ => vec![],
+///         }
+///     }
+/// }
+/// ```
+pub trait TnuaAnimatingStateBlending {
+    /// The recommended duration of the crossfade to this state's animation.
+    ///
+    /// `old_state` is the state of the animation that was played before, or `None` if this is the
+    /// first animation to be played. Defaults to no crossfade.
+    fn transition_duration(&self, old_state: Option<&Self>) -> Duration {
+        let _ = old_state;
+        Duration::ZERO
+    }
+
+    /// Weights for blending the animations this state is made of - e.g. for a running state that
+    /// blends between running animations in different directions, or between walking and running
+    /// based on the speed.
+    ///
+    /// The order and meaning of the weights are decided by the implementor. Defaults to no
+    /// weights.
+    fn blend_weights(&self) -> impl IntoIterator<Item = Float> {
+        []
+    }
+}
+
 pub enum TnuaAnimatingStateDirective<'a, State> {
     /// The animation to play remains the same - possibly with different parameters.
     Maintain { state: &'a State },
@@ -104,6 +174,41 @@ pub enum TnuaAnimatingStateDirective<'a, State> {
     },
 }
 
+impl<'a, State> TnuaAnimatingStateDirective<'a, State> {
+    /// The state of the animation to play.
+    pub fn state(&self) -> &'a State {
+        match self {
+            Self::Maintain { state } => state,
+            Self::Alter { state, .. } => state,
+        }
+    }
+
+    /// The recommended duration of the crossfade from the old animation to the new one.
+    ///
+    /// This is always zero for [`Maintain`](Self::Maintain), since there is no new animation to
+    /// crossfade to.
+    pub fn transition_duration(&self) -> Duration
+    where
+        State: TnuaAnimatingStateBlending,
+    {
+        match self {
+            Self::Maintain { .. } => Duration::ZERO,
+            Self::Alter { old_state, state } => state.transition_duration(old_state.as_ref()),
+        }
+    }
+
+    /// The [blend weights](TnuaAnimatingStateBlending::blend_weights) of the state.
+    ///
+    /// Unlike the transition duration, these are relevant for [`Maintain`](Self::Maintain) as
+    /// well, since they usually change continuously while the same animation is played.
+    pub fn blend_weights(&self) -> impl IntoIterator<Item = Float> + use<'a, State>
+    where
+        State: TnuaAnimatingStateBlending,
+    {
+        self.state().blend_weights()
+    }
+}
+
 impl<State> TnuaAnimatingState<State> {
     /// Consider a new animation to play.
     ///
@@ -4,6 +4,7 @@ use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
 
 use std::{any::Any, time::Duration};
 
+use crate::controller::{TnuaConveyor, TnuaSurfaceTraction};
 use crate::{TnuaMotor, TnuaProximitySensor, TnuaRigidBodyTracker};
 
 /// Various data passed to [`TnuaBasis::apply`].
@@ -18,7 +19,29 @@ pub struct TnuaBasisContext<'a> {
     pub proximity_sensor: &'a TnuaProximitySensor,
 
     /// The direction considered as "up".
+    ///
+    /// This is the opposite of the gravity, unless overridden with
+    /// [`TnuaUpDirection`](crate::controller::TnuaUpDirection).
     pub up_direction: Dir3,
+
+    /// The traction of the ground the character stands on, from its [`TnuaSurfaceTraction`]
+    /// component (or the default traction if it does not have one).
+    pub surface_traction: TnuaSurfaceTraction,
+
+    /// The [`TnuaExternalAcceleration`](crate::controller::TnuaExternalAcceleration) of the
+    /// character.
+    ///
+    /// The controller adds it to the motor after the basis and the action are applied, so they
+    /// should not add it themselves - but they may account for it, e.g. so that they will not
+    /// correct the velocity it causes.
+    pub external_acceleration: Vector3,
+
+    /// The [`TnuaConveyor`] of the ground the character stands on, if it has one.
+    ///
+    /// Its velocity is already added to the
+    /// [`entity_linvel`](crate::TnuaProximitySensorOutput::entity_linvel) of the
+    /// [`proximity_sensor`](Self::proximity_sensor)'s output.
+    pub ground_conveyor: Option<TnuaConveyor>,
 }
 
 /// The main movement command of a character.
@@ -32,7 +55,11 @@ pub struct TnuaBasisContext<'a> {
 /// overwritten each frame by the controller system of the game code. Configuration is considered
 /// as part of the input. If the basis needs to persist data between frames it must keep it in its
 /// [state](Self::State).
-pub trait TnuaBasis: 'static + Send + Sync {
+///
+/// Both the input and the state must be [`Clone`], so that the
+/// [`TnuaController`](crate::controller::TnuaController) can be cloned - e.g. for storing
+/// snapshots in a rollback netcode.
+pub trait TnuaBasis: 'static + Send + Sync + Clone {
     /// The default name of the basis.
     ///
     /// [Once `type_name` becomes `const`](https://github.com/rust-lang/rust/issues/63084), this
@@ -50,7 +77,7 @@ pub trait TnuaBasis: 'static + Send + Sync {
     ///
     /// 3. Inspect the basis from game code systems, like an animation controlling system that
     ///    needs to know which animation to play based on the basis' current state.
-    type State: Default + Send + Sync;
+    type State: Default + Send + Sync + Clone;
 
     /// This is where the basis affects the character's motion.
     ///
@@ -132,8 +159,18 @@ pub trait DynamicBasis: Send + Sync + Any + 'static {
 
     #[doc(hidden)]
     fn violate_coyote_time(&mut self);
+
+    #[doc(hidden)]
+    fn clone_box(&self) -> Box<dyn DynamicBasis>;
+}
+
+impl Clone for Box<dyn DynamicBasis> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
+#[derive(Clone)]
 pub(crate) struct BoxableBasis<B: TnuaBasis> {
     pub(crate) input: B,
     pub(crate) state: B::State,
@@ -188,6 +225,10 @@ impl<B: TnuaBasis> DynamicBasis for BoxableBasis<B> {
     fn violate_coyote_time(&mut self) {
         self.input.violate_coyote_time(&mut self.state)
     }
+
+    fn clone_box(&self) -> Box<dyn DynamicBasis> {
+        Box::new(self.clone())
+    }
 }
 
 /// Various data passed to [`TnuaAction::apply`].
@@ -204,6 +245,18 @@ pub struct TnuaActionContext<'a> {
     /// The direction considered as "up".
     pub up_direction: Dir3,
 
+    /// The traction of the ground the character stands on. See
+    /// [`TnuaBasisContext::surface_traction`].
+    pub surface_traction: TnuaSurfaceTraction,
+
+    /// The external acceleration applied to the character. See
+    /// [`TnuaBasisContext::external_acceleration`].
+    pub external_acceleration: Vector3,
+
+    /// The conveyor of the ground the character stands on. See
+    /// [`TnuaBasisContext::ground_conveyor`].
+    pub ground_conveyor: Option<TnuaConveyor>,
+
     /// An accessor to the currently active basis.
     pub basis: &'a dyn DynamicBasis,
 }
@@ -227,6 +280,9 @@ impl<'a> TnuaActionContext<'a> {
             tracker: self.tracker,
             proximity_sensor: self.proximity_sensor,
             up_direction: self.up_direction,
+            surface_traction: self.surface_traction,
+            external_acceleration: self.external_acceleration,
+            ground_conveyor: self.ground_conveyor,
         }
     }
 
@@ -354,6 +410,50 @@ pub enum TnuaActionInitiationDirective {
     Allow,
 }
 
+/// Determines what happens to the momentum an action leaves the character with once the action
+/// is over. See [`TnuaAction::momentum_policy`].
+///
+/// Only the horizontal momentum is affected. The policy only prevents the basis from slowing the
+/// character down - the basis (and other actions) can still make it go faster or change its
+/// direction.
+#[derive(PartialEq, Debug, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaMomentumPolicy {
+    /// Keep the full velocity the action has left the character with, for as long as the
+    /// character is in the air.
+    ///
+    /// Note that if the action ends while the character is on the ground, this will do nothing.
+    Keep,
+
+    /// Let the basis gradually slow the character down, reaching its regular velocity after the
+    /// specified duration (in seconds).
+    Decay { duration: Float },
+
+    /// Like [`Keep`](Self::Keep), but the kept speed is limited to the specified speed.
+    Clamp { max_speed: Float },
+}
+
+/// What an action costs from the character's [`TnuaStamina`](crate::controller::TnuaStamina).
+/// See [`TnuaAction::stamina_cost`].
+#[derive(PartialEq, Debug, Clone, Copy, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaStaminaCost {
+    /// Stamina spent once, when the action starts. The action cannot start with less stamina than
+    /// this.
+    pub on_start: Float,
+
+    /// Stamina spent every second while the action is active. The action ends when the stamina
+    /// runs out.
+    pub per_second: Float,
+}
+
+impl TnuaStaminaCost {
+    /// Check if the action costs no stamina at all.
+    pub fn is_free(&self) -> bool {
+        self.on_start <= 0.0 && self.per_second <= 0.0
+    }
+}
+
 /// A character movement command for performing special actions.
 ///
 /// "Special" does not necessarily mean **that** special - even
@@ -365,7 +465,9 @@ pub enum TnuaActionInitiationDirective {
 /// overwritten each frame by the controller system of the game code - although unlike basis the
 /// input will probably be the exact same. Configuration is considered as part of the input. If the
 /// action needs to persist data between frames it must keep it in its [state](Self::State).
-pub trait TnuaAction: 'static + Send + Sync {
+///
+/// Like with [`TnuaBasis`], both the input and the state must be [`Clone`].
+pub trait TnuaAction: 'static + Send + Sync + Clone {
     /// The default name of the action.
     ///
     /// [Once `type_name` becomes `const`](https://github.com/rust-lang/rust/issues/63084), this
@@ -383,7 +485,7 @@ pub trait TnuaAction: 'static + Send + Sync {
     ///
     /// 3. Inspect the action from game code systems, like an animation controlling system that
     ///    needs to know which animation to play based on the action's current state.
-    type State: Default + Send + Sync;
+    type State: Default + Send + Sync + Clone;
 
     /// Set this to true for actions that may launch the character into the air.
     const VIOLATES_COYOTE_TIME: bool;
@@ -391,10 +493,16 @@ pub trait TnuaAction: 'static + Send + Sync {
     /// This is where the action affects the character's motion.
     ///
     /// This method gets called each frame to let the action control the [`TnuaMotor`] that will
-    /// later move the character. Note that this happens the motor was set by the basis'
-    /// [`apply`](TnuaBasis::apply). Here the action can modify some aspects of or even completely
-    /// overwrite what the basis did.
-    ///                                                              
+    /// later move the character. Note that this happens after the motor was set by the basis'
+    /// [`apply`](TnuaBasis::apply), so the `motor` already contains the basis' output. Here the
+    /// action can:
+    ///
+    /// * Add to what the basis did - e.g. `motor.lin += TnuaVelChange::boost(...)`.
+    /// * Override some aspects of it - e.g. with
+    ///   [`cancel_on_axis`](crate::TnuaVelChange::cancel_on_axis) to take over the vertical movement
+    ///   while leaving the horizontal movement to the basis.
+    /// * Completely overwrite it - e.g. `motor.lin = ...`.
+    ///
     /// It can also update the state.
     ///
     /// The returned value of this action determines whether or not the action will continue in the
@@ -413,6 +521,24 @@ pub trait TnuaAction: 'static + Send + Sync {
         0.0
     }
 
+    /// The policy for the momentum the character has when the action is over.
+    ///
+    /// When `None` (the default), the basis will bring the character to its regular velocity as
+    /// usual. When the action does set a policy, it replaces the policy of any previous action -
+    /// which allows chaining actions (e.g. dashes and jumps) to build up speed.
+    fn momentum_policy(&self) -> Option<TnuaMomentumPolicy> {
+        None
+    }
+
+    /// The stamina the action costs, if the character has a
+    /// [`TnuaStamina`](crate::controller::TnuaStamina) component.
+    ///
+    /// The controller will not start an action the character cannot afford, and will end an
+    /// action that costs stamina per second once the stamina runs out. Defaults to no cost.
+    fn stamina_cost(&self) -> TnuaStaminaCost {
+        TnuaStaminaCost::default()
+    }
+
     /// Decides whether the action can start.
     ///
     /// The difference between rejecting the action here with
@@ -446,8 +572,18 @@ pub trait DynamicAction: Send + Sync + Any + 'static {
         being_fed_for: &Stopwatch,
     ) -> TnuaActionInitiationDirective;
     fn violates_coyote_time(&self) -> bool;
+    fn momentum_policy(&self) -> Option<TnuaMomentumPolicy>;
+    fn stamina_cost(&self) -> TnuaStaminaCost;
+    fn clone_box(&self) -> Box<dyn DynamicAction>;
+}
+
+impl Clone for Box<dyn DynamicAction> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
+#[derive(Clone)]
 pub(crate) struct BoxableAction<A: TnuaAction> {
     pub(crate) input: A,
     pub(crate) state: A::State,
@@ -496,4 +632,16 @@ impl<A: TnuaAction> DynamicAction for BoxableAction<A> {
     fn violates_coyote_time(&self) -> bool {
         A::VIOLATES_COYOTE_TIME
     }
+
+    fn momentum_policy(&self) -> Option<TnuaMomentumPolicy> {
+        self.input.momentum_policy()
+    }
+
+    fn stamina_cost(&self) -> TnuaStaminaCost {
+        self.input.stamina_cost()
+    }
+
+    fn clone_box(&self) -> Box<dyn DynamicAction> {
+        Box::new(self.clone())
+    }
 }
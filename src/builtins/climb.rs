@@ -0,0 +1,226 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::util::rotation_arc_around_axis;
+use crate::TnuaBasisContext;
+use crate::{TnuaBasis, TnuaVelChange};
+
+/// Marks a collider as a surface that characters can climb on using [`TnuaBuiltinClimb`].
+///
+/// Tnua itself does not act upon this component - it is up to the game code to check for it
+/// (typically on the [`entity`](crate::TnuaProximitySensorOutput::entity) detected by a
+/// [`TnuaWallSensor`](crate::control_helpers::TnuaWallSensor)) before switching to the climb
+/// basis.
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
+pub struct TnuaClimbable;
+
+/// A [basis](TnuaBasis) for climbing on walls, ladders, vines, etc.
+///
+/// The climb basis sticks the character to the surface and constrains its movement to the
+/// surface's plane. Gravity does not affect the character while it is climbing.
+///
+/// The surface is not detected by the basis itself. The game code needs to feed it with the
+/// surface's [`surface_normal`](Self::surface_normal) and
+/// [`surface_distance`](Self::surface_distance) every frame - typically from a
+/// [`TnuaWallSensor`](crate::control_helpers::TnuaWallSensor) that detected an entity with the
+/// [`TnuaClimbable`] component:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::{TnuaBuiltinClimb, TnuaClimbable};
+/// # use bevy_tnua::control_helpers::TnuaWallSensor;
+/// # use bevy_tnua::math::Vector3;
+/// fn climbing_control_system(
+///     mut query: Query<(&mut TnuaController, &TnuaWallSensor)>,
+///     climbable_query: Query<(), With<TnuaClimbable>>,
+/// ) {
+///     for (mut controller, wall_sensor) in query.iter_mut() {
+///         let wall = wall_sensor
+///             .closest_wall()
+///             .filter(|wall| climbable_query.contains(wall.sensor_output.entity));
+///         if let Some(wall) = wall {
+///             controller.basis(TnuaBuiltinClimb {
+///                 desired_velocity: Vector3::Y * 3.0,
+///                 surface_normal: Some(wall.sensor_output.normal),
+///                 surface_distance: wall.sensor_output.proximity,
+///                 ..Default::default()
+///             });
+///         } else {
+///             controller.basis(TnuaBuiltinWalk {
+///                 float_height: 1.5,
+///                 ..Default::default()
+///             });
+///         }
+///     }
+/// }
+/// ```
+///
+/// To detach, simply switch back to another basis (like
+/// [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk)). To jump off the surface, switch to
+/// another basis and feed [`TnuaBuiltinWallJump`](crate::builtins::TnuaBuiltinWallJump) with
+/// the surface normal.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinClimb {
+    /// The direction (in the world space) and speed to accelerate to.
+    ///
+    /// Only the part of this vector that lies on the surface's plane is used.
+    pub desired_velocity: Vector3,
+
+    /// The normal of the surface the character climbs on.
+    ///
+    /// When `None`, the character is considered detached - it will not stick to anything, and
+    /// gravity will affect it.
+    pub surface_normal: Option<Dir3>,
+
+    /// The distance from the character's center to the surface.
+    pub surface_distance: Float,
+
+    /// The distance from the surface the character's center should be at while it climbs.
+    ///
+    /// Set it to be a bit higher than the distance from the center of the entity to the side of
+    /// the collider that faces the surface.
+    pub cling_distance: Float,
+
+    /// The strength of the force that keeps the character at the
+    /// [`cling_distance`](Self::cling_distance) from the surface.
+    pub cling_strength: Float,
+
+    /// A force that slows down the character's motion toward or away from the surface.
+    ///
+    /// The actual dampening is in direct linear relationship to the velocity it tries to dampen.
+    pub cling_dampening: Float,
+
+    /// If the [`surface_distance`](Self::surface_distance) is larger than this value, the
+    /// character is considered detached from the surface.
+    pub detach_distance: Float,
+
+    /// The maximum acceleration when moving along the surface.
+    pub acceleration: Float,
+
+    /// Rotate the character so that its negative Z will face the surface.
+    pub face_surface: bool,
+
+    /// The maximum angular velocity used for facing the surface. If you find the character turns
+    /// too slowly (or too fast), try changing this value.
+    pub turning_angvel: Float,
+}
+
+impl Default for TnuaBuiltinClimb {
+    fn default() -> Self {
+        Self {
+            desired_velocity: Vector3::ZERO,
+            surface_normal: None,
+            surface_distance: 0.0,
+            cling_distance: 0.6,
+            cling_strength: 40.0,
+            cling_dampening: 0.4,
+            detach_distance: 1.0,
+            acceleration: 40.0,
+            face_surface: true,
+            turning_angvel: 10.0,
+        }
+    }
+}
+
+impl TnuaBasis for TnuaBuiltinClimb {
+    const NAME: &'static str = "TnuaBuiltinClimb";
+    type State = TnuaBuiltinClimbState;
+
+    fn apply(&self, state: &mut Self::State, ctx: TnuaBasisContext, motor: &mut crate::TnuaMotor) {
+        let up = ctx.up_direction.adjust_precision();
+        state.effective_velocity = ctx.tracker.velocity;
+
+        let Some(surface_normal) = self
+            .surface_normal
+            .filter(|_| self.surface_distance <= self.detach_distance)
+        else {
+            state.attached = None;
+            motor.lin = TnuaVelChange::ZERO;
+            motor.ang = TnuaVelChange::ZERO;
+            return;
+        };
+        let normal = surface_normal.adjust_precision();
+        state.attached = Some(TnuaBuiltinClimbAttachment {
+            surface_normal,
+            cling_offset: (self.surface_distance - self.cling_distance) * normal,
+        });
+
+        let velocity_on_surface = ctx.tracker.velocity.reject_from(normal);
+        let desired_velocity = self.desired_velocity.reject_from(normal);
+        let acceleration = ((desired_velocity - velocity_on_surface) / ctx.frame_duration)
+            .clamp_length_max(self.acceleration);
+
+        let spring_offset = self.surface_distance - self.cling_distance;
+        let velocity_away_from_surface = ctx.tracker.velocity.dot(normal);
+
+        motor.lin = TnuaVelChange {
+            acceleration: acceleration
+                - normal * spring_offset * self.cling_strength
+                - ctx.tracker.gravity,
+            boost: -normal * velocity_away_from_surface * self.cling_dampening,
+        };
+
+        // Turning
+
+        let desired_angvel = if self.face_surface {
+            let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
+            let rotation_along_up_axis =
+                rotation_arc_around_axis(ctx.up_direction, current_forward, -normal).unwrap_or(0.0);
+            (rotation_along_up_axis / ctx.frame_duration)
+                .clamp(-self.turning_angvel, self.turning_angvel)
+        } else {
+            0.0
+        };
+
+        let existing_angvel = ctx.tracker.angvel.dot(up);
+        motor.ang = TnuaVelChange::boost((desired_angvel - existing_angvel) * up);
+    }
+
+    fn proximity_sensor_cast_range(&self, _state: &Self::State) -> Float {
+        0.0
+    }
+
+    fn displacement(&self, state: &Self::State) -> Option<Vector3> {
+        Some(state.attached.as_ref()?.cling_offset)
+    }
+
+    fn effective_velocity(&self, state: &Self::State) -> Vector3 {
+        state.effective_velocity
+    }
+
+    fn vertical_velocity(&self, _state: &Self::State) -> Float {
+        0.0
+    }
+
+    fn neutralize(&mut self) {
+        self.desired_velocity = Vector3::ZERO;
+    }
+
+    fn is_airborne(&self, state: &Self::State) -> bool {
+        state.attached.is_none()
+    }
+
+    fn violate_coyote_time(&self, _state: &mut Self::State) {}
+}
+
+#[derive(Default, Clone, Debug, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinClimbState {
+    /// Information about the surface the character climbs on. `None` if the character is
+    /// detached.
+    pub attached: Option<TnuaBuiltinClimbAttachment>,
+    effective_velocity: Vector3,
+}
+
+#[derive(Clone, Debug, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinClimbAttachment {
+    /// The normal of the surface the character climbs on.
+    pub surface_normal: Dir3,
+    /// The displacement of the character from where the basis wants it to be relative to the
+    /// surface.
+    pub cling_offset: Vector3,
+}
@@ -18,11 +18,18 @@ use super::TnuaBuiltinWalk;
 /// (compared to its regular float offset while standing). That field should typically have a
 /// negative value.
 ///
+/// If the character moves faster than [`slide_speed_threshold`](Self::slide_speed_threshold) when
+/// the crouch starts, it'll slide instead: the speed decays (but going downhill accelerates it)
+/// and the input is ignored until the speed drops below
+/// [`slide_exit_speed`](Self::slide_exit_speed), and then the character either crouches (if the
+/// action is still fed) or stands up.
+///
 /// If the player stops crouching while crawling under an obstacle, Tnua will push the character
 /// upward toward the obstacle - which will bring about undesired physics behavior (especially if
 /// the player tries to move). To prevent that, use this action together with
 /// [`TnuaCrouchEnforcer`](crate::control_helpers::TnuaCrouchEnforcer).
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaBuiltinCrouch {
     /// Controls how low the character will crouch, compared to its regular float offset while
     /// standing.
@@ -44,6 +51,12 @@ pub struct TnuaBuiltinCrouch {
     /// The maximum impulse to apply when starting or stopping the crouch.
     pub height_change_impulse_limit: Float,
 
+    /// A factor for the walk basis' [`desired_velocity`](TnuaBuiltinWalk::desired_velocity)
+    /// while crouching.
+    ///
+    /// Set to `1.0` to let the character move at the same speed as when it is standing.
+    pub walk_speed_factor: Float,
+
     /// If set to `true`, this action will not yield to other action who try to take control.
     ///
     /// For example - if the player holds the crouch button, and then hits the jump button while
@@ -51,6 +64,22 @@ pub struct TnuaBuiltinCrouch {
     /// But if `uncancellable` is `true`, the character will stay crouched, ignoring the jump
     /// action.
     pub uncancellable: bool,
+
+    /// The minimal horizontal speed for starting a slide instead of a regular crouch.
+    ///
+    /// Defaults to infinity, which disables sliding.
+    pub slide_speed_threshold: Float,
+
+    /// The deceleration of the slide on flat ground.
+    pub slide_deceleration: Float,
+
+    /// A factor for the part of the gravity that pulls the character down the slope while sliding.
+    ///
+    /// Set to `0.0` to make slopes not affect the slide.
+    pub slide_downhill_factor: Float,
+
+    /// When the horizontal speed drops below this, the slide ends.
+    pub slide_exit_speed: Float,
 }
 
 impl Default for TnuaBuiltinCrouch {
@@ -59,7 +88,12 @@ impl Default for TnuaBuiltinCrouch {
             float_offset: 0.0,
             height_change_impulse_for_duration: 0.02,
             height_change_impulse_limit: 40.0,
+            walk_speed_factor: 1.0,
             uncancellable: false,
+            slide_speed_threshold: Float::INFINITY,
+            slide_deceleration: 5.0,
+            slide_downhill_factor: 1.0,
+            slide_exit_speed: 3.0,
         }
     }
 }
@@ -99,6 +133,15 @@ impl TnuaAction for TnuaBuiltinCrouch {
         let spring_offset_down =
             spring_offset_up.adjust_precision() + self.float_offset.adjust_precision();
 
+        let up = ctx.up_direction.adjust_precision();
+        let velocity_on_plane = ctx.basis.effective_velocity().reject_from(up);
+
+        if lifecycle_status.just_started()
+            && self.slide_speed_threshold <= velocity_on_plane.length()
+        {
+            *state = TnuaBuiltinCrouchState::Sliding;
+        }
+
         match lifecycle_status {
             TnuaActionLifecycleStatus::Initiated => {}
             TnuaActionLifecycleStatus::CancelledFrom => {}
@@ -128,6 +171,36 @@ impl TnuaAction for TnuaBuiltinCrouch {
             }
         };
 
+        if self.walk_speed_factor != 1.0
+            && matches!(
+                state,
+                TnuaBuiltinCrouchState::Sinking | TnuaBuiltinCrouchState::Maintaining
+            )
+        {
+            let desired_boost =
+                walk_basis.desired_velocity * self.walk_speed_factor - velocity_on_plane;
+            motor.lin.acceleration = motor.lin.acceleration.project_onto(up)
+                + (desired_boost / ctx.frame_duration).clamp_length_max(walk_basis.acceleration);
+            motor.lin.boost = motor.lin.boost.project_onto(up);
+        }
+
+        if matches!(state, TnuaBuiltinCrouchState::Sliding) {
+            if velocity_on_plane.length() < self.slide_exit_speed {
+                *state = TnuaBuiltinCrouchState::Maintaining;
+            } else {
+                let downhill_acceleration = ctx
+                    .tracker
+                    .gravity
+                    .reject_from(sensor_output.normal.adjust_precision())
+                    .reject_from(up)
+                    * self.slide_downhill_factor;
+                motor.lin.acceleration = motor.lin.acceleration.project_onto(up)
+                    + downhill_acceleration
+                    - velocity_on_plane.normalize_or_zero() * self.slide_deceleration;
+                motor.lin.boost = motor.lin.boost.project_onto(up);
+            }
+        }
+
         let mut set_vel_change = |vel_change: TnuaVelChange| {
             motor
                 .lin
@@ -136,6 +209,15 @@ impl TnuaAction for TnuaBuiltinCrouch {
         };
 
         match state {
+            TnuaBuiltinCrouchState::Sliding => {
+                if spring_offset_down < -0.01 {
+                    set_vel_change(impulse_or_spring_force(spring_offset_down));
+                } else {
+                    set_vel_change(spring_force(spring_offset_down));
+                }
+                // If it's finished/cancelled, something else should changed its state
+                TnuaActionLifecycleDirective::StillActive
+            }
             TnuaBuiltinCrouchState::Sinking => {
                 if spring_offset_down < -0.01 {
                     set_vel_change(impulse_or_spring_force(spring_offset_down));
@@ -180,13 +262,17 @@ impl TnuaBuiltinCrouch {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinCrouchState {
     /// The character is transitioning from standing to crouching.
     #[default]
     Sinking,
     /// The character is currently crouched.
     Maintaining,
+    /// The character is sliding while crouched. See
+    /// [`slide_speed_threshold`](TnuaBuiltinCrouch::slide_speed_threshold).
+    Sliding,
     /// The character is transitioning from crouching to standing.
     Rising,
 }
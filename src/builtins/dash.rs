@@ -4,11 +4,12 @@ use bevy::prelude::*;
 use crate::util::rotation_arc_around_axis;
 use crate::{
     prelude::*, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus, TnuaMotor,
+    TnuaActionLifecycleStatus, TnuaMomentumPolicy, TnuaMotor, TnuaStaminaCost,
 };
 
 /// The basic dash [action](TnuaAction).
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaBuiltinDash {
     /// The direction and distance of the dash.
     ///
@@ -30,6 +31,21 @@ pub struct TnuaBuiltinDash {
     /// The speed the character will move in during the dash.
     pub speed: Float,
 
+    /// When set, the dash will last for this duration, in seconds, instead of stopping when the
+    /// character reaches the [`displacement`](Self::displacement).
+    ///
+    /// Only the direction of the `displacement` is used in that case - not its length. Negative
+    /// values are treated as zero.
+    pub duration: Option<Float>,
+
+    /// Cancel the character's vertical velocity during the dash, and prevent gravity from
+    /// affecting it.
+    ///
+    /// When set to `false`, the dash only affects the horizontal velocity and the vertical
+    /// velocity is left to the basis (and to gravity). This also means that any vertical
+    /// component of the [`displacement`](Self::displacement) is ignored.
+    pub zero_vertical_velocity: bool,
+
     /// After the dash, the character will brake until its speed is below that number.
     pub brake_to_speed: Float,
 
@@ -45,6 +61,19 @@ pub struct TnuaBuiltinDash {
     /// possible (typically when a character is still in the air and about the land) and the dash
     /// action would still get registered and be executed once the dash is possible.
     pub input_buffer_time: Float,
+
+    /// What to do with the character's horizontal momentum once the dash is over.
+    ///
+    /// Note that the dash brakes to [`brake_to_speed`](Self::brake_to_speed) before it finishes,
+    /// so the policy only applies to the speed that's left. Set `brake_to_speed` to infinity to
+    /// make the policy apply to the full speed of the dash.
+    ///
+    /// See [`TnuaMomentumPolicy`].
+    pub momentum_policy: Option<TnuaMomentumPolicy>,
+
+    /// The stamina the dash costs, if the character has a
+    /// [`TnuaStamina`](crate::controller::TnuaStamina). Free by default.
+    pub stamina_cost: TnuaStaminaCost,
 }
 
 impl Default for TnuaBuiltinDash {
@@ -54,10 +83,14 @@ impl Default for TnuaBuiltinDash {
             desired_forward: None,
             allow_in_air: false,
             speed: 80.0,
+            duration: None,
+            zero_vertical_velocity: true,
             brake_to_speed: 20.0,
             acceleration: 400.0,
             brake_acceleration: 200.0,
             input_buffer_time: 0.2,
+            momentum_policy: None,
+            stamina_cost: Default::default(),
         }
     }
 }
@@ -84,6 +117,14 @@ impl TnuaAction for TnuaBuiltinDash {
         }
     }
 
+    fn momentum_policy(&self) -> Option<TnuaMomentumPolicy> {
+        self.momentum_policy
+    }
+
+    fn stamina_cost(&self) -> TnuaStaminaCost {
+        self.stamina_cost
+    }
+
     fn apply(
         &self,
         state: &mut Self::State,
@@ -91,17 +132,27 @@ impl TnuaAction for TnuaBuiltinDash {
         _lifecycle_status: TnuaActionLifecycleStatus,
         motor: &mut TnuaMotor,
     ) -> TnuaActionLifecycleDirective {
+        let up = ctx.up_direction.adjust_precision();
+
         // TODO: Once `std::mem::variant_count` gets stabilized, use that instead.
         for _ in 0..3 {
             return match state {
                 TnuaBuiltinDashState::PreDash => {
-                    let Ok(direction) = Dir3::new(self.displacement.f32()) else {
+                    let displacement = if self.zero_vertical_velocity {
+                        self.displacement
+                    } else {
+                        self.displacement.reject_from(up)
+                    };
+                    let Ok(direction) = Dir3::new(displacement.f32()) else {
                         // Probably unneeded because of the `initiation_decision`, but still
                         return TnuaActionLifecycleDirective::Finished;
                     };
                     *state = TnuaBuiltinDashState::During {
                         direction,
-                        destination: ctx.tracker.translation + self.displacement,
+                        destination: ctx.tracker.translation + displacement,
+                        timer: self.duration.map(|duration| {
+                            Timer::from_seconds(duration.max(0.0).f32(), TimerMode::Once)
+                        }),
                         desired_forward: self.desired_forward,
                         consider_blocked_if_speed_is_less_than: Float::NEG_INFINITY,
                     };
@@ -110,13 +161,20 @@ impl TnuaAction for TnuaBuiltinDash {
                 TnuaBuiltinDashState::During {
                     direction,
                     destination,
+                    timer,
                     desired_forward,
                     consider_blocked_if_speed_is_less_than,
                 } => {
-                    let distance_to_destination = direction
-                        .adjust_precision()
-                        .dot(*destination - ctx.tracker.translation);
-                    if distance_to_destination < 0.0 {
+                    let reached_destination = if let Some(timer) = timer {
+                        timer.tick(ctx.frame_duration_as_duration());
+                        timer.finished()
+                    } else {
+                        let distance_to_destination = direction
+                            .adjust_precision()
+                            .dot(*destination - ctx.tracker.translation);
+                        distance_to_destination < 0.0
+                    };
+                    if reached_destination {
                         *state = TnuaBuiltinDashState::Braking {
                             direction: *direction,
                         };
@@ -128,11 +186,20 @@ impl TnuaAction for TnuaBuiltinDash {
                         return TnuaActionLifecycleDirective::Finished;
                     }
 
-                    motor.lin = Default::default();
-                    motor.lin.acceleration = -ctx.tracker.gravity;
-                    motor.lin.boost = (direction.adjust_precision() * self.speed
-                        - ctx.tracker.velocity)
-                        .clamp_length_max(ctx.frame_duration * self.acceleration);
+                    if self.zero_vertical_velocity {
+                        motor.lin = Default::default();
+                        motor.lin.acceleration = -ctx.tracker.gravity;
+                        motor.lin.boost = (direction.adjust_precision() * self.speed
+                            - ctx.tracker.velocity)
+                            .clamp_length_max(ctx.frame_duration * self.acceleration);
+                    } else {
+                        // Leave the vertical part of the motor to the basis
+                        motor.lin.acceleration = motor.lin.acceleration.project_onto(up);
+                        motor.lin.boost = motor.lin.boost.project_onto(up)
+                            + (direction.adjust_precision() * self.speed
+                                - ctx.tracker.velocity.reject_from(up))
+                            .clamp_length_max(ctx.frame_duration * self.acceleration);
+                    }
                     let expected_speed = direction
                         .adjust_precision()
                         .dot(ctx.tracker.velocity + motor.lin.boost);
@@ -143,11 +210,9 @@ impl TnuaAction for TnuaBuiltinDash {
                     };
 
                     if let Some(desired_forward) = desired_forward {
-                        let up = ctx.up_direction;
-                        let up = up.adjust_precision();
                         let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
                         let rotation_along_up_axis = rotation_arc_around_axis(
-                            Dir3::Y,
+                            ctx.up_direction,
                             current_forward,
                             desired_forward.adjust_precision(),
                         )
@@ -178,13 +243,15 @@ impl TnuaAction for TnuaBuiltinDash {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinDashState {
     #[default]
     PreDash,
     During {
         direction: Dir3,
         destination: Vector3,
+        timer: Option<Timer>,
         desired_forward: Option<Dir3>,
         consider_blocked_if_speed_is_less_than: Float,
     },
@@ -0,0 +1,190 @@
+use crate::math::{AdjustPrecision, Float, Quaternion, Vector3};
+use bevy::prelude::*;
+
+use crate::util::rotation_arc_around_axis;
+use crate::TnuaBasisContext;
+use crate::{TnuaBasis, TnuaVelChange};
+
+/// A [basis](TnuaBasis) for flying characters - drones, fairies, characters with jetpacks, etc.
+///
+/// This basis does not use the ground proximity sensor, and does not let gravity affect the
+/// character. The character moves in all three dimensions according to
+/// [`desired_velocity`](Self::desired_velocity), with its acceleration limited by
+/// [`acceleration`](Self::acceleration).
+///
+/// Since a flying character is always airborne, actions that require ground contact (like
+/// [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump) without
+/// [`allow_in_air`](crate::builtins::TnuaBuiltinJump::allow_in_air)) will not work with this
+/// basis.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinFly {
+    /// The direction (in the world space) and speed to accelerate to.
+    ///
+    /// Unlike the walk basis, this vector does not have to be orthogonal to the up direction.
+    pub desired_velocity: Vector3,
+
+    /// If non-zero, Tnua will rotate the character so that its negative Z will face in that
+    /// direction.
+    ///
+    /// Tnua assumes that this vector is orthogonal to the up direction.
+    pub desired_forward: Option<Dir3>,
+
+    /// The maximum acceleration when changing the velocity.
+    pub acceleration: Float,
+
+    /// When set, the character will hold this altitude (height along the up direction, in world
+    /// coordinates) as long as [`desired_velocity`](Self::desired_velocity) has no vertical
+    /// component.
+    pub altitude_hold: Option<Float>,
+
+    /// The strength of the force that brings the character back to the
+    /// [`altitude_hold`](Self::altitude_hold).
+    pub altitude_hold_strength: Float,
+
+    /// A force that slows down the vertical motion toward the
+    /// [`altitude_hold`](Self::altitude_hold).
+    ///
+    /// The actual dampening is in direct linear relationship to the vertical velocity it tries to
+    /// dampen.
+    pub altitude_hold_dampening: Float,
+
+    /// When set, the basis will calculate a banking angle - up to this value, in radians - based
+    /// on the character's sideways acceleration.
+    ///
+    /// The banking is only calculated - it is not applied to the rigid body. Game code can use
+    /// [`TnuaBuiltinFlyState::bank_rotation`] to tilt the character's visual model.
+    pub max_bank_angle: Option<Float>,
+
+    /// The maximum angular velocity used for keeping the character's forward. If you find the
+    /// character turns too slowly (or too fast), try changing this value.
+    pub turning_angvel: Float,
+}
+
+impl Default for TnuaBuiltinFly {
+    fn default() -> Self {
+        Self {
+            desired_velocity: Vector3::ZERO,
+            desired_forward: None,
+            acceleration: 40.0,
+            altitude_hold: None,
+            altitude_hold_strength: 40.0,
+            altitude_hold_dampening: 0.4,
+            max_bank_angle: None,
+            turning_angvel: 10.0,
+        }
+    }
+}
+
+impl TnuaBasis for TnuaBuiltinFly {
+    const NAME: &'static str = "TnuaBuiltinFly";
+    type State = TnuaBuiltinFlyState;
+
+    fn apply(&self, state: &mut Self::State, ctx: TnuaBasisContext, motor: &mut crate::TnuaMotor) {
+        let up = ctx.up_direction.adjust_precision();
+        state.effective_velocity = ctx.tracker.velocity;
+
+        let altitude_to_hold = self
+            .altitude_hold
+            .filter(|_| self.desired_velocity.dot(up) == 0.0);
+
+        let controlled_velocity = if altitude_to_hold.is_some() {
+            ctx.tracker.velocity.reject_from(up)
+        } else {
+            ctx.tracker.velocity
+        };
+
+        let acceleration = ((self.desired_velocity - controlled_velocity) / ctx.frame_duration)
+            .clamp_length_max(self.acceleration);
+
+        motor.lin = TnuaVelChange::acceleration(acceleration - ctx.tracker.gravity);
+
+        if let Some(altitude_to_hold) = altitude_to_hold {
+            let spring_offset = altitude_to_hold - ctx.tracker.translation.dot(up);
+            let vertical_velocity = ctx.tracker.velocity.dot(up);
+            motor.lin += TnuaVelChange {
+                acceleration: up * spring_offset * self.altitude_hold_strength,
+                boost: up * -vertical_velocity * self.altitude_hold_dampening,
+            };
+        }
+
+        // Banking
+
+        let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
+        state.bank_angle = if let Some(max_bank_angle) = self.max_bank_angle {
+            let right = current_forward.cross(up).normalize_or_zero();
+            let sideways_factor = if 0.0 < self.acceleration {
+                (acceleration.dot(right) / self.acceleration).clamp(-1.0, 1.0)
+            } else {
+                0.0
+            };
+            sideways_factor * max_bank_angle
+        } else {
+            0.0
+        };
+
+        // Turning
+
+        let desired_angvel = if let Some(desired_forward) = self.desired_forward {
+            let rotation_along_up_axis = rotation_arc_around_axis(
+                ctx.up_direction,
+                current_forward,
+                desired_forward.adjust_precision(),
+            )
+            .unwrap_or(0.0);
+            (rotation_along_up_axis / ctx.frame_duration)
+                .clamp(-self.turning_angvel, self.turning_angvel)
+        } else {
+            0.0
+        };
+
+        let existing_angvel = ctx.tracker.angvel.dot(up);
+        motor.ang = TnuaVelChange::boost((desired_angvel - existing_angvel) * up);
+    }
+
+    fn proximity_sensor_cast_range(&self, _state: &Self::State) -> Float {
+        0.0
+    }
+
+    fn displacement(&self, _state: &Self::State) -> Option<Vector3> {
+        None
+    }
+
+    fn effective_velocity(&self, state: &Self::State) -> Vector3 {
+        state.effective_velocity
+    }
+
+    fn vertical_velocity(&self, _state: &Self::State) -> Float {
+        0.0
+    }
+
+    fn neutralize(&mut self) {
+        self.desired_velocity = Vector3::ZERO;
+        self.desired_forward = None;
+    }
+
+    fn is_airborne(&self, _state: &Self::State) -> bool {
+        true
+    }
+
+    fn violate_coyote_time(&self, _state: &mut Self::State) {}
+}
+
+#[derive(Default, Clone, Debug, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinFlyState {
+    /// The banking angle, in radians, calculated from the sideways acceleration. Positive values
+    /// mean the character leans to its right.
+    ///
+    /// Always `0.0` if [`TnuaBuiltinFly::max_bank_angle`] is `None`.
+    pub bank_angle: Float,
+    effective_velocity: Vector3,
+}
+
+impl TnuaBuiltinFlyState {
+    /// The banking as a rotation in the character's local coordinates, to be applied to the
+    /// character's visual model.
+    pub fn bank_rotation(&self) -> Quaternion {
+        Quaternion::from_axis_angle(Vector3::NEG_Z, self.bank_angle)
+    }
+}
@@ -0,0 +1,131 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::reflect::Reflect;
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor, TnuaVelChange,
+};
+
+/// An [action](TnuaAction) for gliding (or using a parachute) - slowly falling through the air.
+///
+/// The action should be fed for as long as the player holds the glide button. It will only start
+/// once the character is in the air and falling, so it can be fed together with
+/// [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump) to make the character jump and then glide
+/// as long as the button is held. Since a newly fed action takes precedence over other newly fed
+/// actions, the glide should only be fed once the character is airborne - otherwise it'll prevent
+/// the jump from starting:
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::TnuaBuiltinGlide;
+/// # let mut controller = TnuaController::default();
+/// # let jump_pressed = true;
+/// if jump_pressed {
+///     controller.action(TnuaBuiltinJump {
+///         height: 4.0,
+///         ..Default::default()
+///     });
+///     if controller.is_airborne().unwrap_or(false) {
+///         // The glide will take over once it can start - that is, once the character starts
+///         // falling.
+///         controller.action(TnuaBuiltinGlide::default());
+///     }
+/// }
+/// ```
+///
+/// The glide finishes when the character lands, when it is no longer fed, or when another action
+/// cancels it.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinGlide {
+    /// The maximum speed the character can fall at while gliding.
+    pub max_fall_speed: Float,
+
+    /// The maximum acceleration used for slowing the character's fall to
+    /// [`max_fall_speed`](Self::max_fall_speed).
+    pub brake_acceleration: Float,
+
+    /// The direction (in the world space) and speed to accelerate to while gliding.
+    ///
+    /// Only used when [`air_acceleration`](Self::air_acceleration) is set.
+    pub desired_velocity: Vector3,
+
+    /// When set, the glide takes over the horizontal movement from the basis and accelerates the
+    /// character toward [`desired_velocity`](Self::desired_velocity) with this acceleration -
+    /// granting it more (or less) air control than usual.
+    ///
+    /// When `None`, the horizontal movement is left to the basis.
+    pub air_acceleration: Option<Float>,
+}
+
+impl Default for TnuaBuiltinGlide {
+    fn default() -> Self {
+        Self {
+            max_fall_speed: 2.0,
+            brake_acceleration: 60.0,
+            desired_velocity: Vector3::ZERO,
+            air_acceleration: None,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinGlide {
+    const NAME: &'static str = "TnuaBuiltinGlide";
+    type State = TnuaBuiltinGlideState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        let up = ctx.up_direction.adjust_precision();
+        if ctx.basis.is_airborne() && ctx.tracker.velocity.dot(up) <= 0.0 {
+            TnuaActionInitiationDirective::Allow
+        } else {
+            TnuaActionInitiationDirective::Delay
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        if !lifecycle_status.is_active() || !ctx.basis.is_airborne() {
+            return TnuaActionLifecycleDirective::Finished;
+        }
+
+        state.glide_time += ctx.frame_duration;
+
+        let up = ctx.up_direction.adjust_precision();
+
+        if let Some(air_acceleration) = self.air_acceleration {
+            let velocity_on_plane = ctx.tracker.velocity.reject_from(up);
+            let desired_boost = self.desired_velocity.reject_from(up) - velocity_on_plane;
+            motor.lin.acceleration = motor.lin.acceleration.project_onto(up)
+                + (desired_boost / ctx.frame_duration).clamp_length_max(air_acceleration);
+            motor.lin.boost = motor.lin.boost.project_onto(up);
+        }
+
+        let fall_speed = -ctx.tracker.velocity.dot(up);
+        motor.lin.cancel_on_axis(up);
+        if self.max_fall_speed < fall_speed {
+            motor.lin += TnuaVelChange::boost(
+                up * (fall_speed - self.max_fall_speed)
+                    .min(ctx.frame_duration * self.brake_acceleration),
+            );
+        }
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinGlideState {
+    /// The time, in seconds, since the glide has started.
+    pub glide_time: Float,
+}
@@ -0,0 +1,196 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::controller::TnuaController;
+use crate::math::{AdjustPrecision, Float};
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor, TnuaPipelineStages, TnuaVelChange,
+};
+
+/// An [action](TnuaAction) for slamming down into the ground from the air.
+///
+/// When the action starts, the character's horizontal movement (and any upward movement) is
+/// halted, and it is accelerated downward until it lands. It can only be started while the
+/// character is in the air - feeding it while on the ground will not make it start later when the
+/// character jumps, so the button needs to be pressed again.
+///
+/// When the character lands, the action enters the [`Impact`](TnuaBuiltinGroundPoundState::Impact)
+/// state for a single frame and then finishes. With [`TnuaGroundPoundPlugin`], a
+/// [`TnuaGroundPoundImpact`] event will be sent on that frame, which games can use for spawning
+/// shockwaves, dealing damage, etc.
+///
+/// Unlike most actions, once started the ground pound will continue until the character lands even
+/// if no longer fed. It can still be cancelled by other actions.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinGroundPound {
+    /// The acceleration of the fall. This replaces the gravity while the action is active.
+    pub acceleration: Float,
+
+    /// The maximum speed the character can fall at during the ground pound.
+    pub max_fall_speed: Float,
+}
+
+impl Default for TnuaBuiltinGroundPound {
+    fn default() -> Self {
+        Self {
+            acceleration: 100.0,
+            max_fall_speed: 40.0,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinGroundPound {
+    const NAME: &'static str = "TnuaBuiltinGroundPound";
+    type State = TnuaBuiltinGroundPoundState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if ctx.basis.is_airborne() {
+            TnuaActionInitiationDirective::Allow
+        } else {
+            TnuaActionInitiationDirective::Reject
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        match state {
+            TnuaBuiltinGroundPoundState::Diving => {
+                if matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto) {
+                    return TnuaActionLifecycleDirective::Finished;
+                }
+
+                let up = ctx.up_direction.adjust_precision();
+                let fall_speed = -ctx.tracker.velocity.dot(up);
+
+                if !ctx.basis.is_airborne() {
+                    *state = TnuaBuiltinGroundPoundState::Impact {
+                        impact_speed: fall_speed.max(0.0),
+                        ground_entity: ctx
+                            .proximity_sensor
+                            .output
+                            .as_ref()
+                            .map(|output| output.entity),
+                    };
+                    // Let the basis handle the landing.
+                    return TnuaActionLifecycleDirective::StillActive;
+                }
+
+                let target_fall_speed = if fall_speed < self.max_fall_speed {
+                    (fall_speed.max(0.0) + self.acceleration * ctx.frame_duration)
+                        .min(self.max_fall_speed)
+                } else {
+                    fall_speed
+                };
+
+                motor.lin = TnuaVelChange {
+                    acceleration: -ctx.tracker.gravity,
+                    boost: -ctx.tracker.velocity.reject_from(up)
+                        - (target_fall_speed - fall_speed) * up,
+                };
+
+                TnuaActionLifecycleDirective::StillActive
+            }
+            TnuaBuiltinGroundPoundState::Impact { .. } => TnuaActionLifecycleDirective::Finished,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinGroundPoundState {
+    /// The character is diving toward the ground.
+    #[default]
+    Diving,
+    /// The character has just hit the ground.
+    Impact {
+        /// The speed in which the character was falling when it hit the ground.
+        impact_speed: Float,
+        /// The entity the character landed on.
+        ground_entity: Option<Entity>,
+    },
+}
+
+pub struct TnuaGroundPoundPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaGroundPoundPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaGroundPoundPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin for sending [`TnuaGroundPoundImpact`] events.
+///
+/// The schedule should be the same one
+/// [`TnuaControllerPlugin`](crate::controller::TnuaControllerPlugin) uses.
+impl Plugin for TnuaGroundPoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TnuaGroundPoundImpact>();
+        app.add_systems(
+            self.schedule,
+            send_ground_pound_impact_events
+                .in_set(TnuaPipelineStages::Logic)
+                .after(crate::controller::apply_controller_system),
+        );
+    }
+}
+
+/// Sent by [`TnuaGroundPoundPlugin`] when a character performing [`TnuaBuiltinGroundPound`] hits
+/// the ground.
+#[derive(Event, Debug, Clone)]
+pub struct TnuaGroundPoundImpact {
+    /// The character entity.
+    pub entity: Entity,
+    /// The speed in which the character was falling when it hit the ground.
+    pub impact_speed: Float,
+    /// The entity the character landed on.
+    pub ground_entity: Option<Entity>,
+}
+
+fn send_ground_pound_impact_events(
+    time: Res<Time>,
+    query: Query<(Entity, &TnuaController)>,
+    mut writer: EventWriter<TnuaGroundPoundImpact>,
+) {
+    if time.delta().is_zero() {
+        // The controller was not updated this frame, so any impact was already reported.
+        return;
+    }
+    for (entity, controller) in query.iter() {
+        if let Some((
+            _,
+            TnuaBuiltinGroundPoundState::Impact {
+                impact_speed,
+                ground_entity,
+            },
+        )) = controller.concrete_action::<TnuaBuiltinGroundPound>()
+        {
+            writer.send(TnuaGroundPoundImpact {
+                entity,
+                impact_speed: *impact_speed,
+                ground_entity: *ground_entity,
+            });
+        }
+    }
+}
@@ -4,7 +4,7 @@ use bevy::prelude::*;
 use crate::util::SegmentedJumpInitialVelocityCalculator;
 use crate::{
     TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus,
+    TnuaActionLifecycleStatus, TnuaMomentumPolicy,
 };
 
 /// The basic jump [action](TnuaAction).
@@ -17,7 +17,8 @@ use crate::{
 /// being fed, it'll apply extra gravity to shorten the jump. If the game desires fixed height
 /// jumps instead (where the player cannot make lower jumps by tapping the jump button)
 /// [`shorten_extra_gravity`](Self::shorten_extra_gravity) should be set to `0.0`.
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaBuiltinJump {
     /// The height the character will jump to.
     ///
@@ -31,6 +32,15 @@ pub struct TnuaBuiltinJump {
     /// and the basis' [`displacement`](crate::TnuaBasis::displacement).
     pub height: Float,
 
+    /// A factor the [`height`](Self::height) gets multiplied by, for making the jump height
+    /// depend on an analog input (like the pressure on a trigger or the magnitude of a stick).
+    ///
+    /// This is only read when the jump starts - changing it in the middle of the jump will not
+    /// change its height - so games that want variable jumps from a single press should usually
+    /// also set [`shorten_extra_gravity`](Self::shorten_extra_gravity) to `0.0`. Values below
+    /// `0.0` are treated as `0.0`.
+    pub height_factor: Float,
+
     /// Allow this action to start even if the character is not touching ground nor in coyote time.
     pub allow_in_air: bool,
 
@@ -99,12 +109,18 @@ pub struct TnuaBuiltinJump {
     /// possible (typically when a character is still in the air and about the land) and the jump
     /// action would still get registered and be executed once the jump is possible.
     pub input_buffer_time: Float,
+
+    /// What to do with the character's horizontal momentum once the jump is over.
+    ///
+    /// See [`TnuaMomentumPolicy`].
+    pub momentum_policy: Option<TnuaMomentumPolicy>,
 }
 
 impl Default for TnuaBuiltinJump {
     fn default() -> Self {
         Self {
             height: 0.0,
+            height_factor: 1.0,
             allow_in_air: false,
             upslope_extra_gravity: 30.0,
             takeoff_extra_gravity: 30.0,
@@ -115,6 +131,7 @@ impl Default for TnuaBuiltinJump {
             peak_prevention_extra_gravity: 20.0,
             reschedule_cooldown: None,
             input_buffer_time: 0.2,
+            momentum_policy: None,
         }
     }
 }
@@ -139,6 +156,10 @@ impl TnuaAction for TnuaBuiltinJump {
         }
     }
 
+    fn momentum_policy(&self) -> Option<TnuaMomentumPolicy> {
+        self.momentum_policy
+    }
+
     fn apply(
         &self,
         state: &mut Self::State,
@@ -149,7 +170,9 @@ impl TnuaAction for TnuaBuiltinJump {
         let up = ctx.up_direction.adjust_precision();
 
         if lifecycle_status.just_started() {
-            let mut calculator = SegmentedJumpInitialVelocityCalculator::new(self.height);
+            let mut calculator = SegmentedJumpInitialVelocityCalculator::new(
+                self.height * self.height_factor.max(0.0),
+            );
             let gravity = ctx.tracker.gravity.dot(-up);
             let kinetic_energy = calculator
                 .add_segment(
@@ -194,6 +217,11 @@ impl TnuaAction for TnuaBuiltinJump {
 
                     motor.lin.cancel_on_axis(up);
                     motor.lin.boost += (desired_upward_velocity - relative_velocity) * up;
+                    if let Some(conveyor) = ctx.ground_conveyor {
+                        if !conveyor.inherit_on_jump {
+                            motor.lin.boost -= conveyor.velocity.reject_from(up);
+                        }
+                    }
                     if 0.0 <= extra_height {
                         *state = TnuaBuiltinJumpState::SlowDownTooFastSlopeJump {
                             desired_energy: *desired_energy,
@@ -315,6 +343,29 @@ impl TnuaAction for TnuaBuiltinJump {
 }
 
 impl TnuaBuiltinJump {
+    /// Configure the jump to follow a [`TnuaJumpArc`].
+    ///
+    /// This sets the [`height`](Self::height) and the extra gravity fields that shape the jump,
+    /// and disables the peak prevention (the arc already determines the time the character spends
+    /// near the peak). Fields that only affect jumps that are cut short or that start from a slope
+    /// are not changed.
+    ///
+    /// `gravity` is the magnitude of the gravity the physics backend applies to the character.
+    ///
+    /// See [`TnuaJumpArc::derive`] for the requirements of the arc's times.
+    pub fn with_arc(self, arc: &TnuaJumpArc, gravity: Float) -> Self {
+        let derived = arc.derive(gravity);
+        Self {
+            height: arc.apex_height,
+            takeoff_extra_gravity: derived.rise_extra_gravity,
+            takeoff_above_velocity: 0.0,
+            fall_extra_gravity: derived.fall_extra_gravity,
+            peak_prevention_at_upward_velocity: 0.0,
+            peak_prevention_extra_gravity: 0.0,
+            ..self
+        }
+    }
+
     fn finish_or_reschedule(&self) -> TnuaActionLifecycleDirective {
         if let Some(cooldown) = self.reschedule_cooldown {
             TnuaActionLifecycleDirective::Reschedule {
@@ -337,7 +388,95 @@ impl TnuaBuiltinJump {
     }
 }
 
-#[derive(Default, Debug)]
+/// A desired jump arc, for configuring [`TnuaBuiltinJump`] by how the jump should look instead
+/// of by its extra gravity fields.
+///
+/// Use [`TnuaBuiltinJump::with_arc`] to apply it, and [`derive`](Self::derive) to get the values
+/// that it derives (e.g. for displaying them in a tuning UI).
+///
+/// ```
+/// # use bevy_tnua::builtins::{TnuaBuiltinJump, TnuaJumpArc};
+/// let jump = TnuaBuiltinJump::default().with_arc(
+///     &TnuaJumpArc {
+///         apex_height: 4.0,
+///         time_to_apex: 0.4,
+///         fall_time: 0.3,
+///     },
+///     9.81,
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaJumpArc {
+    /// The height of the jump. See [`TnuaBuiltinJump::height`].
+    pub apex_height: Float,
+
+    /// The time, in seconds, it takes the character to reach the peak of the jump after taking
+    /// off.
+    pub time_to_apex: Float,
+
+    /// The time, in seconds, it takes the character to fall from the peak of the jump back to the
+    /// height it took off from.
+    pub fall_time: Float,
+}
+
+/// The values derived from a [`TnuaJumpArc`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct TnuaJumpArcDerivedValues {
+    /// The upward velocity the character takes off with.
+    pub takeoff_velocity: Float,
+
+    /// The total gravity pulling the character down while it goes up.
+    pub rise_gravity: Float,
+
+    /// The total gravity pulling the character down while it falls back down.
+    pub fall_gravity: Float,
+
+    /// The gravity that needs to be added to the regular gravity while the character goes up.
+    ///
+    /// This can be negative, if the arc requires weaker gravity than the regular one.
+    pub rise_extra_gravity: Float,
+
+    /// The gravity that needs to be added to the regular gravity while the character falls back
+    /// down.
+    ///
+    /// This can be negative, if the arc requires weaker gravity than the regular one.
+    pub fall_extra_gravity: Float,
+}
+
+/// The shortest time [`TnuaJumpArc::derive`] uses for the rise and the fall.
+const MIN_ARC_TIME: Float = 0.001;
+
+impl TnuaJumpArc {
+    /// Calculate the gravity and velocities of the arc.
+    ///
+    /// `gravity` is the magnitude of the gravity the physics backend applies to the character.
+    ///
+    /// [`time_to_apex`](Self::time_to_apex) and [`fall_time`](Self::fall_time) must be positive.
+    /// In debug builds this panics if they are not, and in release builds they are raised to a
+    /// millisecond so that the derived values will not be infinite or NaN.
+    pub fn derive(&self, gravity: Float) -> TnuaJumpArcDerivedValues {
+        debug_assert!(
+            0.0 < self.time_to_apex && 0.0 < self.fall_time,
+            "TnuaJumpArc times must be positive, got {self:?}"
+        );
+        // `max` also turns NaN into the minimum.
+        let time_to_apex = self.time_to_apex.max(MIN_ARC_TIME);
+        let fall_time = self.fall_time.max(MIN_ARC_TIME);
+        let rise_gravity = 2.0 * self.apex_height / time_to_apex.powi(2);
+        let fall_gravity = 2.0 * self.apex_height / fall_time.powi(2);
+        TnuaJumpArcDerivedValues {
+            takeoff_velocity: rise_gravity * time_to_apex,
+            rise_gravity,
+            fall_gravity,
+            rise_extra_gravity: rise_gravity - gravity,
+            fall_extra_gravity: fall_gravity - gravity,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinJumpState {
     #[default]
     NoJump,
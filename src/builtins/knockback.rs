@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use crate::{
-    math::{AdjustPrecision, AsF32, Float, Vector3},
+    math::{ops, AdjustPrecision, AsF32, Float, Vector3},
     prelude::*,
     util::rotation_arc_around_axis,
     TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
@@ -24,7 +24,8 @@ use bevy::prelude::*;
 /// * [`barrier_strength_diminishing`](Self::barrier_strength_diminishing). Setting it too low
 ///   makes it very hard for the character to push through the boundary. It starts getting slightly
 ///   weird below 1.0, and really weird below 0.5. Better keep it at above - 1.0 levels.
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaBuiltinKnockback {
     /// Initial impulse to apply to the character before the Pushover stage starts.
     ///
@@ -34,7 +35,7 @@ pub struct TnuaBuiltinKnockback {
     pub shove: Vector3,
 
     /// Timeout (in seconds) for abandoning a Pushover boundary that no longer gets pushed.
-    pub no_push_timeout: f32,
+    pub no_push_timeout: Float,
 
     /// An exponent for controlling the shape of the Pushover barrier diminishing.
     ///
@@ -64,6 +65,17 @@ pub struct TnuaBuiltinKnockback {
     /// single frame. It is useful for when the knockback animation needs to be aligned with the
     /// knockback direction.
     pub force_forward: Option<Dir3>,
+
+    /// A duration, in seconds, after the shove during which the basis cannot control the
+    /// character's horizontal movement.
+    ///
+    /// During that time the character will be carried by the shove, without the basis trying to
+    /// bring it back to the velocity the player wants.
+    pub no_control_duration: Float,
+
+    /// A duration, in seconds, after the [`no_control_duration`](Self::no_control_duration) during
+    /// which the basis gradually regains control over the character's horizontal movement.
+    pub control_recovery_duration: Float,
 }
 
 impl Default for TnuaBuiltinKnockback {
@@ -75,6 +87,8 @@ impl Default for TnuaBuiltinKnockback {
             acceleration_limit: 3.0,
             air_acceleration_limit: 1.0,
             force_forward: None,
+            no_control_duration: 0.0,
+            control_recovery_duration: 0.0,
         }
     }
 }
@@ -101,13 +115,28 @@ impl TnuaAction for TnuaBuiltinKnockback {
                     return TnuaActionLifecycleDirective::Finished;
                 };
                 motor.lin += TnuaVelChange::boost(self.shove);
-                *state = TnuaBuiltinKnockbackState::Pushback { boundary };
+                *state = TnuaBuiltinKnockbackState::Pushback {
+                    boundary,
+                    elapsed: 0.0,
+                };
             }
-            TnuaBuiltinKnockbackState::Pushback { boundary } => {
+            TnuaBuiltinKnockbackState::Pushback { boundary, elapsed } => {
+                *elapsed += ctx.frame_duration;
                 boundary.update(ctx.tracker.velocity, ctx.frame_duration_as_duration());
-                if boundary.is_cleared() {
+                let control_factor = self.control_factor(*elapsed);
+                if boundary.is_cleared() && 1.0 <= control_factor {
                     return TnuaActionLifecycleDirective::Finished;
-                } else {
+                }
+                if control_factor < 1.0 {
+                    let up = ctx.up_direction.adjust_precision();
+                    motor.lin = TnuaVelChange {
+                        acceleration: motor.lin.acceleration.project_onto(up)
+                            + control_factor * motor.lin.acceleration.reject_from(up),
+                        boost: motor.lin.boost.project_onto(up)
+                            + control_factor * motor.lin.boost.reject_from(up),
+                    };
+                }
+                if !boundary.is_cleared() {
                     let regular_boost = crate::util::calc_boost(&motor.lin, ctx.frame_duration);
                     if let Some((component_direction, component_limit)) = boundary
                         .calc_boost_part_on_boundary_axis_after_limit(
@@ -178,7 +207,22 @@ impl TnuaAction for TnuaBuiltinKnockback {
     }
 }
 
-#[derive(Default)]
+impl TnuaBuiltinKnockback {
+    /// How much of the basis' horizontal control is allowed, `elapsed` seconds after the shove.
+    fn control_factor(&self, elapsed: Float) -> Float {
+        let recovery_time = elapsed - self.no_control_duration;
+        if recovery_time <= 0.0 {
+            0.0
+        } else if recovery_time < self.control_recovery_duration {
+            recovery_time / self.control_recovery_duration
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Default, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinKnockbackState {
     /// Applying the [`shove`](TnuaBuiltinKnockback::shove) impulse to the character.
     #[default]
@@ -186,11 +230,17 @@ pub enum TnuaBuiltinKnockbackState {
     /// Hindering the character's ability to overcome the
     /// [`Shove`](TnuaBuiltinKnockbackState::Shove) while waiting for it to overcome it despite the
     /// hindrance.
-    Pushback { boundary: VelocityBoundary },
+    Pushback {
+        boundary: VelocityBoundary,
+        /// The time, in seconds, since the shove.
+        elapsed: Float,
+    },
 }
 
 /// An indication that a character was knocked back and "struggles" to get back to its original
 /// velocity.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VelocityBoundary {
     base: Float,
     original_frontier: Float,
@@ -200,7 +250,11 @@ pub struct VelocityBoundary {
 }
 
 impl VelocityBoundary {
-    fn new(disruption_from: Vector3, disruption_to: Vector3, no_push_timeout: f32) -> Option<Self> {
+    fn new(
+        disruption_from: Vector3,
+        disruption_to: Vector3,
+        no_push_timeout: Float,
+    ) -> Option<Self> {
         let Ok(disruption_direction) = Dir3::new((disruption_to - disruption_from).f32()) else {
             return None;
         };
@@ -210,7 +264,7 @@ impl VelocityBoundary {
             original_frontier: frontier,
             frontier,
             direction: disruption_direction,
-            no_push_timer: Timer::from_seconds(no_push_timeout, TimerMode::Once),
+            no_push_timer: Timer::from_seconds(no_push_timeout.f32(), TimerMode::Once),
         })
     }
 
@@ -303,7 +357,7 @@ impl VelocityBoundary {
 
         let total_boost = boost_outside_barrier + boost_inside_barrier;
 
-        let barrier_strength = self.percentage_left().powf(barrier_strength_diminishing);
+        let barrier_strength = ops::powf(self.percentage_left(), barrier_strength_diminishing);
         let total_boost = (1.0 - barrier_strength) * boost + barrier_strength * total_boost;
 
         Some((-self.direction, -total_boost))
@@ -0,0 +1,204 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::control_helpers::TnuaLedge;
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor, TnuaVelChange,
+};
+
+/// An [action](TnuaAction) for grabbing a ledge, hanging from it, and climbing up on it.
+///
+/// The only field that must be set is [`ledge`](Self::ledge), which is typically taken from a
+/// [`TnuaLedgeSensor`](crate::control_helpers::TnuaLedgeSensor). When it is `None` (no ledge was
+/// detected) the action will not start.
+///
+/// Once the ledge is grabbed, the character will hang from it for as long as the action is fed.
+/// Setting [`climb_up`](Self::climb_up) will make the character climb up the ledge in a scripted
+/// motion lasting [`climb_duration`](Self::climb_duration) seconds, after which the action will
+/// finish and the basis will take control again. Stop feeding the action (or feed some other
+/// action, like a jump) to let go of the ledge.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinLedgeGrab {
+    /// The ledge to grab.
+    ///
+    /// This input parameter is cached when the action starts. This means that the control system
+    /// does not have to keep feeding the same ledge after the character starts to climb it.
+    pub ledge: Option<TnuaLedge>,
+
+    /// Set this to make the character climb up from the ledge it's hanging from.
+    pub climb_up: bool,
+
+    /// How far below the top of the ledge the character's center will be while hanging.
+    pub hang_height: Float,
+
+    /// A duration, in seconds, of the climb up motion.
+    pub climb_duration: Float,
+
+    /// How high above the top of the ledge the character's center will be at the end of the climb
+    /// up. Should usually be the same as the walk basis'
+    /// [`float_height`](crate::builtins::TnuaBuiltinWalk::float_height).
+    pub stand_height: Float,
+
+    /// The part of the [`climb_duration`](Self::climb_duration) spent on climbing vertically.
+    /// The rest of the duration is spent on moving forward over the ledge.
+    pub vertical_climb_part: Float,
+
+    /// A duration, in seconds, where a player can press the grab button before a ledge is detected
+    /// and the grab would still get registered and be executed once the character reaches a
+    /// ledge.
+    ///
+    /// Defaults to infinity, so that holding the grab button will grab any ledge the character
+    /// reaches. For a button that is pressed once instead of being held, use
+    /// [`TnuaController::buffered_action`](crate::controller::TnuaController::buffered_action).
+    pub input_buffer_time: Float,
+}
+
+impl Default for TnuaBuiltinLedgeGrab {
+    fn default() -> Self {
+        Self {
+            ledge: None,
+            climb_up: false,
+            hang_height: 1.0,
+            climb_duration: 0.5,
+            stand_height: 1.0,
+            vertical_climb_part: 0.6,
+            input_buffer_time: Float::INFINITY,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinLedgeGrab {
+    const NAME: &'static str = "TnuaBuiltinLedgeGrab";
+    type State = TnuaBuiltinLedgeGrabState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn initiation_decision(
+        &self,
+        _ctx: TnuaActionContext,
+        being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if self.ledge.is_some() {
+            TnuaActionInitiationDirective::Allow
+        } else if (being_fed_for.elapsed().as_secs_f64() as Float) < self.input_buffer_time {
+            TnuaActionInitiationDirective::Delay
+        } else {
+            TnuaActionInitiationDirective::Reject
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        let up = ctx.up_direction.adjust_precision();
+
+        // Steer the character to a position this frame, ignoring gravity and the basis.
+        let mut move_to = |target: Vector3| {
+            let desired_velocity = (target - ctx.tracker.translation) / ctx.frame_duration;
+            motor.lin = TnuaVelChange {
+                acceleration: -ctx.tracker.gravity,
+                boost: desired_velocity - ctx.tracker.velocity,
+            };
+        };
+
+        // TODO: Once `std::mem::variant_count` gets stabilized, use that instead.
+        for _ in 0..3 {
+            return match state {
+                TnuaBuiltinLedgeGrabState::Grabbing => {
+                    let Some(ledge) = self.ledge.clone() else {
+                        // Probably unneeded because of the `initiation_decision`, but still
+                        return TnuaActionLifecycleDirective::Finished;
+                    };
+                    let ledge_height = ledge.position.dot(up);
+                    let hang_position = ctx.tracker.translation.reject_from(up)
+                        + (ledge_height - self.hang_height) * up;
+                    *state = TnuaBuiltinLedgeGrabState::Hanging {
+                        ledge,
+                        hang_position,
+                    };
+                    continue;
+                }
+                TnuaBuiltinLedgeGrabState::Hanging {
+                    ledge,
+                    hang_position,
+                } => {
+                    if !lifecycle_status.is_active() {
+                        return TnuaActionLifecycleDirective::Finished;
+                    }
+                    if self.climb_up {
+                        *state = TnuaBuiltinLedgeGrabState::ClimbingUp {
+                            ledge: ledge.clone(),
+                            hang_position: *hang_position,
+                            elapsed: 0.0,
+                        };
+                        continue;
+                    }
+                    move_to(*hang_position);
+                    TnuaActionLifecycleDirective::StillActive
+                }
+                TnuaBuiltinLedgeGrabState::ClimbingUp {
+                    ledge,
+                    hang_position,
+                    elapsed,
+                } => {
+                    *elapsed += ctx.frame_duration;
+                    let progress = if 0.0 < self.climb_duration {
+                        (*elapsed / self.climb_duration).min(1.0)
+                    } else {
+                        1.0
+                    };
+
+                    let stand_position = ledge.position + self.stand_height * up;
+                    let above_hang_position =
+                        hang_position.reject_from(up) + stand_position.dot(up) * up;
+
+                    let target = if progress < self.vertical_climb_part {
+                        hang_position.lerp(above_hang_position, progress / self.vertical_climb_part)
+                    } else if self.vertical_climb_part < 1.0 {
+                        above_hang_position.lerp(
+                            stand_position,
+                            (progress - self.vertical_climb_part)
+                                / (1.0 - self.vertical_climb_part),
+                        )
+                    } else {
+                        stand_position
+                    };
+                    move_to(target);
+
+                    if 1.0 <= progress {
+                        TnuaActionLifecycleDirective::Finished
+                    } else {
+                        TnuaActionLifecycleDirective::StillActive
+                    }
+                }
+            };
+        }
+        error!("Tnua could not decide on ledge grab state");
+        TnuaActionLifecycleDirective::Finished
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinLedgeGrabState {
+    /// The ledge is about to be grabbed.
+    #[default]
+    Grabbing,
+    /// The character is hanging from the ledge.
+    Hanging {
+        ledge: TnuaLedge,
+        hang_position: Vector3,
+    },
+    /// The character is climbing up the ledge.
+    ClimbingUp {
+        ledge: TnuaLedge,
+        hang_position: Vector3,
+        /// The time, in seconds, since the climb up has started.
+        elapsed: Float,
+    },
+}
@@ -1,11 +1,99 @@
+use bevy::prelude::*;
+
+mod climb;
 mod crouch;
 mod dash;
+mod fly;
+mod glide;
+mod ground_pound;
 mod jump;
 mod knockback;
+mod ledge_grab;
+mod skate;
+mod ski;
+mod sprint;
+mod swim;
+mod swing;
+mod vehicle;
 mod walk;
+mod wall_jump;
+mod wall_run;
+mod wall_slide;
 
+pub use climb::{
+    TnuaBuiltinClimb, TnuaBuiltinClimbAttachment, TnuaBuiltinClimbState, TnuaClimbable,
+};
 pub use crouch::{TnuaBuiltinCrouch, TnuaBuiltinCrouchState};
 pub use dash::{TnuaBuiltinDash, TnuaBuiltinDashState};
-pub use jump::{TnuaBuiltinJump, TnuaBuiltinJumpState};
+pub use fly::{TnuaBuiltinFly, TnuaBuiltinFlyState};
+pub use glide::{TnuaBuiltinGlide, TnuaBuiltinGlideState};
+pub use ground_pound::{
+    TnuaBuiltinGroundPound, TnuaBuiltinGroundPoundState, TnuaGroundPoundImpact,
+    TnuaGroundPoundPlugin,
+};
+pub use jump::{TnuaBuiltinJump, TnuaBuiltinJumpState, TnuaJumpArc, TnuaJumpArcDerivedValues};
 pub use knockback::{TnuaBuiltinKnockback, TnuaBuiltinKnockbackState};
-pub use walk::{TnuaBuiltinWalk, TnuaBuiltinWalkState};
+pub use ledge_grab::{TnuaBuiltinLedgeGrab, TnuaBuiltinLedgeGrabState};
+pub use skate::{TnuaBuiltinSkate, TnuaBuiltinSkateState};
+pub use ski::{TnuaBuiltinSki, TnuaBuiltinSkiState};
+pub use sprint::{TnuaBuiltinSprint, TnuaBuiltinSprintState};
+pub use swim::{
+    TnuaBuiltinSwim, TnuaBuiltinSwimMode, TnuaBuiltinSwimState, TnuaSwimModeChanged, TnuaSwimPlugin,
+};
+pub use swing::{TnuaBuiltinSwing, TnuaBuiltinSwingState};
+pub use vehicle::{TnuaBuiltinVehicle, TnuaBuiltinVehicleState};
+pub use walk::{
+    TnuaBuiltinWalk, TnuaBuiltinWalkState, TnuaLandingSpeedRetention, TnuaTurningPolicy,
+};
+pub use wall_jump::{TnuaBuiltinWallJump, TnuaBuiltinWallJumpState};
+pub use wall_run::{TnuaBuiltinWallRun, TnuaBuiltinWallRunState};
+pub use wall_slide::{TnuaBuiltinWallSlide, TnuaBuiltinWallSlideState};
+
+/// Register the builtin bases, actions and their states for reflection.
+pub(crate) fn register_types(app: &mut App) {
+    app.register_type::<TnuaBuiltinClimb>();
+    app.register_type::<TnuaBuiltinClimbAttachment>();
+    app.register_type::<TnuaBuiltinClimbState>();
+    app.register_type::<TnuaClimbable>();
+    app.register_type::<TnuaBuiltinCrouch>();
+    app.register_type::<TnuaBuiltinCrouchState>();
+    app.register_type::<TnuaBuiltinDash>();
+    app.register_type::<TnuaBuiltinDashState>();
+    app.register_type::<TnuaBuiltinFly>();
+    app.register_type::<TnuaBuiltinFlyState>();
+    app.register_type::<TnuaBuiltinGlide>();
+    app.register_type::<TnuaBuiltinGlideState>();
+    app.register_type::<TnuaBuiltinGroundPound>();
+    app.register_type::<TnuaBuiltinGroundPoundState>();
+    app.register_type::<TnuaBuiltinJump>();
+    app.register_type::<TnuaBuiltinJumpState>();
+    app.register_type::<TnuaJumpArc>();
+    app.register_type::<TnuaJumpArcDerivedValues>();
+    app.register_type::<TnuaBuiltinKnockback>();
+    app.register_type::<TnuaBuiltinKnockbackState>();
+    app.register_type::<TnuaBuiltinLedgeGrab>();
+    app.register_type::<TnuaBuiltinLedgeGrabState>();
+    app.register_type::<TnuaBuiltinSkate>();
+    app.register_type::<TnuaBuiltinSkateState>();
+    app.register_type::<TnuaBuiltinSki>();
+    app.register_type::<TnuaBuiltinSkiState>();
+    app.register_type::<TnuaBuiltinSprint>();
+    app.register_type::<TnuaBuiltinSprintState>();
+    app.register_type::<TnuaBuiltinSwim>();
+    app.register_type::<TnuaBuiltinSwimState>();
+    app.register_type::<TnuaBuiltinSwimMode>();
+    app.register_type::<TnuaBuiltinSwing>();
+    app.register_type::<TnuaBuiltinSwingState>();
+    app.register_type::<TnuaBuiltinVehicle>();
+    app.register_type::<TnuaBuiltinVehicleState>();
+    app.register_type::<TnuaBuiltinWalk>();
+    app.register_type::<TnuaBuiltinWalkState>();
+    app.register_type::<TnuaLandingSpeedRetention>();
+    app.register_type::<TnuaTurningPolicy>();
+    app.register_type::<TnuaBuiltinWallJump>();
+    app.register_type::<TnuaBuiltinWallJumpState>();
+    app.register_type::<TnuaBuiltinWallRun>();
+    app.register_type::<TnuaBuiltinWallRunState>();
+    app.register_type::<TnuaBuiltinWallSlide>();
+    app.register_type::<TnuaBuiltinWallSlideState>();
+}
@@ -0,0 +1,194 @@
+use crate::math::{AdjustPrecision, AsF32, Float, Quaternion, Vector3};
+use bevy::prelude::*;
+
+use crate::builtins::{TnuaBuiltinWalk, TnuaBuiltinWalkState, TnuaTurningPolicy};
+use crate::controller::TnuaSurfaceTraction;
+use crate::TnuaBasis;
+use crate::TnuaBasisContext;
+
+/// A momentum based [basis](TnuaBasis) for skating, hoverboards and similar.
+///
+/// Unlike [`TnuaBuiltinWalk`], the input does not set the velocity the character moves at. The
+/// character keeps its momentum, and the input ([`desired_thrust`](Self::desired_thrust)) only
+/// affects it gradually:
+///
+/// * The part of the thrust along the direction the character moves in pushes it forward (up to
+///   [`max_push_speed`](Self::max_push_speed)), and the part against it brakes.
+/// * The part of the thrust to the side carves - turns the velocity without losing speed.
+///
+/// Slopes accelerate the character when it goes down and slow it down when it goes up, so with the
+/// right [`suspension`](Self::suspension) configuration (a low
+/// [`cling_distance`](TnuaBuiltinWalk::cling_distance) and no
+/// [`max_snap_distance`](TnuaBuiltinWalk::max_snap_distance)) the character will launch off
+/// ramps. In the air the character keeps its momentum and cannot push or carve.
+///
+/// Like in [`TnuaBuiltinVehicle`](crate::builtins::TnuaBuiltinVehicle), the floating (the hover
+/// part) is done by the [`suspension`](Self::suspension) walk basis. Its velocity, forward and
+/// acceleration fields are ignored.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSkate {
+    /// The direction (in the world space) of the thrust, with a length of up to `1.0` for the
+    /// strength of the thrust.
+    ///
+    /// Tnua assumes that this vector is orthogonal to the up direction.
+    pub desired_thrust: Vector3,
+
+    /// If non-zero, Tnua will rotate the character so that its negative Z will face in that
+    /// direction.
+    ///
+    /// When `None`, the character will face the direction it moves in.
+    pub desired_forward: Option<Dir3>,
+
+    /// The acceleration from pushing off at full thrust.
+    pub push_acceleration: Float,
+
+    /// Pushing off will not accelerate the character above this speed. The character can still go
+    /// faster than that, e.g. when going down a slope.
+    pub max_push_speed: Float,
+
+    /// The deceleration from thrusting against the direction the character moves in at full
+    /// thrust.
+    pub braking: Float,
+
+    /// The angular velocity, in radians per second, the velocity turns at when carving at full
+    /// thrust.
+    pub carve_angvel: Float,
+
+    /// A deceleration that is always applied on the ground, so that the character will eventually
+    /// stop when there is no thrust.
+    pub rolling_friction: Float,
+
+    /// The configuration of the floating. See the [type documentation](Self).
+    pub suspension: TnuaBuiltinWalk,
+}
+
+impl Default for TnuaBuiltinSkate {
+    fn default() -> Self {
+        Self {
+            desired_thrust: Vector3::ZERO,
+            desired_forward: None,
+            push_acceleration: 15.0,
+            max_push_speed: 12.0,
+            braking: 10.0,
+            carve_angvel: 3.0,
+            rolling_friction: 0.5,
+            suspension: Default::default(),
+        }
+    }
+}
+
+impl TnuaBasis for TnuaBuiltinSkate {
+    const NAME: &'static str = "TnuaBuiltinSkate";
+    type State = TnuaBuiltinSkateState;
+
+    fn apply(&self, state: &mut Self::State, ctx: TnuaBasisContext, motor: &mut crate::TnuaMotor) {
+        let up = ctx.up_direction.adjust_precision();
+        let ground_velocity = ctx
+            .proximity_sensor
+            .output
+            .as_ref()
+            .map_or(Vector3::ZERO, |sensor_output| sensor_output.entity_linvel);
+        let velocity_on_plane = (ctx.tracker.velocity - ground_velocity).reject_from(up);
+
+        let thrust = self.desired_thrust.reject_from(up).clamp_length_max(1.0);
+        let speed = velocity_on_plane.length();
+        let desired_velocity = if let Some(direction) = velocity_on_plane.try_normalize() {
+            let thrust_forward = thrust.dot(direction);
+            let thrust_sideways = thrust - direction * thrust_forward;
+
+            let mut new_speed = speed - self.rolling_friction * ctx.frame_duration;
+            if 0.0 <= thrust_forward {
+                if new_speed < self.max_push_speed {
+                    new_speed = (new_speed
+                        + thrust_forward * self.push_acceleration * ctx.frame_duration)
+                        .min(self.max_push_speed);
+                }
+            } else {
+                new_speed += thrust_forward * self.braking * ctx.frame_duration;
+            }
+
+            let carve_side = direction.cross(thrust_sideways).dot(up).signum();
+            let carve_angle =
+                carve_side * thrust_sideways.length() * self.carve_angvel * ctx.frame_duration;
+            Quaternion::from_axis_angle(up, carve_angle) * direction * new_speed.max(0.0)
+        } else {
+            thrust * self.push_acceleration * ctx.frame_duration
+        };
+        state.speed = desired_velocity.length();
+
+        let desired_forward = self.desired_forward.or_else(|| {
+            if state.speed == 0.0 {
+                None
+            } else {
+                Dir3::new(desired_velocity.f32()).ok()
+            }
+        });
+
+        TnuaBuiltinWalk {
+            desired_velocity,
+            desired_forward,
+            desired_look: None,
+            root_motion: None,
+            // The velocity change was already limited, so the walk basis should apply it fully.
+            acceleration: (desired_velocity - velocity_on_plane).length() / ctx.frame_duration,
+            acceleration_curve: None,
+            friction: None,
+            air_acceleration: 0.0,
+            air_acceleration_curve: None,
+            project_on_slopes: false,
+            turning_policy: TnuaTurningPolicy::DesiredForward,
+            ..self.suspension.clone()
+        }
+        .apply(
+            &mut state.walk,
+            TnuaBasisContext {
+                // So that slopes will affect the momentum.
+                surface_traction: TnuaSurfaceTraction {
+                    slide_down_slopes: true,
+                    ..ctx.surface_traction
+                },
+                ..ctx
+            },
+            motor,
+        );
+    }
+
+    fn proximity_sensor_cast_range(&self, state: &Self::State) -> Float {
+        self.suspension.proximity_sensor_cast_range(&state.walk)
+    }
+
+    fn displacement(&self, state: &Self::State) -> Option<Vector3> {
+        self.suspension.displacement(&state.walk)
+    }
+
+    fn effective_velocity(&self, state: &Self::State) -> Vector3 {
+        self.suspension.effective_velocity(&state.walk)
+    }
+
+    fn vertical_velocity(&self, state: &Self::State) -> Float {
+        self.suspension.vertical_velocity(&state.walk)
+    }
+
+    fn neutralize(&mut self) {
+        self.desired_thrust = Vector3::ZERO;
+        self.desired_forward = None;
+    }
+
+    fn is_airborne(&self, state: &Self::State) -> bool {
+        self.suspension.is_airborne(&state.walk)
+    }
+
+    fn violate_coyote_time(&self, state: &mut Self::State) {
+        self.suspension.violate_coyote_time(&mut state.walk)
+    }
+}
+
+#[derive(Default, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSkateState {
+    /// The state of the [`suspension`](TnuaBuiltinSkate::suspension) walk basis.
+    pub walk: TnuaBuiltinWalkState,
+    /// The horizontal speed the character is moving at.
+    pub speed: Float,
+}
@@ -0,0 +1,119 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor,
+};
+
+/// An [action](TnuaAction) for skiing down slopes, like in the Tribes games.
+///
+/// While this action is fed, the basis no longer corrects the character's horizontal velocity.
+/// Instead, the character keeps its momentum, slopes accelerate it with the part of the gravity
+/// that pulls it along the ground, and the input ([`desired_direction`](Self::desired_direction))
+/// only steers it with the weak [`steering_acceleration`](Self::steering_acceleration). The basis
+/// still controls the floating.
+///
+/// Jumps can be chained while skiing - the jump action will take over, and if this action is
+/// still fed it'll resume once the jump is over. With
+/// [`allow_in_air`](Self::allow_in_air) the character keeps its momentum in the air, so skiing
+/// down a slope and jumping off a ramp can launch it far.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSki {
+    /// The direction (in the world space) to steer toward, with a length of up to `1.0` for the
+    /// strength of the steering.
+    pub desired_direction: Vector3,
+
+    /// The acceleration from steering at full strength.
+    pub steering_acceleration: Float,
+
+    /// A factor for the part of the gravity that pulls the character along the ground.
+    pub gravity_factor: Float,
+
+    /// A deceleration that is always applied on the ground.
+    pub friction: Float,
+
+    /// Keep skiing (keep the momentum and steer) in the air.
+    ///
+    /// When `false`, the action is delayed until the character is on the ground, and the basis
+    /// controls the character in the air.
+    pub allow_in_air: bool,
+}
+
+impl Default for TnuaBuiltinSki {
+    fn default() -> Self {
+        Self {
+            desired_direction: Vector3::ZERO,
+            steering_acceleration: 5.0,
+            gravity_factor: 1.0,
+            friction: 0.5,
+            allow_in_air: true,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinSki {
+    const NAME: &'static str = "TnuaBuiltinSki";
+    type State = TnuaBuiltinSkiState;
+    const VIOLATES_COYOTE_TIME: bool = false;
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if self.allow_in_air || !ctx.basis.is_airborne() {
+            TnuaActionInitiationDirective::Allow
+        } else {
+            TnuaActionInitiationDirective::Delay
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        if !lifecycle_status.is_active() {
+            // Reschedule, so that skiing will resume after a jump if the action is still fed.
+            return lifecycle_status.directive_simple_reschedule(0.0);
+        }
+        let airborne = ctx.basis.is_airborne();
+        if airborne && !self.allow_in_air {
+            return TnuaActionLifecycleDirective::Reschedule { after_seconds: 0.0 };
+        }
+
+        let up = ctx.up_direction.adjust_precision();
+        let velocity_on_plane = ctx.basis.effective_velocity().reject_from(up);
+        state.speed = velocity_on_plane.length();
+
+        let mut acceleration = self.desired_direction.reject_from(up).clamp_length_max(1.0)
+            * self.steering_acceleration;
+        if let (false, Some(sensor_output)) = (airborne, &ctx.proximity_sensor.output) {
+            acceleration += ctx
+                .tracker
+                .gravity
+                .reject_from(sensor_output.normal.adjust_precision())
+                .reject_from(up)
+                * self.gravity_factor;
+            // Friction should stop the character, not make it go backward.
+            let friction = self.friction.min(state.speed / ctx.frame_duration);
+            acceleration -= velocity_on_plane.normalize_or_zero() * friction;
+        }
+
+        motor.lin.acceleration = motor.lin.acceleration.project_onto(up) + acceleration;
+        motor.lin.boost = motor.lin.boost.project_onto(up);
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+#[derive(Default, Clone, Debug, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSkiState {
+    /// The horizontal speed the character is moving at.
+    pub speed: Float,
+}
@@ -0,0 +1,145 @@
+use crate::math::{AdjustPrecision, Float};
+use bevy::prelude::*;
+
+use crate::{TnuaAction, TnuaMotor, TnuaStaminaCost};
+use crate::{
+    TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus,
+};
+
+use super::TnuaBuiltinWalk;
+
+/// An [action](TnuaAction) for sprinting. Only works when [`TnuaBuiltinWalk`] is the
+/// [basis](crate::TnuaBasis).
+///
+/// While the action is fed, the character moves at the walk basis'
+/// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) multiplied by the
+/// [`speed_factor`](Self::speed_factor), so the controls system can keep feeding the same desired
+/// velocity and only feed this action while the sprint button is held.
+///
+/// The sprint stops automatically when another action (e.g. a
+/// [crouch](crate::builtins::TnuaBuiltinCrouch) or a [jump](crate::builtins::TnuaBuiltinJump))
+/// takes over, and - unless [`cancel_when_aiming`](Self::cancel_when_aiming) is disabled - when
+/// the walk basis has a [`desired_look`](TnuaBuiltinWalk::desired_look). If the action is still
+/// fed afterward, the sprint will start again.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSprint {
+    /// A factor for the walk basis' [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) while
+    /// sprinting.
+    pub speed_factor: Float,
+
+    /// The acceleration for reaching the sprinting speed on the ground.
+    ///
+    /// When `None`, the walk basis' [`acceleration`](TnuaBuiltinWalk::acceleration) is used. In
+    /// the air, the walk basis' [`air_acceleration`](TnuaBuiltinWalk::air_acceleration) is always
+    /// used.
+    pub acceleration: Option<Float>,
+
+    /// The time, in seconds, it takes the speed factor to grow from `1.0` to
+    /// [`speed_factor`](Self::speed_factor) after the sprint starts.
+    pub ramp_up_time: Float,
+
+    /// Stop sprinting while the walk basis has a [`desired_look`](TnuaBuiltinWalk::desired_look),
+    /// which usually means the character is aiming.
+    pub cancel_when_aiming: bool,
+
+    /// The stamina the sprint costs, if the character has a
+    /// [`TnuaStamina`](crate::controller::TnuaStamina). Free by default.
+    pub stamina_cost: TnuaStaminaCost,
+}
+
+impl Default for TnuaBuiltinSprint {
+    fn default() -> Self {
+        Self {
+            speed_factor: 1.6,
+            acceleration: None,
+            ramp_up_time: 0.0,
+            cancel_when_aiming: true,
+            stamina_cost: Default::default(),
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinSprint {
+    const NAME: &'static str = "TnuaBuiltinSprint";
+    type State = TnuaBuiltinSprintState;
+    const VIOLATES_COYOTE_TIME: bool = false;
+
+    fn stamina_cost(&self) -> TnuaStaminaCost {
+        self.stamina_cost
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        let Some((walk_basis, _)) = ctx.concrete_basis::<TnuaBuiltinWalk>() else {
+            return TnuaActionInitiationDirective::Reject;
+        };
+        if self.is_prevented(walk_basis) || ctx.basis.is_airborne() {
+            TnuaActionInitiationDirective::Delay
+        } else {
+            TnuaActionInitiationDirective::Allow
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        let Some((walk_basis, _)) = ctx.concrete_basis::<TnuaBuiltinWalk>() else {
+            error!("Cannot sprint - basis is not TnuaBuiltinWalk");
+            return TnuaActionLifecycleDirective::Finished;
+        };
+        if !lifecycle_status.is_active() {
+            // Reschedule, so that the sprint will resume after the other action if the action
+            // is still fed.
+            return lifecycle_status.directive_simple_reschedule(0.0);
+        }
+        if self.is_prevented(walk_basis) {
+            return TnuaActionLifecycleDirective::Reschedule { after_seconds: 0.0 };
+        }
+
+        state.sprinting_for += ctx.frame_duration;
+        state.speed_factor = if state.sprinting_for < self.ramp_up_time {
+            1.0 + (self.speed_factor - 1.0) * (state.sprinting_for / self.ramp_up_time)
+        } else {
+            self.speed_factor
+        };
+
+        let max_acceleration = if ctx.basis.is_airborne() {
+            walk_basis.air_acceleration
+        } else {
+            self.acceleration.unwrap_or(walk_basis.acceleration)
+        };
+        let up = ctx.up_direction.adjust_precision();
+        let velocity_on_plane = ctx.basis.effective_velocity().reject_from(up);
+        let desired_boost = walk_basis.desired_velocity * state.speed_factor - velocity_on_plane;
+        motor.lin.acceleration = motor.lin.acceleration.project_onto(up)
+            + (desired_boost / ctx.frame_duration).clamp_length_max(max_acceleration);
+        motor.lin.boost = motor.lin.boost.project_onto(up);
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+impl TnuaBuiltinSprint {
+    fn is_prevented(&self, walk_basis: &TnuaBuiltinWalk) -> bool {
+        self.cancel_when_aiming && walk_basis.desired_look.is_some()
+    }
+}
+
+#[derive(Default, Clone, Debug, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSprintState {
+    /// The time, in seconds, since the sprint started.
+    pub sprinting_for: Float,
+    /// The factor currently applied to the desired velocity. Grows during the
+    /// [`ramp_up_time`](TnuaBuiltinSprint::ramp_up_time).
+    pub speed_factor: Float,
+}
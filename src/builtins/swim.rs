@@ -0,0 +1,335 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::controller::TnuaController;
+use crate::util::rotation_arc_around_axis;
+use crate::TnuaBasisContext;
+use crate::{TnuaBasis, TnuaPipelineStages, TnuaVelChange};
+
+/// A [basis](TnuaBasis) for swimming.
+///
+/// Unlike [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk), this basis does not use the
+/// ground proximity sensor. Instead, it applies buoyancy that pushes the character toward
+/// [`water_surface_height`](Self::water_surface_height), and lets the character move in all three
+/// dimensions using [`desired_velocity`](Self::desired_velocity).
+///
+/// The basis itself does not detect water volumes. The game code should detect when the character
+/// enters or leaves the water - either with
+/// [`TnuaWaterSensor`](crate::control_helpers::TnuaWaterSensor) or with its own sensor collider -
+/// and switch between feeding this basis and feeding the walk basis accordingly. Since neither
+/// basis relies on state that the other one needs to set, the switch is seamless.
+///
+/// The character is either [swimming at the surface](TnuaBuiltinSwimMode::Surface), where it
+/// moves horizontally and can dive by adding a downward component to the desired velocity, or
+/// [underwater](TnuaBuiltinSwimMode::Underwater) once it dives below
+/// [`submerged_depth`](Self::submerged_depth), where it moves in all three dimensions. With
+/// [`TnuaSwimPlugin`], a [`TnuaSwimModeChanged`] event is sent when the mode changes.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSwim {
+    /// The direction (in the world space) and speed to accelerate to.
+    ///
+    /// Unlike the walk basis, this vector does not have to be orthogonal to the up direction. If
+    /// it has a vertical component, the character will dive or rise and buoyancy will not be
+    /// applied.
+    pub desired_velocity: Vector3,
+
+    /// If non-zero, Tnua will rotate the character so that its negative Z will face in that
+    /// direction.
+    ///
+    /// Tnua assumes that this vector is orthogonal to the up direction.
+    pub desired_forward: Option<Dir3>,
+
+    /// The height of the water surface, along the up direction, in world coordinates.
+    pub water_surface_height: Float,
+
+    /// How deep below the water surface the center of the character will float at rest.
+    ///
+    /// The character is considered airborne when its center is above the water surface, so this
+    /// should be positive to let the character jump out of the water.
+    pub float_depth: Float,
+
+    /// The depth below which the character is considered
+    /// [underwater](TnuaBuiltinSwimMode::Underwater).
+    ///
+    /// Underwater, buoyancy is not applied and the character's vertical movement is controlled by
+    /// the desired velocity - so it can stay at any depth. It returns to the
+    /// [surface](TnuaBuiltinSwimMode::Surface) mode once it rises back to the
+    /// [`float_depth`](Self::float_depth).
+    ///
+    /// Set to infinity to never go underwater.
+    pub submerged_depth: Float,
+
+    /// The strength of the force that pushes the character toward its floating depth.
+    pub buoyancy: Float,
+
+    /// A force that slows down the character's vertical movement while buoyancy is applied, so
+    /// that it won't bob up and down for too long.
+    ///
+    /// The actual dampening is in direct linear relationship to the vertical velocity it tries to
+    /// dampen. Like the walk basis'
+    /// [`spring_dampening`](crate::builtins::TnuaBuiltinWalk::spring_dampening), this should be
+    /// kept below 2.0.
+    pub buoyancy_dampening: Float,
+
+    /// The maximum acceleration of the character's thrust.
+    pub acceleration: Float,
+
+    /// How fast the water slows down the character when there is no thrust.
+    ///
+    /// This is the fraction of the velocity lost per second.
+    pub drag: Float,
+
+    /// The maximum angular velocity used for keeping the character's forward. If you find the
+    /// character turns too slowly (or too fast), try changing this value.
+    pub turning_angvel: Float,
+}
+
+impl Default for TnuaBuiltinSwim {
+    fn default() -> Self {
+        Self {
+            desired_velocity: Vector3::ZERO,
+            desired_forward: None,
+            water_surface_height: 0.0,
+            float_depth: 0.5,
+            submerged_depth: 1.5,
+            buoyancy: 40.0,
+            buoyancy_dampening: 0.4,
+            acceleration: 30.0,
+            drag: 2.0,
+            turning_angvel: 10.0,
+        }
+    }
+}
+
+impl TnuaBasis for TnuaBuiltinSwim {
+    const NAME: &'static str = "TnuaBuiltinSwim";
+    type State = TnuaBuiltinSwimState;
+
+    fn apply(&self, state: &mut Self::State, ctx: TnuaBasisContext, motor: &mut crate::TnuaMotor) {
+        let up = ctx.up_direction.adjust_precision();
+        let velocity = ctx.tracker.velocity;
+
+        state.depth = self.water_surface_height - ctx.tracker.translation.dot(up);
+        state.float_offset = (self.float_depth - state.depth) * up;
+        state.effective_velocity = velocity;
+        let submerged = 0.0 < state.depth;
+
+        let mode = match state.mode {
+            TnuaBuiltinSwimMode::Surface if self.submerged_depth < state.depth => {
+                TnuaBuiltinSwimMode::Underwater
+            }
+            TnuaBuiltinSwimMode::Underwater if state.depth <= self.float_depth => {
+                TnuaBuiltinSwimMode::Surface
+            }
+            mode => mode,
+        };
+        if mode == state.mode {
+            state.mode_changed_from = None;
+            if mode == TnuaBuiltinSwimMode::Underwater {
+                state.underwater_duration += ctx.frame_duration;
+            }
+        } else {
+            state.mode_changed_from = Some(state.mode);
+            state.mode = mode;
+            state.underwater_duration = 0.0;
+        }
+
+        let vertical_input =
+            self.desired_velocity.dot(up) != 0.0 || state.mode == TnuaBuiltinSwimMode::Underwater;
+        let controlled_velocity = if submerged && vertical_input {
+            velocity
+        } else {
+            velocity.reject_from(up)
+        };
+
+        let thrust = if self.desired_velocity == Vector3::ZERO {
+            let drag = (self.drag * ctx.frame_duration).min(1.0);
+            TnuaVelChange::boost(-controlled_velocity * drag)
+        } else {
+            let desired_velocity = if submerged {
+                self.desired_velocity
+            } else {
+                // Out of the water, the character cannot use thrust to move upward.
+                self.desired_velocity.reject_from(up)
+            };
+            TnuaVelChange::acceleration(
+                ((desired_velocity - controlled_velocity) / ctx.frame_duration)
+                    .clamp_length_max(self.acceleration),
+            )
+        };
+
+        let vertical = if !submerged {
+            // Let gravity do its thing
+            TnuaVelChange::ZERO
+        } else if vertical_input {
+            // The thrust takes care of the vertical movement
+            TnuaVelChange::acceleration(-ctx.tracker.gravity)
+        } else {
+            let vertical_velocity = velocity.dot(up);
+            let spring_offset = state.depth - self.float_depth;
+            TnuaVelChange {
+                acceleration: up * spring_offset * self.buoyancy - ctx.tracker.gravity,
+                boost: up * -vertical_velocity * self.buoyancy_dampening,
+            }
+        };
+
+        motor.lin = thrust + vertical;
+
+        // Turning
+
+        let desired_angvel = if let Some(desired_forward) = self.desired_forward {
+            let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
+            let rotation_along_up_axis = rotation_arc_around_axis(
+                ctx.up_direction,
+                current_forward,
+                desired_forward.adjust_precision(),
+            )
+            .unwrap_or(0.0);
+            (rotation_along_up_axis / ctx.frame_duration)
+                .clamp(-self.turning_angvel, self.turning_angvel)
+        } else {
+            0.0
+        };
+
+        let existing_angvel = ctx.tracker.angvel.dot(up);
+        motor.ang = TnuaVelChange::boost((desired_angvel - existing_angvel) * up);
+    }
+
+    fn proximity_sensor_cast_range(&self, _state: &Self::State) -> Float {
+        0.0
+    }
+
+    fn displacement(&self, state: &Self::State) -> Option<Vector3> {
+        if self.is_airborne(state) {
+            None
+        } else {
+            Some(state.float_offset)
+        }
+    }
+
+    fn effective_velocity(&self, state: &Self::State) -> Vector3 {
+        state.effective_velocity
+    }
+
+    fn vertical_velocity(&self, _state: &Self::State) -> Float {
+        0.0
+    }
+
+    fn neutralize(&mut self) {
+        self.desired_velocity = Vector3::ZERO;
+        self.desired_forward = None;
+    }
+
+    fn is_airborne(&self, state: &Self::State) -> bool {
+        state.depth <= 0.0
+    }
+
+    fn violate_coyote_time(&self, _state: &mut Self::State) {}
+}
+
+#[derive(Default, Clone, Debug, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSwimState {
+    /// How deep below the water surface the center of the character is. Negative when the
+    /// character is above the water.
+    pub depth: Float,
+    /// The displacement of the character from its floating depth.
+    pub float_offset: Vector3,
+    /// Whether the character swims at the surface or underwater.
+    pub mode: TnuaBuiltinSwimMode,
+    /// The time, in seconds, since the character went underwater - e.g. for a breath timer. Zero
+    /// when the character is at the surface.
+    pub underwater_duration: Float,
+    mode_changed_from: Option<TnuaBuiltinSwimMode>,
+    effective_velocity: Vector3,
+}
+
+impl TnuaBuiltinSwimState {
+    /// The mode the character was in before, if the mode has changed this frame.
+    pub fn mode_changed_from(&self) -> Option<TnuaBuiltinSwimMode> {
+        self.mode_changed_from
+    }
+}
+
+/// See [`TnuaBuiltinSwimState::mode`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinSwimMode {
+    /// The character floats at the water surface and moves horizontally.
+    #[default]
+    Surface,
+    /// The character is below [`TnuaBuiltinSwim::submerged_depth`], and moves in all three
+    /// dimensions.
+    Underwater,
+}
+
+pub struct TnuaSwimPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaSwimPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaSwimPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin for sending [`TnuaSwimModeChanged`] events.
+///
+/// The schedule should be the same one
+/// [`TnuaControllerPlugin`](crate::controller::TnuaControllerPlugin) uses.
+impl Plugin for TnuaSwimPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TnuaSwimModeChanged>();
+        app.add_systems(
+            self.schedule,
+            send_swim_mode_changed_events
+                .in_set(TnuaPipelineStages::Logic)
+                .after(crate::controller::apply_controller_system),
+        );
+    }
+}
+
+/// Sent by [`TnuaSwimPlugin`] when a character using [`TnuaBuiltinSwim`] goes underwater or
+/// returns to the surface.
+#[derive(Event, Debug, Clone)]
+pub struct TnuaSwimModeChanged {
+    /// The character entity.
+    pub entity: Entity,
+    /// The mode the character was in.
+    pub from: TnuaBuiltinSwimMode,
+    /// The mode the character is now in.
+    pub to: TnuaBuiltinSwimMode,
+}
+
+fn send_swim_mode_changed_events(
+    time: Res<Time>,
+    query: Query<(Entity, &TnuaController)>,
+    mut writer: EventWriter<TnuaSwimModeChanged>,
+) {
+    if time.delta().is_zero() {
+        // The controller was not updated this frame, so any change was already reported.
+        return;
+    }
+    for (entity, controller) in query.iter() {
+        let Some((_, state)) = controller.concrete_basis::<TnuaBuiltinSwim>() else {
+            continue;
+        };
+        if let Some(from) = state.mode_changed_from {
+            writer.send(TnuaSwimModeChanged {
+                entity,
+                from,
+                to: state.mode,
+            });
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::math::{Float, Vector3};
+use bevy::reflect::Reflect;
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor, TnuaVelChange,
+};
+
+/// An [action](TnuaAction) for swinging from a rope - e.g. for a grappling hook.
+///
+/// The action should be fed for as long as the character holds the rope, with the
+/// [`anchor`](Self::anchor) point the rope is attached to. While the rope is taut, the character
+/// moves along a pendulum arc around the anchor. While it is slack, the character falls freely (or
+/// walks, if it is on the ground) until the rope becomes taut again.
+///
+/// Stop feeding the action to release the rope. The character keeps its momentum (scaled by
+/// [`release_momentum_preservation`](Self::release_momentum_preservation)) and the basis takes
+/// over again. Note that [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk) will still use its
+/// [`air_acceleration`](crate::builtins::TnuaBuiltinWalk::air_acceleration) to bring the carried
+/// momentum toward its `desired_velocity`.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSwing {
+    /// The point (in world coordinates) the rope is attached to.
+    ///
+    /// This is not cached, so it can be updated every frame in order to swing from moving
+    /// objects.
+    pub anchor: Vector3,
+
+    /// The maximum length of the rope.
+    ///
+    /// When the action starts, the rope length is set to the distance of the character from the
+    /// [`anchor`](Self::anchor) - unless that distance is longer than this.
+    pub rope_length: Float,
+
+    /// The minimum length the rope can be reeled in to.
+    pub min_rope_length: Float,
+
+    /// The speed in which the rope is reeled in. Use a negative number to reel it out.
+    pub reel_in_speed: Float,
+
+    /// The direction (in the world space) and speed the player wants the character to swing at.
+    ///
+    /// Only the part perpendicular to the rope is used.
+    pub desired_velocity: Vector3,
+
+    /// The acceleration used for pushing the character toward
+    /// [`desired_velocity`](Self::desired_velocity).
+    pub swing_acceleration: Float,
+
+    /// The part of the velocity the character keeps when it releases the rope.
+    pub release_momentum_preservation: Float,
+}
+
+impl Default for TnuaBuiltinSwing {
+    fn default() -> Self {
+        Self {
+            anchor: Vector3::ZERO,
+            rope_length: 10.0,
+            min_rope_length: 1.0,
+            reel_in_speed: 0.0,
+            desired_velocity: Vector3::ZERO,
+            swing_acceleration: 5.0,
+            release_momentum_preservation: 1.0,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinSwing {
+    const NAME: &'static str = "TnuaBuiltinSwing";
+    type State = TnuaBuiltinSwingState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn initiation_decision(
+        &self,
+        _ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        TnuaActionInitiationDirective::Allow
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        if !lifecycle_status.is_active() {
+            motor.lin += TnuaVelChange::boost(
+                (self.release_momentum_preservation - 1.0) * ctx.tracker.velocity,
+            );
+            return TnuaActionLifecycleDirective::Finished;
+        }
+
+        let from_anchor = ctx.tracker.translation - self.anchor;
+        let distance = from_anchor.length();
+        let rope_length = state
+            .rope_length
+            .get_or_insert(distance.min(self.rope_length));
+        *rope_length = (*rope_length - self.reel_in_speed * ctx.frame_duration)
+            .min(self.rope_length)
+            .max(self.min_rope_length);
+
+        let is_taut = *rope_length <= distance;
+        state.is_taut = is_taut;
+        if !is_taut && !ctx.basis.is_airborne() {
+            // The rope is slack, so let the basis move the character on the ground.
+            return TnuaActionLifecycleDirective::StillActive;
+        }
+
+        let rope_direction = from_anchor.normalize_or_zero();
+        let velocity = ctx.tracker.velocity;
+
+        let desired_velocity = self.desired_velocity.reject_from(rope_direction);
+        let swing_direction = desired_velocity.normalize_or_zero();
+        let swing_acceleration = if velocity.dot(swing_direction) < desired_velocity.length() {
+            self.swing_acceleration * swing_direction
+        } else {
+            Vector3::ZERO
+        };
+
+        let mut new_velocity =
+            velocity + (ctx.tracker.gravity + swing_acceleration) * ctx.frame_duration;
+
+        // Don't let the character move away from the anchor past the rope's length - and if it
+        // already did, pull it back in.
+        let radial_velocity = new_velocity.dot(rope_direction);
+        let max_radial_velocity = (*rope_length - distance) / ctx.frame_duration;
+        if max_radial_velocity < radial_velocity {
+            new_velocity += (max_radial_velocity - radial_velocity) * rope_direction;
+        }
+
+        motor.lin = TnuaVelChange {
+            acceleration: -ctx.tracker.gravity,
+            boost: new_velocity - velocity,
+        };
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinSwingState {
+    /// The current length of the rope. `None` before the swing has started.
+    pub rope_length: Option<Float>,
+    /// Whether the rope is currently stretched to its full length.
+    pub is_taut: bool,
+}
@@ -0,0 +1,195 @@
+use crate::math::{ops, AdjustPrecision, AsF32, Float, Quaternion, Vector3};
+use bevy::prelude::*;
+
+use crate::builtins::{TnuaBuiltinWalk, TnuaBuiltinWalkState, TnuaTurningPolicy};
+use crate::TnuaBasis;
+use crate::TnuaBasisContext;
+
+/// A [basis](TnuaBasis) for cars, mounts and other vehicles.
+///
+/// Instead of a desired velocity, this basis is controlled by a [`throttle`](Self::throttle) that
+/// accelerates the character along its forward direction (its negative Z) and a
+/// [`steering`](Self::steering) that turns it - faster the faster the vehicle moves, so that it
+/// cannot turn in place. Sideways velocity is removed according to the [`drift`](Self::drift) -
+/// a vehicle with full grip always moves where it faces.
+///
+/// The floating - which acts as the vehicle's suspension - is done by the
+/// [`suspension`](Self::suspension) walk basis, so that the same entity can switch between walking
+/// and driving (e.g. when mounting and dismounting) while keeping the same float configuration.
+/// Its [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) and
+/// [`desired_forward`](TnuaBuiltinWalk::desired_forward) are ignored, and so are its acceleration
+/// and turning limits - the vehicle's own limits are used instead. Actions that rely on the walk
+/// basis (like [`TnuaBuiltinCrouch`](crate::builtins::TnuaBuiltinCrouch)) will not work with this
+/// basis.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinVehicle {
+    /// The forward (positive) or reverse (negative) throttle, between `-1.0` and `1.0`.
+    pub throttle: Float,
+
+    /// How much to turn right (positive) or left (negative), between `-1.0` and `1.0`.
+    ///
+    /// When driving in reverse the turn is inverted, like in a real car.
+    pub steering: Float,
+
+    /// The speed the vehicle will reach at full forward throttle.
+    pub max_speed: Float,
+
+    /// The speed the vehicle will reach at full reverse throttle.
+    pub max_reverse_speed: Float,
+
+    /// The acceleration when the throttle is applied.
+    pub acceleration: Float,
+
+    /// The deceleration when the throttle is applied against the direction the vehicle moves in.
+    pub braking: Float,
+
+    /// The deceleration when the throttle is released (or is lower than the vehicle's speed).
+    pub coast_deceleration: Float,
+
+    /// The maximum angular velocity, in radians per second, for turning at full steering.
+    pub turn_rate: Float,
+
+    /// The speed from which the vehicle turns at the full [`turn_rate`](Self::turn_rate). Below
+    /// it, the turn rate is proportional to the speed.
+    pub full_turn_rate_speed: Float,
+
+    /// The fraction of the sideways velocity the vehicle keeps every second.
+    ///
+    /// At `0.0` the vehicle has full grip and never slides sideways. Values closer to `1.0` let it
+    /// drift when it turns at high speed.
+    pub drift: Float,
+
+    /// The configuration of the floating. See the [type documentation](Self).
+    pub suspension: TnuaBuiltinWalk,
+}
+
+impl Default for TnuaBuiltinVehicle {
+    fn default() -> Self {
+        Self {
+            throttle: 0.0,
+            steering: 0.0,
+            max_speed: 20.0,
+            max_reverse_speed: 5.0,
+            acceleration: 10.0,
+            braking: 30.0,
+            coast_deceleration: 3.0,
+            turn_rate: 2.0,
+            full_turn_rate_speed: 5.0,
+            drift: 0.0,
+            suspension: Default::default(),
+        }
+    }
+}
+
+impl TnuaBasis for TnuaBuiltinVehicle {
+    const NAME: &'static str = "TnuaBuiltinVehicle";
+    type State = TnuaBuiltinVehicleState;
+
+    fn apply(&self, state: &mut Self::State, ctx: TnuaBasisContext, motor: &mut crate::TnuaMotor) {
+        let up = ctx.up_direction.adjust_precision();
+        let ground_velocity = ctx
+            .proximity_sensor
+            .output
+            .as_ref()
+            .map_or(Vector3::ZERO, |sensor_output| sensor_output.entity_linvel);
+        let velocity_on_plane = (ctx.tracker.velocity - ground_velocity).reject_from(up);
+        let forward = ctx
+            .tracker
+            .rotation
+            .mul_vec3(Vector3::NEG_Z)
+            .reject_from(up)
+            .normalize_or_zero();
+
+        let current_speed = velocity_on_plane.dot(forward);
+        let sideways_velocity = velocity_on_plane - forward * current_speed;
+
+        let throttle = self.throttle.clamp(-1.0, 1.0);
+        let target_speed = if 0.0 <= throttle {
+            throttle * self.max_speed
+        } else {
+            throttle * self.max_reverse_speed
+        };
+        let deceleration = if current_speed * throttle < 0.0 {
+            self.braking
+        } else if throttle == 0.0 || target_speed.abs() < current_speed.abs() {
+            self.coast_deceleration
+        } else {
+            self.acceleration
+        };
+        let max_speed_change = deceleration * ctx.frame_duration;
+        let new_speed = current_speed
+            + (target_speed - current_speed).clamp(-max_speed_change, max_speed_change);
+        state.speed = new_speed;
+
+        let sideways_retention = ops::powf(self.drift.clamp(0.0, 1.0), ctx.frame_duration);
+        let desired_velocity = forward * new_speed + sideways_velocity * sideways_retention;
+
+        let turn_factor = if 0.0 < self.full_turn_rate_speed {
+            (new_speed / self.full_turn_rate_speed).clamp(-1.0, 1.0)
+        } else {
+            new_speed.signum()
+        };
+        // Rotating around the up axis by a positive angle turns left.
+        let turn_angle =
+            -self.steering.clamp(-1.0, 1.0) * self.turn_rate * turn_factor * ctx.frame_duration;
+        let desired_forward =
+            Dir3::new((Quaternion::from_axis_angle(up, turn_angle) * forward).f32()).ok();
+
+        TnuaBuiltinWalk {
+            desired_velocity,
+            desired_forward,
+            desired_look: None,
+            root_motion: None,
+            // The speed change was already limited, so the walk basis should apply it fully.
+            acceleration: (desired_velocity - velocity_on_plane).length() / ctx.frame_duration,
+            acceleration_curve: None,
+            friction: None,
+            air_acceleration: 0.0,
+            air_acceleration_curve: None,
+            project_on_slopes: false,
+            turning_policy: TnuaTurningPolicy::DesiredForward,
+            turning_angvel: Float::INFINITY,
+            ..self.suspension.clone()
+        }
+        .apply(&mut state.walk, ctx, motor);
+    }
+
+    fn proximity_sensor_cast_range(&self, state: &Self::State) -> Float {
+        self.suspension.proximity_sensor_cast_range(&state.walk)
+    }
+
+    fn displacement(&self, state: &Self::State) -> Option<Vector3> {
+        self.suspension.displacement(&state.walk)
+    }
+
+    fn effective_velocity(&self, state: &Self::State) -> Vector3 {
+        self.suspension.effective_velocity(&state.walk)
+    }
+
+    fn vertical_velocity(&self, state: &Self::State) -> Float {
+        self.suspension.vertical_velocity(&state.walk)
+    }
+
+    fn neutralize(&mut self) {
+        self.throttle = 0.0;
+        self.steering = 0.0;
+    }
+
+    fn is_airborne(&self, state: &Self::State) -> bool {
+        self.suspension.is_airborne(&state.walk)
+    }
+
+    fn violate_coyote_time(&self, state: &mut Self::State) {
+        self.suspension.violate_coyote_time(&mut state.walk)
+    }
+}
+
+#[derive(Default, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinVehicleState {
+    /// The state of the [`suspension`](TnuaBuiltinVehicle::suspension) walk basis.
+    pub walk: TnuaBuiltinWalkState,
+    /// The speed of the vehicle along its forward direction. Negative when driving in reverse.
+    pub speed: Float,
+}
@@ -3,7 +3,7 @@ use std::time::Duration;
 use crate::math::{float_consts, AdjustPrecision, AsF32, Float, Quaternion, Vector3};
 use bevy::prelude::*;
 
-use crate::util::rotation_arc_around_axis;
+use crate::util::{rotation_arc_around_axis, TnuaCurve};
 use crate::TnuaBasisContext;
 use crate::{TnuaBasis, TnuaVelChange};
 
@@ -32,7 +32,8 @@ use crate::{TnuaBasis, TnuaVelChange};
 ///   leaving `desired_forward` as the default `Vector3::ZERO` may cause the character to spin
 ///   uncontrollably when it contacts other colliders. Unless, of course, some other mechanism
 ///   prevents that.
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaBuiltinWalk {
     /// The direction (in the world space) and speed to accelerate to.
     ///
@@ -43,8 +44,32 @@ pub struct TnuaBuiltinWalk {
     /// direction.
     ///
     /// Tnua assumes that this vector is orthogonal to the up direction.
+    ///
+    /// When `None`, the character will keep its facing relative to the entity it stands on - so
+    /// it'll turn together with rotating platforms.
+    ///
+    /// Only used with the default [`turning_policy`](Self::turning_policy).
     pub desired_forward: Option<Dir3>,
 
+    /// If set, the character will face that direction regardless of the
+    /// [`turning_policy`](Self::turning_policy), turning at [`look_angvel`](Self::look_angvel).
+    ///
+    /// This allows the character to run in one direction and aim in another - e.g. use
+    /// [`TnuaTurningPolicy::DesiredVelocity`] for facing the movement direction, and set this
+    /// while the player aims. Tnua assumes that this vector is orthogonal to the up direction.
+    pub desired_look: Option<Dir3>,
+
+    /// A displacement (in the world space) to move the character by this frame, instead of using
+    /// [`desired_velocity`](Self::desired_velocity).
+    ///
+    /// This is meant for root motion - set it to the displacement of the animation's root bone
+    /// since the previous frame, and the character will move with the velocity required to cover
+    /// that displacement, ignoring the [`acceleration`](Self::acceleration) and
+    /// [`air_acceleration`](Self::air_acceleration) limits. Only the part orthogonal to the up
+    /// direction is used - the float spring still controls the height, the movement still follows
+    /// the slope, and platforms the character stands on still carry it.
+    pub root_motion: Option<Vector3>,
+
     /// The height at which the character will float above ground at rest.
     ///
     /// Note that this is the height of the character's center of mass - not the distance from its
@@ -82,13 +107,43 @@ pub struct TnuaBuiltinWalk {
     /// Note that this is the acceleration for starting the horizontal motion and for reaching the
     /// top speed. When braking or changing direction the acceleration is greater, up to 2 times
     /// `acceleration` when doing a 180 turn.
+    ///
+    /// On the ground, this is multiplied by the factors of the
+    /// [`TnuaSurfaceTraction`](crate::controller::TnuaSurfaceTraction) of the ground entity.
     pub acceleration: Float,
 
+    /// When set, replaces [`acceleration`](Self::acceleration) with a curve that maps the
+    /// character's current horizontal speed to the acceleration.
+    ///
+    /// This allows, for example, a fast initial burst followed by a slow approach to the top
+    /// speed. Like `acceleration`, the result is increased when braking or changing direction.
+    pub acceleration_curve: Option<TnuaCurve>,
+
+    /// When set, replaces [`acceleration`](Self::acceleration) (and
+    /// [`acceleration_curve`](Self::acceleration_curve)) on the ground when the character slows
+    /// down - that is, when the desired speed is lower than the character's current speed.
+    ///
+    /// This allows stopping at a different rate than starting to move. Unlike `acceleration`, it
+    /// is not increased when changing direction.
+    pub friction: Option<Float>,
+
     /// The acceleration for horizontal movement while in the air.
     ///
     /// Set to 0.0 to completely disable air movement.
     pub air_acceleration: Float,
 
+    /// When set, replaces [`air_acceleration`](Self::air_acceleration) with a curve that maps the
+    /// angle (in radians, from `0.0` to `PI`) between the character's current velocity and the
+    /// desired velocity to the acceleration.
+    ///
+    /// This allows, for example, fine corrections of the direction in mid-air while preventing
+    /// full reversals. When the character is not moving the angle is `0.0`, and when the desired
+    /// velocity is zero (the character is braking) the angle is `PI`.
+    ///
+    /// Unlike `air_acceleration`, the result is not increased when changing direction - the curve
+    /// should already include that.
+    pub air_acceleration_curve: Option<TnuaCurve>,
+
     /// The time, in seconds, the character can still jump after losing their footing.
     pub coyote_time: Float,
 
@@ -117,8 +172,133 @@ pub struct TnuaBuiltinWalk {
     /// The maximum angular velocity used for turning the character when the direction changes.
     pub turning_angvel: Float,
 
+    /// The maximum angular velocity used for turning the character toward the
+    /// [`desired_look`](Self::desired_look) direction.
+    pub look_angvel: Float,
+
+    /// Which direction the character turns to face.
+    ///
+    /// See [`TnuaTurningPolicy`].
+    pub turning_policy: TnuaTurningPolicy,
+
     /// The maximum slope, in radians, that the character can stand on without slipping.
+    ///
+    /// Ground steeper than this is treated like a wall: the spring will not hold the character
+    /// above it, the character will not be considered grounded (so the coyote time will not be
+    /// refreshed by touching it) and it'll slide down - even if it tries to walk up.
+    ///
+    /// Use [`TnuaBuiltinWalkState::is_slipping`] to check if the character is sliding down such a
+    /// slope, e.g. for playing a sliding animation.
     pub max_slope: Float,
+
+    /// An acceleration, down the slope, applied to the character while it slides down ground
+    /// steeper than [`max_slope`](Self::max_slope) - in addition to the gravity.
+    pub steep_slope_slide_acceleration: Float,
+
+    /// When walking on a slope, adjust the [`desired_velocity`](Self::desired_velocity) so that
+    /// the speed along the slope (rather than the horizontal speed) will match its length.
+    ///
+    /// Without it, the character moves faster on steep slopes because it also moves vertically.
+    pub project_on_slopes: bool,
+
+    /// The maximum distance above the [`float_height`](Self::float_height) from which the
+    /// character will be snapped down to the ground.
+    ///
+    /// When walking off a small ledge or down a convex slope, the spring alone may be too soft to
+    /// keep the character on the ground, making it briefly airborne. With snapping, as long as the
+    /// ground is no farther than this distance, the character will be moved straight to the float
+    /// height instead. Snapping only happens while the character is grounded and not moving upward
+    /// relative to the ground - so it will not interfere with jumps.
+    ///
+    /// This may be bigger than the [`cling_distance`](Self::cling_distance). Set to 0.0 to disable
+    /// snapping.
+    pub max_snap_distance: Float,
+
+    /// The acceleration used for cancelling velocity the character did not cause itself.
+    ///
+    /// When something else changes the character's velocity - e.g. it gets hit, caught in an
+    /// explosion, or bumped by another character - the walk basis will not immediately fight that
+    /// change with its full [`acceleration`](Self::acceleration). Instead, it'll keep that external
+    /// velocity, reducing it toward zero with this acceleration. Collisions that merely stop the
+    /// character (like walking into a wall) are not considered external velocity.
+    ///
+    /// Set to infinity (the default) to treat external velocity like any other velocity.
+    pub external_velocity_recovery: Float,
+
+    /// What to do with horizontal speed above the desired speed (the length of
+    /// [`desired_velocity`](Self::desired_velocity)) when the character lands.
+    ///
+    /// See [`TnuaLandingSpeedRetention`].
+    pub landing_speed_retention: TnuaLandingSpeedRetention,
+
+    /// How much of the horizontal part of the
+    /// [`external_acceleration`](crate::TnuaBasisContext::external_acceleration) (e.g. wind) the
+    /// character resists.
+    ///
+    /// At `0.0` (the default) the velocity the external acceleration causes is added to the
+    /// velocity the character moves at, and at `1.0` the character corrects it like any other
+    /// deviation from the desired velocity. Once the external acceleration stops, the velocity it
+    /// has added is reduced with the regular [`acceleration`](Self::acceleration) (or
+    /// [`air_acceleration`](Self::air_acceleration) in the air).
+    pub wind_resistance: Float,
+
+    /// Slows down the velocity caused by the horizontal part of the
+    /// [`external_acceleration`](crate::TnuaBasisContext::external_acceleration), proportionally
+    /// to that velocity.
+    ///
+    /// This gives a constant wind a terminal speed - the wind's acceleration (after
+    /// [`wind_resistance`](Self::wind_resistance)) divided by the drag - instead of making the
+    /// character go faster and faster. Setting it to `0.0` removes the limit.
+    pub wind_drag: Float,
+}
+
+/// How [`TnuaBuiltinWalk`] treats horizontal speed above the desired speed when the character
+/// lands.
+///
+/// The speed is only retained while the character keeps moving (the desired velocity is not zero),
+/// and it is redirected toward the desired direction. Stopping the character works as usual.
+#[derive(PartialEq, Debug, Clone, Copy, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaLandingSpeedRetention {
+    /// Correct the excess speed like any other difference from the desired velocity, with the
+    /// regular [`acceleration`](TnuaBuiltinWalk::acceleration).
+    #[default]
+    Correct,
+
+    /// Remove the excess speed at once, in the frame the character lands.
+    Clamp,
+
+    /// Reduce the excess speed with the specified deceleration, which is usually lower than the
+    /// regular [`acceleration`](TnuaBuiltinWalk::acceleration).
+    Decay { deceleration: Float },
+
+    /// Keep the full speed for the specified duration (in seconds) after landing, and then correct
+    /// it as usual.
+    ///
+    /// This is meant for bunny-hopping: a jump that was pressed before landing and got buffered
+    /// will start during this window, and the character will take off with the retained speed.
+    /// Even a duration of `0.0` will keep the speed for the frame where the character lands.
+    KeepFor { duration: Float },
+}
+
+/// Which direction [`TnuaBuiltinWalk`] turns the character to face.
+#[derive(PartialEq, Debug, Clone, Copy, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaTurningPolicy {
+    /// Face the [`desired_forward`](TnuaBuiltinWalk::desired_forward) direction. This is usually
+    /// the input direction, but can also be a fixed direction or one provided by some other system.
+    #[default]
+    DesiredForward,
+
+    /// Face the direction of the [`desired_velocity`](TnuaBuiltinWalk::desired_velocity), keeping
+    /// the facing when it is zero.
+    DesiredVelocity,
+
+    /// Face the direction the character actually moves in (relative to the entity it stands on),
+    /// so that a character that gets knocked back or slides will face where it's going.
+    ///
+    /// When the character's horizontal speed is below `min_speed` it keeps its facing.
+    Velocity { min_speed: Float },
 }
 
 impl Default for TnuaBuiltinWalk {
@@ -126,18 +306,32 @@ impl Default for TnuaBuiltinWalk {
         Self {
             desired_velocity: Vector3::ZERO,
             desired_forward: None,
+            desired_look: None,
+            root_motion: None,
             float_height: 0.0,
             cling_distance: 1.0,
             spring_strength: 400.0,
             spring_dampening: 1.2,
             acceleration: 60.0,
+            acceleration_curve: None,
+            friction: None,
             air_acceleration: 20.0,
+            air_acceleration_curve: None,
             coyote_time: 0.15,
             free_fall_extra_gravity: 60.0,
             tilt_offset_angvel: 5.0,
             tilt_offset_angacl: 500.0,
             turning_angvel: 10.0,
+            look_angvel: 20.0,
+            turning_policy: TnuaTurningPolicy::DesiredForward,
             max_slope: float_consts::FRAC_PI_2,
+            steep_slope_slide_acceleration: 0.0,
+            project_on_slopes: false,
+            max_snap_distance: 0.0,
+            external_velocity_recovery: Float::INFINITY,
+            landing_speed_retention: TnuaLandingSpeedRetention::Correct,
+            wind_resistance: 0.0,
+            wind_drag: 1.0,
         }
     }
 }
@@ -213,6 +407,7 @@ impl TnuaBasis for TnuaBuiltinWalk {
                     state.standing_on = Some(StandingOnState {
                         entity: sensor_output.entity,
                         entity_linvel: sensor_output.entity_linvel,
+                        entity_angvel: sensor_output.entity_angvel,
                     });
                 } else {
                     considered_in_air = true;
@@ -228,25 +423,87 @@ impl TnuaBasis for TnuaBuiltinWalk {
             state.standing_on = None;
         }
         state.effective_velocity += impulse_to_offset;
+        state.slipping = slipping_vector.is_some();
+        let steep_slope_slide_acceleration = match (&ctx.proximity_sensor.output, slipping_vector) {
+            (Some(sensor_output), Some(_)) => {
+                ctx.tracker
+                    .gravity
+                    .reject_from(sensor_output.normal.adjust_precision())
+                    .normalize_or_zero()
+                    * self.steep_slope_slide_acceleration
+            }
+            _ => Vector3::ZERO,
+        };
 
         let velocity_on_plane = state
             .effective_velocity
             .reject_from(ctx.up_direction.adjust_precision());
 
-        let desired_boost = self.desired_velocity - velocity_on_plane;
+        self.update_external_velocity(state, &ctx, motor);
+        let target_velocity = if let Some(root_motion) = self.root_motion {
+            root_motion.reject_from(ctx.up_direction.adjust_precision()) / ctx.frame_duration
+        } else if self.project_on_slopes && !considered_in_air && slipping_vector.is_none() {
+            Self::velocity_on_slope(&ctx, self.desired_velocity)
+        } else {
+            self.desired_velocity
+        } + state.external_velocity;
+
+        self.update_wind_velocity(state, &ctx, considered_in_air);
+        // In the air the slide momentum is kept, and slipping down steep slopes is handled
+        // separately.
+        self.update_slide_velocity(state, &ctx, considered_in_air || slipping_vector.is_some());
+        let target_velocity = target_velocity + state.wind_velocity + state.slide_velocity;
+
+        let (target_velocity, landing_clamp_boost) = self.retain_landing_speed(
+            state,
+            &ctx,
+            considered_in_air,
+            velocity_on_plane,
+            target_velocity,
+        );
+        let velocity_on_plane = velocity_on_plane + landing_clamp_boost;
+
+        let desired_boost = target_velocity - velocity_on_plane;
 
-        let safe_direction_coefficient = self
-            .desired_velocity
+        let safe_direction_coefficient = target_velocity
             .normalize_or_zero()
             .dot(velocity_on_plane.normalize_or_zero());
         let direction_change_factor = 1.5 - 0.5 * safe_direction_coefficient;
 
+        let slowing_down = target_velocity.length() < velocity_on_plane.length();
         let relevant_acceleration_limit = if considered_in_air {
             self.air_acceleration
+        } else if let (true, Some(friction)) = (slowing_down, self.friction) {
+            // Cancel the direction change factor, since friction should not depend on it.
+            friction / direction_change_factor
+        } else if let Some(acceleration_curve) = &self.acceleration_curve {
+            acceleration_curve.sample(velocity_on_plane.length())
         } else {
             self.acceleration
         };
-        let max_acceleration = direction_change_factor * relevant_acceleration_limit;
+        let traction_factor = if considered_in_air {
+            1.0
+        } else if desired_boost.dot(velocity_on_plane) < 0.0 {
+            ctx.surface_traction.deceleration_factor
+        } else {
+            ctx.surface_traction.acceleration_factor
+        };
+        let max_acceleration = if self.root_motion.is_some() {
+            Float::INFINITY
+        } else if let (true, Some(air_acceleration_curve)) =
+            (considered_in_air, &self.air_acceleration_curve)
+        {
+            let angle = if velocity_on_plane == Vector3::ZERO {
+                0.0
+            } else if target_velocity == Vector3::ZERO {
+                float_consts::PI
+            } else {
+                velocity_on_plane.angle_between(target_velocity)
+            };
+            air_acceleration_curve.sample(angle)
+        } else {
+            traction_factor * direction_change_factor * relevant_acceleration_limit
+        };
 
         state.vertical_velocity = if let Some(climb_vectors) = &climb_vectors {
             state.effective_velocity.dot(climb_vectors.direction)
@@ -257,9 +514,12 @@ impl TnuaBasis for TnuaBuiltinWalk {
             0.0
         };
 
-        let walk_vel_change = if self.desired_velocity == Vector3::ZERO && slipping_vector.is_none()
+        let walk_vel_change = if (self.desired_velocity == Vector3::ZERO
+            || self.root_motion.is_some())
+            && slipping_vector.is_none()
         {
-            // When stopping, prefer a boost to be able to reach a precise stop (see issue #39)
+            // When stopping, prefer a boost to be able to reach a precise stop (see issue #39).
+            // Root motion also needs to be precise, since it should match the animation.
             let walk_boost = desired_boost.clamp_length_max(ctx.frame_duration * max_acceleration);
             let walk_boost = if let Some(climb_vectors) = &climb_vectors {
                 climb_vectors.project(walk_boost)
@@ -312,14 +572,15 @@ impl TnuaBasis for TnuaBuiltinWalk {
                 slipping_direction.adjust_precision() * diff
             };
             TnuaVelChange {
-                acceleration: walk_acceleration,
+                acceleration: walk_acceleration + steep_slope_slide_acceleration,
                 boost: slipping_boost,
             }
         };
 
         let upward_impulse: TnuaVelChange = 'upward_impulse: {
-            let should_disable_due_to_slipping =
-                slipping_vector.is_some() && state.vertical_velocity <= 0.0;
+            // Even when moving upward - otherwise the character would be able to creep up steep
+            // slopes by walking into them.
+            let should_disable_due_to_slipping = slipping_vector.is_some();
             for _ in 0..2 {
                 #[allow(clippy::unnecessary_cast)]
                 match &mut state.airborne_timer {
@@ -330,10 +591,31 @@ impl TnuaBasis for TnuaBuiltinWalk {
                             // not doing the jump calculation here
                             let spring_offset =
                                 self.float_height - sensor_output.proximity.adjust_precision();
-                            state.standing_offset =
-                                -spring_offset * ctx.up_direction.adjust_precision();
-                            break 'upward_impulse self.spring_force(state, &ctx, spring_offset);
+                            if let Some(snap) = self.snap_to_ground(state, &ctx, spring_offset) {
+                                state.standing_offset =
+                                    -spring_offset * ctx.up_direction.adjust_precision();
+                                state.snapped_to_ground = true;
+                                break 'upward_impulse snap;
+                            }
+                            state.snapped_to_ground = false;
+                            if -spring_offset <= self.cling_distance {
+                                state.standing_offset =
+                                    -spring_offset * ctx.up_direction.adjust_precision();
+                                break 'upward_impulse self.spring_force(
+                                    state,
+                                    &ctx,
+                                    spring_offset,
+                                );
+                            }
+                            // The sensor's range was extended for snapping, but the character is
+                            // not snapping and the ground is too far for the spring.
+                            state.airborne_timer = Some(Timer::from_seconds(
+                                self.coyote_time as f32,
+                                TimerMode::Once,
+                            ));
+                            continue;
                         } else {
+                            state.snapped_to_ground = false;
                             state.airborne_timer = Some(Timer::from_seconds(
                                 self.coyote_time as f32,
                                 TimerMode::Once,
@@ -364,7 +646,9 @@ impl TnuaBasis for TnuaBuiltinWalk {
             TnuaVelChange::ZERO
         };
 
-        motor.lin = walk_vel_change + TnuaVelChange::boost(impulse_to_offset) + upward_impulse;
+        motor.lin = walk_vel_change
+            + TnuaVelChange::boost(impulse_to_offset + landing_clamp_boost)
+            + upward_impulse;
         let new_velocity = state.effective_velocity
             + motor.lin.boost
             + ctx.frame_duration * motor.lin.acceleration
@@ -382,12 +666,37 @@ impl TnuaBasis for TnuaBuiltinWalk {
             let desired_angvel = (rotation_required_to_fix_tilt.xyz() / ctx.frame_duration)
                 .clamp_length_max(self.tilt_offset_angvel);
             let angular_velocity_diff = desired_angvel - ctx.tracker.angvel;
-            angular_velocity_diff.clamp_length_max(ctx.frame_duration * self.tilt_offset_angacl)
+            // Slippery ground gives less grip for uprighting the character, so the tilt correction
+            // will not kill the momentum of a character that tumbles while sliding.
+            let tilt_traction_factor = if considered_in_air {
+                1.0
+            } else {
+                ctx.surface_traction.deceleration_factor
+            };
+            angular_velocity_diff.clamp_length_max(
+                ctx.frame_duration * tilt_traction_factor * self.tilt_offset_angacl,
+            )
         };
 
         // Turning
 
-        let desired_angvel = if let Some(desired_forward) = self.desired_forward {
+        let (desired_forward, turning_angvel) = if let Some(desired_look) = self.desired_look {
+            (Some(desired_look), self.look_angvel)
+        } else {
+            let desired_forward = match self.turning_policy {
+                TnuaTurningPolicy::DesiredForward => self.desired_forward,
+                TnuaTurningPolicy::DesiredVelocity => Dir3::new(self.desired_velocity.f32()).ok(),
+                TnuaTurningPolicy::Velocity { min_speed } => {
+                    if min_speed <= state.running_velocity.length() {
+                        Dir3::new(state.running_velocity.f32()).ok()
+                    } else {
+                        None
+                    }
+                }
+            };
+            (desired_forward, self.turning_angvel)
+        };
+        let desired_angvel = if let Some(desired_forward) = desired_forward {
             let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
             let rotation_along_up_axis = rotation_arc_around_axis(
                 ctx.up_direction,
@@ -395,8 +704,13 @@ impl TnuaBasis for TnuaBuiltinWalk {
                 desired_forward.adjust_precision(),
             )
             .unwrap_or(0.0);
-            (rotation_along_up_axis / ctx.frame_duration)
-                .clamp(-self.turning_angvel, self.turning_angvel)
+            (rotation_along_up_axis / ctx.frame_duration).clamp(-turning_angvel, turning_angvel)
+        } else if let Some(standing_on_state) = &state.standing_on {
+            // Keep the facing relative to the platform, so that the character will rotate together
+            // with rotating platforms (like carousels)
+            standing_on_state
+                .entity_angvel
+                .dot(ctx.up_direction.adjust_precision())
         } else {
             0.0
         };
@@ -417,7 +731,7 @@ impl TnuaBasis for TnuaBuiltinWalk {
     }
 
     fn proximity_sensor_cast_range(&self, _state: &Self::State) -> Float {
-        self.float_height + self.cling_distance
+        self.float_height + self.cling_distance.max(self.max_snap_distance)
     }
 
     fn displacement(&self, state: &Self::State) -> Option<Vector3> {
@@ -438,6 +752,8 @@ impl TnuaBasis for TnuaBuiltinWalk {
     fn neutralize(&mut self) {
         self.desired_velocity = Vector3::ZERO;
         self.desired_forward = None;
+        self.desired_look = None;
+        self.root_motion = None;
     }
 
     fn is_airborne(&self, state: &Self::State) -> bool {
@@ -474,7 +790,7 @@ impl TnuaBuiltinWalk {
             .dot(ctx.up_direction.adjust_precision())
             - state.vertical_velocity;
 
-        let gravity_compensation = -ctx.tracker.gravity;
+        let gravity_compensation = Self::gravity_compensation(ctx);
 
         let dampening_boost = relative_velocity * self.spring_dampening;
 
@@ -483,15 +799,299 @@ impl TnuaBuiltinWalk {
             boost: ctx.up_direction.adjust_precision() * -dampening_boost,
         }
     }
+
+    /// A preset for first-person shooters.
+    ///
+    /// The character reaches its desired velocity almost instantly on the ground and stops a bit
+    /// slower (see [`friction`](Self::friction)), and its speed does not change on slopes (see
+    /// [`project_on_slopes`](Self::project_on_slopes)). The basis does not tilt or turn the
+    /// character - it should face the camera's yaw, so the controls system should set its rotation
+    /// directly and the physics backend should lock its rotation.
+    ///
+    /// ```no_run
+    /// # use bevy_tnua::prelude::*;
+    /// # use bevy_tnua::math::Vector3;
+    /// # let mut controller = TnuaController::default();
+    /// # let direction = Vector3::ZERO;
+    /// controller.basis(TnuaBuiltinWalk {
+    ///     desired_velocity: direction * 8.0,
+    ///     float_height: 1.5,
+    ///     ..TnuaBuiltinWalk::fps()
+    /// });
+    /// ```
+    pub fn fps() -> Self {
+        Self {
+            acceleration: 200.0,
+            friction: Some(80.0),
+            air_acceleration: 15.0,
+            coyote_time: 0.1,
+            free_fall_extra_gravity: 0.0,
+            tilt_offset_angvel: 0.0,
+            tilt_offset_angacl: 0.0,
+            turning_angvel: 0.0,
+            project_on_slopes: true,
+            ..Default::default()
+        }
+    }
+
+    /// The acceleration that holds the character against the gravity while it stands on the
+    /// ground.
+    fn gravity_compensation(ctx: &TnuaBasisContext) -> Vector3 {
+        match &ctx.proximity_sensor.output {
+            Some(sensor_output) if ctx.surface_traction.slide_down_slopes => {
+                // Only the part that pushes the character into the ground - the rest will make it
+                // slide down the slope.
+                -ctx.tracker
+                    .gravity
+                    .project_onto(sensor_output.normal.adjust_precision())
+            }
+            _ => -ctx.tracker.gravity,
+        }
+    }
+
+    /// Scale down a horizontal velocity so that moving with it along the slope of the ground will
+    /// be at its full speed, without changing its direction.
+    fn velocity_on_slope(ctx: &TnuaBasisContext, velocity: Vector3) -> Vector3 {
+        let Some(sensor_output) = &ctx.proximity_sensor.output else {
+            return velocity;
+        };
+        let up = ctx.up_direction.adjust_precision();
+        let normal = sensor_output.normal.adjust_precision();
+        let normal_up = normal.dot(up);
+        if normal_up <= 0.0 {
+            return velocity;
+        }
+        // Lift the velocity vertically onto the ground plane.
+        let velocity_along_slope = velocity - up * (velocity.dot(normal) / normal_up);
+        let Some(direction) = velocity_along_slope.try_normalize() else {
+            return velocity;
+        };
+        (direction * velocity.length()).reject_from(up)
+    }
+
+    fn update_external_velocity(
+        &self,
+        state: &mut TnuaBuiltinWalkState,
+        ctx: &TnuaBasisContext,
+        motor: &crate::TnuaMotor,
+    ) {
+        let up = ctx.up_direction.adjust_precision();
+        let previous_velocity = state
+            .previous_velocity
+            .replace((ctx.tracker.velocity, ctx.frame_duration));
+        if self.external_velocity_recovery == Float::INFINITY {
+            state.external_velocity = Vector3::ZERO;
+            return;
+        }
+        if let Some((previous_velocity, previous_frame_duration)) = previous_velocity {
+            // At this point the motor still holds whatever was applied in the previous frame -
+            // including the modifications made by actions.
+            let expected_velocity = (previous_velocity
+                + motor.lin.boost
+                + (motor.lin.acceleration + ctx.tracker.gravity) * previous_frame_duration)
+                .reject_from(up);
+            let external_change = ctx.tracker.velocity.reject_from(up) - expected_velocity;
+
+            // Getting stopped by an obstacle reduces the velocity in the direction the character
+            // was expected to move, but only down to zero. Anything beyond that was pushing it
+            // back.
+            let expected_direction = expected_velocity.normalize_or_zero();
+            let change_along_expected = external_change.dot(expected_direction);
+            let external_change = if change_along_expected < 0.0 {
+                let pushed_back = (change_along_expected + expected_velocity.length()).min(0.0);
+                external_change + (pushed_back - change_along_expected) * expected_direction
+            } else {
+                external_change
+            };
+            state.external_velocity += external_change;
+        }
+        state.external_velocity = state.external_velocity.reject_from(up);
+        let recovery = self.external_velocity_recovery * ctx.frame_duration;
+        let external_speed = state.external_velocity.length();
+        state.external_velocity = if external_speed <= recovery {
+            Vector3::ZERO
+        } else {
+            state.external_velocity * (1.0 - recovery / external_speed)
+        };
+    }
+
+    fn update_wind_velocity(
+        &self,
+        state: &mut TnuaBuiltinWalkState,
+        ctx: &TnuaBasisContext,
+        considered_in_air: bool,
+    ) {
+        let wind = ctx
+            .external_acceleration
+            .reject_from(ctx.up_direction.adjust_precision());
+        if wind == Vector3::ZERO {
+            let recovery = if considered_in_air {
+                self.air_acceleration
+            } else {
+                self.acceleration
+            } * ctx.frame_duration;
+            let wind_speed = state.wind_velocity.length();
+            state.wind_velocity = if wind_speed <= recovery {
+                Vector3::ZERO
+            } else {
+                state.wind_velocity * (1.0 - recovery / wind_speed)
+            };
+        } else {
+            let drag = (self.wind_drag * ctx.frame_duration).clamp(0.0, 1.0);
+            state.wind_velocity = state.wind_velocity * (1.0 - drag)
+                + (1.0 - self.wind_resistance.clamp(0.0, 1.0)) * wind * ctx.frame_duration;
+        }
+    }
+
+    /// Track the velocity the character gained from sliding down slopes on surfaces with
+    /// [`slide_down_slopes`](crate::TnuaSurfaceTraction::slide_down_slopes), so that the walk will
+    /// not brake it.
+    fn update_slide_velocity(
+        &self,
+        state: &mut TnuaBuiltinWalkState,
+        ctx: &TnuaBasisContext,
+        keep_momentum: bool,
+    ) {
+        if keep_momentum {
+            return;
+        }
+        let up = ctx.up_direction.adjust_precision();
+        let slope_gravity = match &ctx.proximity_sensor.output {
+            Some(sensor_output) if ctx.surface_traction.slide_down_slopes => ctx
+                .tracker
+                .gravity
+                .reject_from(sensor_output.normal.adjust_precision())
+                .reject_from(up),
+            _ => Vector3::ZERO,
+        };
+        if slope_gravity == Vector3::ZERO {
+            let recovery =
+                ctx.surface_traction.deceleration_factor * self.acceleration * ctx.frame_duration;
+            let slide_speed = state.slide_velocity.length();
+            state.slide_velocity = if slide_speed <= recovery {
+                Vector3::ZERO
+            } else {
+                state.slide_velocity * (1.0 - recovery / slide_speed)
+            };
+        } else {
+            state.slide_velocity += slope_gravity * ctx.frame_duration;
+        }
+    }
+
+    /// Returns the target velocity, adjusted to retain the speed the character landed with, and a
+    /// boost for removing the excess speed at once.
+    fn retain_landing_speed(
+        &self,
+        state: &mut TnuaBuiltinWalkState,
+        ctx: &TnuaBasisContext,
+        considered_in_air: bool,
+        velocity_on_plane: Vector3,
+        target_velocity: Vector3,
+    ) -> (Vector3, Vector3) {
+        if considered_in_air {
+            state.time_since_landing = None;
+            return (target_velocity, Vector3::ZERO);
+        }
+        let speed = velocity_on_plane.length();
+        let just_landed = state.time_since_landing.is_none();
+        let time_since_landing = state
+            .time_since_landing
+            .map_or(0.0, |time| time + ctx.frame_duration);
+        state.time_since_landing = Some(time_since_landing);
+        if just_landed {
+            state.retained_speed = speed;
+        }
+
+        let target_speed = target_velocity.length();
+        if self.root_motion.is_some() || target_speed == 0.0 || speed <= target_speed {
+            state.retained_speed = state.retained_speed.min(speed);
+            return (target_velocity, Vector3::ZERO);
+        }
+
+        let retained_speed = match self.landing_speed_retention {
+            TnuaLandingSpeedRetention::Correct => 0.0,
+            TnuaLandingSpeedRetention::Clamp => {
+                if just_landed {
+                    return (
+                        target_velocity,
+                        velocity_on_plane * (target_speed / speed - 1.0),
+                    );
+                }
+                0.0
+            }
+            TnuaLandingSpeedRetention::Decay { deceleration } => {
+                if !just_landed {
+                    state.retained_speed -= deceleration * ctx.frame_duration;
+                }
+                state.retained_speed
+            }
+            TnuaLandingSpeedRetention::KeepFor { duration } => {
+                if time_since_landing <= duration {
+                    state.retained_speed
+                } else {
+                    0.0
+                }
+            }
+        };
+        // Never accelerate the character back up, e.g. after it was slowed down by an obstacle.
+        state.retained_speed = state.retained_speed.min(speed);
+        let retained_speed = retained_speed.min(speed);
+
+        if target_speed < retained_speed {
+            (
+                target_velocity * (retained_speed / target_speed),
+                Vector3::ZERO,
+            )
+        } else {
+            (target_velocity, Vector3::ZERO)
+        }
+    }
+
+    fn snap_to_ground(
+        &self,
+        state: &TnuaBuiltinWalkState,
+        ctx: &TnuaBasisContext,
+        spring_offset: Float,
+    ) -> Option<TnuaVelChange> {
+        if self.max_snap_distance <= 0.0 {
+            return None;
+        }
+        let relative_velocity = state
+            .effective_velocity
+            .dot(ctx.up_direction.adjust_precision())
+            - state.vertical_velocity;
+        if 0.0 < relative_velocity {
+            // Moving away from the ground - probably jumping.
+            return None;
+        }
+        let should_snap = if spring_offset <= 0.0 {
+            -spring_offset <= self.max_snap_distance
+        } else {
+            // Only when the character overshot the float height because of the snapping in the
+            // previous frame. Other than that, the spring should push it up.
+            state.snapped_to_ground
+        };
+        if !should_snap {
+            return None;
+        }
+        Some(TnuaVelChange {
+            acceleration: Self::gravity_compensation(ctx),
+            boost: ctx.up_direction.adjust_precision()
+                * (spring_offset / ctx.frame_duration - relative_velocity),
+        })
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct StandingOnState {
     entity: Entity,
     entity_linvel: Vector3,
+    entity_angvel: Vector3,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaBuiltinWalkState {
     airborne_timer: Option<Timer>,
     /// The current distance of the character from the distance its supposed to float at.
@@ -499,6 +1099,14 @@ pub struct TnuaBuiltinWalkState {
     standing_on: Option<StandingOnState>,
     effective_velocity: Vector3,
     vertical_velocity: Float,
+    snapped_to_ground: bool,
+    previous_velocity: Option<(Vector3, Float)>,
+    external_velocity: Vector3,
+    time_since_landing: Option<Float>,
+    retained_speed: Float,
+    wind_velocity: Vector3,
+    slide_velocity: Vector3,
+    slipping: bool,
     /// The velocity, perpendicular to the up direction, that the character is supposed to move at.
     ///
     /// If the character is standing on something else
@@ -508,10 +1116,36 @@ pub struct TnuaBuiltinWalkState {
 }
 
 impl TnuaBuiltinWalkState {
+    /// Returns the velocity caused by something other than the character itself, which has not
+    /// been cancelled yet.
+    ///
+    /// See [`TnuaBuiltinWalk::external_velocity_recovery`].
+    pub fn external_velocity(&self) -> Vector3 {
+        self.external_velocity
+    }
+
+    /// Check if the character is sliding down ground steeper than
+    /// [`max_slope`](TnuaBuiltinWalk::max_slope).
+    pub fn is_slipping(&self) -> bool {
+        self.slipping
+    }
+
     /// Returns the entity that the character currently stands on.
     pub fn standing_on_entity(&self) -> Option<Entity> {
         Some(self.standing_on.as_ref()?.entity)
     }
+
+    /// Returns the velocity of the entity that the character currently stands on, at the point
+    /// where the character stands on it.
+    pub fn standing_on_linvel(&self) -> Option<Vector3> {
+        Some(self.standing_on.as_ref()?.entity_linvel)
+    }
+
+    /// Returns the angular velocity of the entity that the character currently stands on, given
+    /// as the rotation axis multiplied by the rotation speed in radians per second.
+    pub fn standing_on_angvel(&self) -> Option<Vector3> {
+        Some(self.standing_on.as_ref()?.entity_angvel)
+    }
 }
 
 struct ClimbVectors {
@@ -0,0 +1,151 @@
+use crate::math::{float_consts, ops, AdjustPrecision, AsF32, Float};
+use bevy::prelude::*;
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor,
+};
+
+/// An [action](TnuaAction) for jumping off a wall.
+///
+/// The only field that must be set is [`wall_normal`](Self::wall_normal), which is typically taken
+/// from a [`TnuaWallSensor`](crate::control_helpers::TnuaWallSensor). When it is `None` (no wall
+/// was detected) the action will not start. Taking it from
+/// [`TnuaWallSensor::recent_wall`](crate::control_helpers::TnuaWallSensor::recent_wall) allows
+/// wall jumping for a short while after the character has left the wall.
+///
+/// After kicking off the wall, the action will prevent the [basis](crate::TnuaBasis) from
+/// steering the character horizontally for [`control_lockout`](Self::control_lockout) seconds, so
+/// that the player cannot immediately steer back into the wall.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinWallJump {
+    /// The normal of the wall the character jumps off.
+    ///
+    /// This input parameter is cached when the action starts. This means that the control system
+    /// does not have to keep feeding the same normal value after the character has left the wall.
+    pub wall_normal: Option<Dir3>,
+
+    /// The speed the character will be pushed away from the wall.
+    pub horizontal_impulse: Float,
+
+    /// The angle, in radians, between the kick off direction and the horizontal plane.
+    ///
+    /// The upward speed of the kick off is calculated from this and the
+    /// [`horizontal_impulse`](Self::horizontal_impulse), so `0.0` means the character will not be
+    /// pushed upward at all and `FRAC_PI_4` means it'll be pushed upward at the same speed it is
+    /// pushed away from the wall.
+    pub kick_off_angle: Float,
+
+    /// A duration, in seconds, after the kick off during which the basis cannot steer the
+    /// character horizontally.
+    ///
+    /// The lockout will end early if the character lands or if another action is fed. Negative
+    /// values are treated as zero.
+    pub control_lockout: Float,
+
+    /// A duration, in seconds, where a player can press a jump button before a wall jump becomes
+    /// possible (typically when a character is still in the air and about to touch a wall) and
+    /// the wall jump action would still get registered and be executed once it is possible.
+    pub input_buffer_time: Float,
+}
+
+impl Default for TnuaBuiltinWallJump {
+    fn default() -> Self {
+        Self {
+            wall_normal: None,
+            horizontal_impulse: 10.0,
+            kick_off_angle: float_consts::FRAC_PI_4,
+            control_lockout: 0.2,
+            input_buffer_time: 0.2,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinWallJump {
+    const NAME: &'static str = "TnuaBuiltinWallJump";
+    type State = TnuaBuiltinWallJumpState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn initiation_decision(
+        &self,
+        _ctx: TnuaActionContext,
+        being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if self.wall_normal.is_some() {
+            TnuaActionInitiationDirective::Allow
+        } else if (being_fed_for.elapsed().as_secs_f64() as Float) < self.input_buffer_time {
+            TnuaActionInitiationDirective::Delay
+        } else {
+            TnuaActionInitiationDirective::Reject
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        let up = ctx.up_direction.adjust_precision();
+
+        match state {
+            TnuaBuiltinWallJumpState::KickOff => {
+                let Some(wall_normal) = self.wall_normal else {
+                    // Probably unneeded because of the `initiation_decision`, but still
+                    return TnuaActionLifecycleDirective::Finished;
+                };
+                let Ok(away_from_wall) =
+                    Dir3::new(wall_normal.adjust_precision().reject_from(up).f32())
+                else {
+                    // The wall is actually a floor or a ceiling
+                    return TnuaActionLifecycleDirective::Finished;
+                };
+                let away_from_wall = away_from_wall.adjust_precision();
+
+                let upward_speed = self.horizontal_impulse * ops::tan(self.kick_off_angle);
+
+                motor.lin.cancel_on_axis(up);
+                motor.lin.cancel_on_axis(away_from_wall);
+                motor.lin.boost += (self.horizontal_impulse
+                    - away_from_wall.dot(ctx.tracker.velocity))
+                    * away_from_wall;
+                motor.lin.boost += (upward_speed - up.dot(ctx.tracker.velocity)) * up;
+
+                *state = TnuaBuiltinWallJumpState::Lockout {
+                    timer: Timer::from_seconds(
+                        self.control_lockout.max(0.0).f32(),
+                        TimerMode::Once,
+                    ),
+                };
+                TnuaActionLifecycleDirective::StillActive
+            }
+            TnuaBuiltinWallJumpState::Lockout { timer } => {
+                timer.tick(ctx.frame_duration_as_duration());
+                if timer.finished()
+                    || !ctx.basis.is_airborne()
+                    || matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto)
+                {
+                    TnuaActionLifecycleDirective::Finished
+                } else {
+                    // Only let the basis affect the vertical motion - so that gravity and
+                    // falling behave as usual.
+                    motor.lin.acceleration = motor.lin.acceleration.project_onto(up);
+                    motor.lin.boost = motor.lin.boost.project_onto(up);
+                    TnuaActionLifecycleDirective::StillActive
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinWallJumpState {
+    /// Applying the kick off impulse.
+    #[default]
+    KickOff,
+    /// Preventing the basis from steering the character back into the wall.
+    Lockout { timer: Timer },
+}
@@ -0,0 +1,153 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor, TnuaStaminaCost,
+};
+
+/// An [action](TnuaAction) for running horizontally along a wall.
+///
+/// The action should be fed for as long as the player wants to keep running, with
+/// [`wall_normal`](Self::wall_normal) typically taken from a
+/// [`TnuaWallSensor`](crate::control_helpers::TnuaWallSensor). It will only start while the
+/// character is in the air.
+///
+/// When the run starts the character stops falling, and gravity gradually returns over
+/// [`gravity_decay_time`](Self::gravity_decay_time) - so the longer the character runs the faster
+/// it falls. The run finishes when the character lands, the wall is no longer detected, the
+/// action is no longer fed, or [`max_duration`](Self::max_duration) has passed.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinWallRun {
+    /// The normal of the wall the character runs along.
+    ///
+    /// This is not cached - the control system should feed the current wall normal every frame,
+    /// so that the character can follow curved walls. Feeding `None` will end the run.
+    pub wall_normal: Option<Dir3>,
+
+    /// The direction and speed to run at.
+    ///
+    /// Only the horizontal part of this vector that is parallel to the wall is used.
+    pub desired_velocity: Vector3,
+
+    /// The maximum acceleration when changing the running velocity.
+    pub acceleration: Float,
+
+    /// A duration, in seconds, over which the gravity goes back from zero to its full strength.
+    ///
+    /// Set it to [`Float::INFINITY`] to disable gravity for the entire run.
+    pub gravity_decay_time: Float,
+
+    /// An acceleration that pushes the character into the wall, so that it will not drift away
+    /// from it while running.
+    pub wall_stick_acceleration: Float,
+
+    /// The maximum duration, in seconds, of the run.
+    pub max_duration: Float,
+
+    /// The stamina the run costs, if the character has a
+    /// [`TnuaStamina`](crate::controller::TnuaStamina). Free by default.
+    pub stamina_cost: TnuaStaminaCost,
+}
+
+impl Default for TnuaBuiltinWallRun {
+    fn default() -> Self {
+        Self {
+            wall_normal: None,
+            desired_velocity: Vector3::ZERO,
+            acceleration: 60.0,
+            gravity_decay_time: 1.0,
+            wall_stick_acceleration: 5.0,
+            max_duration: 1.5,
+            stamina_cost: Default::default(),
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinWallRun {
+    const NAME: &'static str = "TnuaBuiltinWallRun";
+    type State = TnuaBuiltinWallRunState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn stamina_cost(&self) -> TnuaStaminaCost {
+        self.stamina_cost
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if self.wall_normal.is_some() && ctx.basis.is_airborne() {
+            TnuaActionInitiationDirective::Allow
+        } else {
+            TnuaActionInitiationDirective::Delay
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        if !lifecycle_status.is_active() || !ctx.basis.is_airborne() {
+            return TnuaActionLifecycleDirective::Finished;
+        }
+        let Some(wall_normal) = self.wall_normal else {
+            return TnuaActionLifecycleDirective::Finished;
+        };
+
+        let up = ctx.up_direction.adjust_precision();
+        let wall_normal = wall_normal.adjust_precision();
+
+        let TnuaBuiltinWallRunState::Running { run_time } = state else {
+            *state = TnuaBuiltinWallRunState::Running { run_time: 0.0 };
+            // Stop the fall, so that the run will start horizontally.
+            let vertical_velocity = ctx.tracker.velocity.dot(up);
+            motor.lin = Default::default();
+            if vertical_velocity < 0.0 {
+                motor.lin.boost = -vertical_velocity * up;
+            }
+            motor.lin.acceleration = -ctx.tracker.gravity;
+            return TnuaActionLifecycleDirective::StillActive;
+        };
+
+        *run_time += ctx.frame_duration;
+        if self.max_duration <= *run_time {
+            return TnuaActionLifecycleDirective::Finished;
+        }
+
+        let run_velocity = self
+            .desired_velocity
+            .reject_from(up)
+            .reject_from(wall_normal);
+        let velocity_on_plane = ctx.tracker.velocity.reject_from(up);
+        let run_acceleration = ((run_velocity - velocity_on_plane) / ctx.frame_duration)
+            .clamp_length_max(self.acceleration);
+
+        let gravity_factor = (*run_time / self.gravity_decay_time).min(1.0);
+
+        motor.lin = Default::default();
+        motor.lin.acceleration = run_acceleration
+            - wall_normal * self.wall_stick_acceleration
+            - ctx.tracker.gravity * (1.0 - gravity_factor);
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinWallRunState {
+    /// The run is starting, and the character's fall is being stopped.
+    #[default]
+    Starting,
+    /// The character is running along the wall.
+    Running {
+        /// The time, in seconds, since the run has started.
+        run_time: Float,
+    },
+}
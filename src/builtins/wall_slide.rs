@@ -0,0 +1,105 @@
+use crate::math::{AdjustPrecision, Float};
+use bevy::prelude::*;
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor,
+};
+
+/// An [action](TnuaAction) for sliding down a wall at a reduced speed.
+///
+/// The action should be fed for as long as the player presses into the wall, with
+/// [`wall_normal`](Self::wall_normal) typically taken from a
+/// [`TnuaWallSensor`](crate::control_helpers::TnuaWallSensor). It will only start while the
+/// character is in the air, and will finish once the character lands, the wall is no longer
+/// detected, or the action is no longer fed.
+///
+/// The slide only limits the fall speed - the horizontal movement is still controlled by the
+/// basis.
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinWallSlide {
+    /// The normal of the wall the character slides on.
+    ///
+    /// Unlike with [`TnuaBuiltinWallJump`](crate::builtins::TnuaBuiltinWallJump), this is not
+    /// cached - the control system should feed the current wall normal every frame. Feeding
+    /// `None` will end the slide.
+    pub wall_normal: Option<Dir3>,
+
+    /// The maximum speed the character can fall at while sliding.
+    pub max_fall_speed: Float,
+
+    /// The maximum acceleration used for slowing the character's fall to
+    /// [`max_fall_speed`](Self::max_fall_speed).
+    pub brake_acceleration: Float,
+}
+
+impl Default for TnuaBuiltinWallSlide {
+    fn default() -> Self {
+        Self {
+            wall_normal: None,
+            max_fall_speed: 2.0,
+            brake_acceleration: 60.0,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinWallSlide {
+    const NAME: &'static str = "TnuaBuiltinWallSlide";
+    type State = TnuaBuiltinWallSlideState;
+    const VIOLATES_COYOTE_TIME: bool = false;
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if self.wall_normal.is_some() && ctx.basis.is_airborne() {
+            TnuaActionInitiationDirective::Allow
+        } else {
+            TnuaActionInitiationDirective::Delay
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        if !lifecycle_status.is_active() {
+            return TnuaActionLifecycleDirective::Finished;
+        }
+        if self.wall_normal.is_none() || !ctx.basis.is_airborne() {
+            // The player is still pressing into the wall, so let it restart once possible.
+            return TnuaActionLifecycleDirective::Reschedule { after_seconds: 0.0 };
+        }
+
+        let up = ctx.up_direction.adjust_precision();
+        let fall_speed = -ctx.tracker.velocity.dot(up);
+
+        if self.max_fall_speed < fall_speed {
+            *state = TnuaBuiltinWallSlideState::Sliding;
+            motor.lin.cancel_on_axis(up);
+            motor.lin.boost += up
+                * (fall_speed - self.max_fall_speed)
+                    .min(ctx.frame_duration * self.brake_acceleration);
+        } else {
+            *state = TnuaBuiltinWallSlideState::Falling;
+        }
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+#[derive(Default, Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinWallSlideState {
+    /// The character is next to the wall, but is not falling fast enough for the slide to slow
+    /// it down.
+    #[default]
+    Falling,
+    /// The character is sliding down the wall, and its fall is being slowed down.
+    Sliding,
+}
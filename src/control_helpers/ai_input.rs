@@ -0,0 +1,96 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Vector3;
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{TnuaToggle, TnuaUserControlsSystemSet};
+
+pub struct TnuaAiInputPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaAiInputPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaAiInputPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaAiInput`] work.
+impl Plugin for TnuaAiInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaAiInput>();
+        app.add_systems(
+            self.schedule,
+            apply_ai_input.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// Movement input for characters controlled by AI or navigation code.
+///
+/// Every frame, [`TnuaAiInputPlugin`] feeds the [`TnuaController`] with the [`walk`](Self::walk)
+/// basis, with its [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) and
+/// [`desired_forward`](TnuaBuiltinWalk::desired_forward) replaced by the ones in this component.
+/// This allows AI systems to move the character by only writing to this component, without
+/// accessing the controller.
+///
+/// Systems that feed actions to the controller should run in [`TnuaUserControlsSystemSet`] as
+/// usual.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaAiInputPlugin`].
+/// 2. Adding [`TnuaAiInput`] as a component to the character entity (instead of feeding the
+///    basis from a controls system).
+#[derive(Component, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct TnuaAiInput {
+    /// The velocity the character should move at. See
+    /// [`TnuaBuiltinWalk::desired_velocity`].
+    pub desired_velocity: Vector3,
+
+    /// The direction the character should face. See [`TnuaBuiltinWalk::desired_forward`].
+    pub desired_facing: Option<Dir3>,
+
+    /// The configuration of the walk basis. Its `desired_velocity` and `desired_forward` are
+    /// ignored.
+    pub walk: TnuaBuiltinWalk,
+}
+
+impl TnuaAiInput {
+    /// Create a new AI input, with no movement, to be added as a component to the character
+    /// entity.
+    pub fn new(walk: TnuaBuiltinWalk) -> Self {
+        Self {
+            desired_velocity: Vector3::ZERO,
+            desired_facing: None,
+            walk,
+        }
+    }
+}
+
+pub(crate) fn apply_ai_input(
+    mut query: Query<(&TnuaAiInput, &mut TnuaController, Option<&TnuaToggle>)>,
+) {
+    for (ai_input, mut controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: ai_input.desired_velocity,
+            desired_forward: ai_input.desired_facing,
+            ..ai_input.walk.clone()
+        });
+    }
+}
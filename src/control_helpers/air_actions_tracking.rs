@@ -8,7 +8,7 @@ use crate::prelude::*;
 /// It's [`update`](Self::update) must be called every frame - even when the result is not used.
 ///
 /// For simpler usage, see [`TnuaSimpleAirActionsCounter`].
-#[derive(Default)]
+#[derive(Default, Clone, Reflect)]
 pub struct TnuaAirActionsTracker {
     considered_in_air: bool,
 }
@@ -102,7 +102,8 @@ pub enum TnuaAirActionsUpdate {
 /// A simple counter that counts together all the air actions a character is able to perform.
 ///
 /// It's [`update`](Self::update) must be called every frame.
-#[derive(Component, Default)]
+#[derive(Component, Clone, Default, Reflect)]
+#[reflect(Component)]
 pub struct TnuaSimpleAirActionsCounter {
     tracker: TnuaAirActionsTracker,
     current_action: Option<(&'static str, usize)>,
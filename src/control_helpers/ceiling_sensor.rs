@@ -0,0 +1,206 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Vector3};
+
+use super::sensor_modifier::SensorModifier;
+use crate::controller::TnuaController;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{
+    TnuaMotor, TnuaPipelineStages, TnuaProximitySensor, TnuaProximitySensorOutput,
+    TnuaRigidBodyTracker, TnuaToggle, TnuaUpdateTimer,
+};
+
+pub struct TnuaCeilingSensorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCeilingSensorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaCeilingSensorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaCeilingSensor`] work.
+impl Plugin for TnuaCeilingSensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaCeilingSensor>();
+        app.add_systems(
+            self.schedule,
+            update_ceiling_sensors.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+        app.add_systems(
+            self.schedule,
+            soften_head_bumps
+                .in_set(TnuaPipelineStages::Logic)
+                .after(crate::controller::apply_controller_system),
+        );
+    }
+}
+
+/// Detects the ceiling above the character's head.
+///
+/// This will create a child entity with a proximity sensor cast upward from the top of the
+/// character's collider, and store what it detects so that gameplay code can read it with
+/// [`clearance`](Self::clearance) and [`ceiling`](Self::ceiling). For preventing the character
+/// from standing up from a crouch under a low ceiling, use
+/// [`TnuaCrouchEnforcer`](super::TnuaCrouchEnforcer).
+///
+/// When [`soften_head_bumps`](Self::soften_head_bumps) is set, a character that moves upward
+/// toward a ceiling (e.g. when jumping) gets decelerated so that it stops when its head reaches the
+/// ceiling - instead of the abrupt stop the physics engine would do when the collider hits it.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaCeilingSensorPlugin`].
+/// 2. Adding [`TnuaCeilingSensor`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaCeilingSensor {
+    /// Decelerate the character when it moves upward toward a ceiling, so that the head bump will
+    /// not stop it abruptly.
+    pub soften_head_bumps: bool,
+
+    #[reflect(ignore)]
+    sensor_entity: Option<Entity>,
+    offset: Vector3,
+    cast_range: Float,
+    #[reflect(ignore)]
+    modify_sensor: SensorModifier,
+    #[reflect(ignore)]
+    ceiling: Option<TnuaProximitySensorOutput>,
+}
+
+impl TnuaCeilingSensor {
+    /// Create a new ceiling sensor, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `offset` - the origin of the proximity sensor, in the character's coord system. Should be
+    ///   placed at the top of the collider.
+    /// * `cast_range` - how far above the `offset` the sensor detects ceilings.
+    /// * `modify_sensor` - a function called with the command that creates the sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        offset: Vector3,
+        cast_range: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            soften_head_bumps: true,
+            sensor_entity: None,
+            offset,
+            cast_range,
+            modify_sensor: SensorModifier::new(modify_sensor),
+            ceiling: None,
+        }
+    }
+
+    /// The ceiling detected above the character's head, if there is one within range.
+    pub fn ceiling(&self) -> Option<&TnuaProximitySensorOutput> {
+        self.ceiling.as_ref()
+    }
+
+    /// The distance between the top of the character and the ceiling above it, if there is one
+    /// within range.
+    pub fn clearance(&self) -> Option<Float> {
+        Some(self.ceiling.as_ref()?.proximity)
+    }
+}
+
+fn update_ceiling_sensors(
+    mut query: Query<(Entity, &mut TnuaCeilingSensor)>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, mut ceiling_sensor) in query.iter_mut() {
+        let ceiling_sensor = ceiling_sensor.as_mut();
+
+        let Ok((main_sensor, false)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let up = -main_sensor.cast_direction;
+
+        if let Some((mut sensor, true)) = ceiling_sensor
+            .sensor_entity
+            .and_then(|entity| sensors_query.get_mut(entity).ok())
+        {
+            ceiling_sensor.ceiling = sensor.output.clone();
+            sensor.cast_origin = ceiling_sensor.offset;
+            sensor.cast_direction = up;
+            sensor.cast_range = ceiling_sensor.cast_range;
+        } else {
+            let mut cmd = commands.spawn((
+                Transform::default(),
+                TnuaSubservientSensor { owner_entity },
+                TnuaProximitySensor {
+                    cast_origin: ceiling_sensor.offset,
+                    cast_direction: up,
+                    cast_range: ceiling_sensor.cast_range,
+                    ..Default::default()
+                },
+            ));
+            cmd.set_parent(owner_entity);
+            ceiling_sensor.modify_sensor.apply(&mut cmd);
+            ceiling_sensor.sensor_entity = Some(cmd.id());
+            ceiling_sensor.ceiling = None;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn soften_head_bumps(
+    mut query: Query<(
+        &TnuaCeilingSensor,
+        &TnuaController,
+        &TnuaProximitySensor,
+        &TnuaRigidBodyTracker,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+) {
+    for (ceiling_sensor, controller, main_sensor, tracker, mut motor, tnua_toggle, update_timer) in
+        query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        if !ceiling_sensor.soften_head_bumps || controller.basis_name().is_none() {
+            // Without a basis the motor does not get overwritten every frame, so changing it
+            // would accumulate.
+            continue;
+        }
+        let Some(clearance) = ceiling_sensor.clearance() else {
+            continue;
+        };
+        let up = -main_sensor.cast_direction.adjust_precision();
+        let upward_speed = tracker.velocity.dot(up);
+        if upward_speed <= 0.0 {
+            continue;
+        }
+
+        // The deceleration that will stop the character exactly when its head reaches the
+        // ceiling.
+        let required_deceleration = upward_speed.powi(2) / (2.0 * clearance.max(0.01));
+        let max_upward_acceleration = -required_deceleration - tracker.gravity.dot(up);
+        let upward_acceleration = motor.lin.acceleration.dot(up);
+        if max_upward_acceleration < upward_acceleration {
+            motor.lin.acceleration += (max_upward_acceleration - upward_acceleration) * up;
+        }
+    }
+}
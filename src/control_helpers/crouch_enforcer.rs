@@ -5,9 +5,10 @@ use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
 
+use super::sensor_modifier::SensorModifier;
 use crate::controller::TnuaController;
 use crate::subservient_sensors::TnuaSubservientSensor;
-use crate::{TnuaAction, TnuaPipelineStages, TnuaProximitySensor};
+use crate::{TnuaAction, TnuaPipelineStages, TnuaProximitySensor, TnuaToggle};
 
 pub struct TnuaCrouchEnforcerPlugin {
     schedule: InternedScheduleLabel,
@@ -30,6 +31,7 @@ impl Default for TnuaCrouchEnforcerPlugin {
 /// A plugin required for making [`TnuaCrouchEnforcer`] work.
 impl Plugin for TnuaCrouchEnforcerPlugin {
     fn build(&self, app: &mut App) {
+        app.register_type::<TnuaCrouchEnforcer>();
         app.add_systems(
             self.schedule,
             update_crouch_enforcer.in_set(TnuaPipelineStages::SubservientSensors),
@@ -61,12 +63,17 @@ impl Plugin for TnuaCrouchEnforcerPlugin {
 ///         ..Default::default()
 ///     }));
 ///     ```
-#[derive(Component)]
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
 pub struct TnuaCrouchEnforcer {
+    #[reflect(ignore)]
     sensor_entity: Option<Entity>,
     offset: Vector3,
-    modify_sensor: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+    #[reflect(ignore)]
+    modify_sensor: SensorModifier,
+    #[reflect(ignore)]
     enforced_action: Option<(Box<dyn DynamicCrouchEnforcedAction>, bool)>,
+    #[reflect(ignore)]
     currently_enforcing: bool,
 }
 
@@ -89,7 +96,7 @@ impl TnuaCrouchEnforcer {
         Self {
             sensor_entity: None,
             offset,
-            modify_sensor: Box::new(modify_sensor),
+            modify_sensor: SensorModifier::new(modify_sensor),
             enforced_action: None,
             currently_enforcing: false,
         }
@@ -130,6 +137,13 @@ trait DynamicCrouchEnforcedAction: Send + Sync {
     fn overwrite(&mut self, value: &dyn Any) -> Result<(), ()>;
     fn feed_to_controller(&mut self, controller: &mut TnuaController);
     fn range_to_cast_up(&self, controller: &TnuaController) -> Option<Float>;
+    fn clone_box(&self) -> Box<dyn DynamicCrouchEnforcedAction>;
+}
+
+impl Clone for Box<dyn DynamicCrouchEnforcedAction> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
 struct BoxableCrouchEnforcedAction<A: TnuaCrouchEnforcedAction>(A);
@@ -157,14 +171,28 @@ impl<A: TnuaCrouchEnforcedAction> DynamicCrouchEnforcedAction for BoxableCrouchE
             None
         }
     }
+
+    fn clone_box(&self) -> Box<dyn DynamicCrouchEnforcedAction> {
+        Box::new(BoxableCrouchEnforcedAction(self.0.clone()))
+    }
 }
 
 fn update_crouch_enforcer(
-    mut query: Query<(Entity, &mut TnuaController, &mut TnuaCrouchEnforcer)>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaController,
+        &mut TnuaCrouchEnforcer,
+        Option<&TnuaToggle>,
+    )>,
     mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
     mut commands: Commands,
 ) {
-    for (owner_entity, mut controller, mut crouch_enforcer) in query.iter_mut() {
+    for (owner_entity, mut controller, mut crouch_enforcer, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
         struct SetSensor {
             cast_direction: Dir3,
             cast_range: Float,
@@ -214,7 +242,7 @@ fn update_crouch_enforcer(
                     },
                 ));
                 cmd.set_parent(owner_entity);
-                (crouch_enforcer.modify_sensor)(&mut cmd);
+                crouch_enforcer.modify_sensor.apply(&mut cmd);
                 let sensor_entity = cmd.id();
                 crouch_enforcer.sensor_entity = Some(sensor_entity);
             }
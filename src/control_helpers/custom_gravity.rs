@@ -0,0 +1,122 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Vector3;
+
+use crate::controller::TnuaController;
+use crate::{TnuaMotor, TnuaPipelineStages, TnuaRigidBodyTracker, TnuaToggle, TnuaUpdateTimer};
+
+pub struct TnuaCustomGravityPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCustomGravityPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaCustomGravityPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaCustomGravity`] work.
+impl Plugin for TnuaCustomGravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaCustomGravity>();
+        app.add_systems(
+            self.schedule,
+            override_gravity_in_trackers.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+        app.add_systems(
+            self.schedule,
+            apply_custom_gravity
+                .in_set(TnuaPipelineStages::Logic)
+                .after(crate::controller::apply_controller_system),
+        );
+    }
+}
+
+/// Replaces the gravity the physics backend applies to the character with a different gravity.
+///
+/// Physics engines usually only support a single, uniform, gravity. With this component, each
+/// character can have its own gravity, and it can be changed every frame - e.g. to make it point
+/// at the center of a spherical planet. Since the up direction is the opposite of the gravity, the
+/// character will stand on the planet's surface, and the basis and the actions will work relative
+/// to it.
+///
+/// Tnua will pretend the gravity is [`gravity`](Self::gravity), and add the difference between it
+/// and the physics engine's gravity to the motor.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaCustomGravityPlugin`].
+/// 2. Adding [`TnuaCustomGravity`] as a component to the character entity, and updating it as
+///    needed:
+///     ```no_run
+///     # use bevy::prelude::*;
+///     # use bevy_tnua::control_helpers::TnuaCustomGravity;
+///     # struct Planet { center: Vec3, gravity: f32 }
+///     # let planet = Planet { center: Vec3::ZERO, gravity: 9.81 };
+///     # let transform = Transform::default();
+///     # let mut custom_gravity = TnuaCustomGravity::new(Vec3::ZERO);
+///     custom_gravity.gravity =
+///         (planet.center - transform.translation).normalize_or_zero() * planet.gravity;
+///     ```
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaCustomGravity {
+    /// The gravity to apply to the character.
+    pub gravity: Vector3,
+    engine_gravity: Vector3,
+}
+
+impl TnuaCustomGravity {
+    /// Create a new custom gravity, to be added as a component to the character entity.
+    pub fn new(gravity: Vector3) -> Self {
+        Self {
+            gravity,
+            engine_gravity: Vector3::ZERO,
+        }
+    }
+}
+
+fn override_gravity_in_trackers(
+    mut query: Query<(&mut TnuaCustomGravity, &mut TnuaRigidBodyTracker)>,
+) {
+    for (mut custom_gravity, mut tracker) in query.iter_mut() {
+        custom_gravity.engine_gravity = tracker.gravity;
+        tracker.gravity = custom_gravity.gravity;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_custom_gravity(
+    mut query: Query<(
+        &TnuaCustomGravity,
+        &TnuaController,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+) {
+    for (custom_gravity, controller, mut motor, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        if controller.basis_name().is_none() {
+            // Without a basis the motor does not get overwritten every frame, so adding to it
+            // would accumulate.
+            continue;
+        }
+        motor.lin.acceleration += custom_gravity.gravity - custom_gravity.engine_gravity;
+    }
+}
@@ -0,0 +1,262 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use super::sensor_modifier::SensorModifier;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaToggle, TnuaUpdateTimer,
+};
+
+pub struct TnuaEdgeSensorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaEdgeSensorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaEdgeSensorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaEdgeSensor`] work.
+impl Plugin for TnuaEdgeSensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaEdgeSensor>();
+        app.register_type::<TnuaEdge>();
+        app.add_systems(
+            self.schedule,
+            update_edge_sensors.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Detects when the character is about to walk off an edge.
+///
+/// This will create a child entity with a proximity sensor, cast downward from ahead of the
+/// character - in the direction it moves in, or in the direction it faces (its negative Z axis)
+/// when it does not move. If the ground there is lower than the ground under the character by more
+/// than [`min_drop`](Self::min_drop), that's an [edge](TnuaEdge).
+///
+/// To prevent walking off high drops (e.g. for NPCs, or for an assisted mode for players), set
+/// [`prevent_drops_above`](Self::prevent_drops_above) and pass the desired velocity through
+/// [`restrict_velocity`](Self::restrict_velocity):
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaEdgeSensor;
+/// # use bevy_tnua::math::Vector3;
+/// # let mut controller = TnuaController::default();
+/// # let edge_sensor = TnuaEdgeSensor::new(Vector3::ZERO, 0.5, 5.0, |_| {});
+/// # let direction = Vector3::ZERO;
+/// controller.basis(TnuaBuiltinWalk {
+///     desired_velocity: edge_sensor.restrict_velocity(direction * 10.0),
+///     float_height: 1.5,
+///     ..Default::default()
+/// });
+/// ```
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaEdgeSensorPlugin`].
+/// 2. Adding [`TnuaEdgeSensor`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaEdgeSensor {
+    #[reflect(ignore)]
+    sensor_entity: Option<Entity>,
+    origin: Vector3,
+    lookahead: Float,
+    detection_depth: Float,
+
+    /// The minimal difference between the height of the ground under the character and the height
+    /// of the ground ahead of it to be considered an edge.
+    ///
+    /// Should be higher than the steps the character can walk down.
+    pub min_drop: Float,
+
+    /// When set, [`restrict_velocity`](Self::restrict_velocity) will not let the character walk
+    /// off edges with drops higher than this.
+    pub prevent_drops_above: Option<Float>,
+
+    #[reflect(ignore)]
+    modify_sensor: SensorModifier,
+    #[reflect(ignore)]
+    edge: Option<TnuaEdge>,
+}
+
+impl TnuaEdgeSensor {
+    /// Create a new edge sensor, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `origin` - the origin of the proximity sensor before moving it ahead of the character, in
+    ///   the character's coord system. Usually the center of the character.
+    /// * `lookahead` - how far ahead of the `origin` the sensor is cast from. Should be a little
+    ///   more than the radius of the collider.
+    /// * `detection_depth` - how far below the ground under the character the sensor detects the
+    ///   ground ahead. Drops deeper than that are reported without a height.
+    /// * `modify_sensor` - a function called with the command that creates the sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        origin: Vector3,
+        lookahead: Float,
+        detection_depth: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            sensor_entity: None,
+            origin,
+            lookahead,
+            detection_depth,
+            min_drop: 0.5,
+            prevent_drops_above: None,
+            modify_sensor: SensorModifier::new(modify_sensor),
+            edge: None,
+        }
+    }
+
+    /// The edge detected during the last update.
+    pub fn edge(&self) -> Option<&TnuaEdge> {
+        self.edge.as_ref()
+    }
+
+    /// Remove the part of the velocity that would take the character off an edge with a drop
+    /// higher than [`prevent_drops_above`](Self::prevent_drops_above).
+    pub fn restrict_velocity(&self, velocity: Vector3) -> Vector3 {
+        let (Some(max_drop), Some(edge)) = (self.prevent_drops_above, &self.edge) else {
+            return velocity;
+        };
+        if edge
+            .drop_height
+            .is_some_and(|drop_height| drop_height <= max_drop)
+        {
+            return velocity;
+        }
+        let direction = edge.direction.adjust_precision();
+        let toward_edge = velocity.dot(direction);
+        if 0.0 < toward_edge {
+            velocity - direction * toward_edge
+        } else {
+            velocity
+        }
+    }
+}
+
+/// An edge detected by [`TnuaEdgeSensor`].
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaEdge {
+    /// The horizontal direction from the character to the edge.
+    pub direction: Dir3,
+
+    /// How much lower the ground beyond the edge is than the ground under the character.
+    ///
+    /// `None` when the drop is deeper than the sensor's detection depth.
+    pub drop_height: Option<Float>,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_edge_sensors(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &TnuaRigidBodyTracker,
+        &mut TnuaEdgeSensor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, transform, tracker, mut edge_sensor, tnua_toggle, update_timer) in
+        query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let edge_sensor = edge_sensor.as_mut();
+        let Ok((owner_sensor, _)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        let rotation = rotation.adjust_precision();
+        let down = owner_sensor.cast_direction;
+        // The distance of the ground from the character's center.
+        let ground_proximity = owner_sensor.output.as_ref().map(|output| {
+            output.proximity + (rotation * owner_sensor.cast_origin).dot(down.adjust_precision())
+        });
+
+        let up = -down.adjust_precision();
+        let velocity_on_plane = tracker.velocity.reject_from(up);
+        let direction = if 0.1 < velocity_on_plane.length() {
+            Dir3::new(velocity_on_plane.f32()).ok()
+        } else {
+            None
+        }
+        .or_else(|| Dir3::new((rotation * Vector3::NEG_Z).reject_from(up).f32()).ok())
+        .unwrap_or(Dir3::NEG_Z);
+        let cast_origin = edge_sensor.origin
+            + rotation.inverse() * direction.adjust_precision() * edge_sensor.lookahead;
+        let origin_proximity = ground_proximity.map(|ground_proximity| {
+            ground_proximity - (rotation * edge_sensor.origin).dot(down.adjust_precision())
+        });
+        let cast_range = origin_proximity.unwrap_or(0.0) + edge_sensor.detection_depth;
+
+        let output = if let Some((mut sensor, true)) = edge_sensor
+            .sensor_entity
+            .and_then(|entity| sensors_query.get_mut(entity).ok())
+        {
+            sensor.cast_origin = cast_origin;
+            sensor.cast_direction = down;
+            sensor.cast_range = cast_range;
+            Some(sensor.output.clone())
+        } else {
+            let mut cmd = commands.spawn((
+                Transform::default(),
+                TnuaSubservientSensor { owner_entity },
+                TnuaProximitySensor {
+                    cast_origin,
+                    cast_direction: down,
+                    cast_range,
+                    ..Default::default()
+                },
+            ));
+            cmd.set_parent(owner_entity);
+            edge_sensor.modify_sensor.apply(&mut cmd);
+            edge_sensor.sensor_entity = Some(cmd.id());
+            None
+        };
+
+        edge_sensor.edge =
+            if let (Some(output), Some(origin_proximity)) = (output, origin_proximity) {
+                let drop_height = output.map(|output| output.proximity - origin_proximity);
+                if drop_height.is_none_or(|drop_height| edge_sensor.min_drop < drop_height) {
+                    Some(TnuaEdge {
+                        direction,
+                        drop_height,
+                    })
+                } else {
+                    None
+                }
+            } else {
+                // Either the character is not on the ground, or the sensor was just created.
+                None
+            };
+    }
+}
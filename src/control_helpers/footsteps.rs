@@ -0,0 +1,142 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float};
+
+use crate::controller::TnuaController;
+use crate::{TnuaPipelineStages, TnuaToggle, TnuaUpdateTimer};
+
+pub struct TnuaFootstepsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaFootstepsPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaFootstepsPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaFootsteps`] work.
+///
+/// The schedule should be the same one
+/// [`TnuaControllerPlugin`](crate::controller::TnuaControllerPlugin) uses.
+impl Plugin for TnuaFootstepsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaFootsteps>();
+        app.add_event::<TnuaFootstepEvent>();
+        app.add_systems(
+            self.schedule,
+            send_footstep_events
+                .in_set(TnuaPipelineStages::Logic)
+                .after(crate::controller::apply_controller_system),
+        );
+    }
+}
+
+/// Sends a [`TnuaFootstepEvent`] every time the character walks a stride's length on the ground.
+///
+/// The distance is measured with the basis'
+/// [`effective_velocity`](crate::TnuaBasis::effective_velocity) - which is relative to the ground,
+/// so standing on a moving platform will not count as walking - along the ground's surface. It is
+/// only measured while the character is on the ground, and gets reset when it leaves the ground, so
+/// that the first step after landing will take a full stride.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaFootstepsPlugin`].
+/// 2. Adding [`TnuaFootsteps`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaFootsteps {
+    /// The distance the character walks between two footsteps.
+    pub stride_length: Float,
+    distance_since_last_step: Float,
+    is_second_foot: bool,
+}
+
+impl TnuaFootsteps {
+    /// Create a new footsteps tracker, to be added as a component to the character entity.
+    pub fn new(stride_length: Float) -> Self {
+        Self {
+            stride_length,
+            distance_since_last_step: 0.0,
+            is_second_foot: false,
+        }
+    }
+}
+
+/// Sent by [`TnuaFootstepsPlugin`] when a character with [`TnuaFootsteps`] takes a step.
+#[derive(Event, Debug, Clone)]
+pub struct TnuaFootstepEvent {
+    /// The character entity.
+    pub entity: Entity,
+    /// The entity the character stepped on - e.g. for choosing a sound based on its surface.
+    pub ground_entity: Entity,
+    /// The speed in which the character was walking, relative to the ground.
+    pub speed: Float,
+    /// Alternates between `false` and `true` for consecutive footsteps.
+    pub is_second_foot: bool,
+}
+
+#[allow(clippy::type_complexity)]
+fn send_footstep_events(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &TnuaController,
+        &mut TnuaFootsteps,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut writer: EventWriter<TnuaFootstepEvent>,
+) {
+    for (entity, controller, mut footsteps, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let frame_duration = match update_timer {
+            Some(update_timer) if !update_timer.is_update_frame() => continue,
+            Some(update_timer) => update_timer.delta(),
+            None => time.delta(),
+        }
+        .as_secs_f64() as Float;
+        if frame_duration == 0.0 {
+            continue;
+        }
+        let (Ok(false), Some(ground_entity), Some(ground_normal), Some(basis)) = (
+            controller.is_airborne(),
+            controller.ground_entity(),
+            controller.ground_normal(),
+            controller.dynamic_basis(),
+        ) else {
+            footsteps.distance_since_last_step = 0.0;
+            continue;
+        };
+        let speed = basis
+            .effective_velocity()
+            .reject_from(ground_normal.adjust_precision())
+            .length();
+        footsteps.distance_since_last_step += speed * frame_duration;
+        if 0.0 < footsteps.stride_length
+            && footsteps.stride_length <= footsteps.distance_since_last_step
+        {
+            footsteps.distance_since_last_step %= footsteps.stride_length;
+            writer.send(TnuaFootstepEvent {
+                entity,
+                ground_entity,
+                speed,
+                is_second_foot: footsteps.is_second_foot,
+            });
+            footsteps.is_second_foot = !footsteps.is_second_foot;
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Vector3};
+
+use super::TnuaVolumeShape;
+use crate::controller::TnuaExternalAcceleration;
+use crate::{TnuaPipelineStages, TnuaToggle, TnuaUpdateTimer};
+
+pub struct TnuaForceFieldPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaForceFieldPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaForceFieldPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaForceField`] work.
+impl Plugin for TnuaForceFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaForceField>();
+        app.add_systems(
+            self.schedule,
+            apply_force_fields.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// A volume that continuously accelerates the characters inside it - e.g. for wind tunnels, fans
+/// and updrafts.
+///
+/// The accelerations of all the force fields that contain the character's center are summed into
+/// its [`TnuaExternalAcceleration`] component, which the
+/// [`TnuaController`](crate::controller::TnuaController) applies to the character. The basis does
+/// not treat the velocity it causes as a deviation it needs to correct - see
+/// [`TnuaBuiltinWalk::wind_resistance`](crate::builtins::TnuaBuiltinWalk::wind_resistance) for
+/// how much the walk basis resists it.
+///
+/// Using it requires three things:
+///
+/// 1. Adding the plugin [`TnuaForceFieldPlugin`].
+/// 2. Adding [`TnuaForceField`] as a component to the volume entities.
+/// 3. Adding [`TnuaExternalAcceleration`] as a component to the character entities. Note that the
+///    plugin overwrites it every frame.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaForceField {
+    /// The volume, relative to the entity's transform, where the force field is in effect.
+    pub shape: TnuaVolumeShape,
+
+    /// The acceleration, in world space, applied to the characters inside the force field.
+    pub acceleration: Vector3,
+}
+
+fn apply_force_fields(
+    fields_query: Query<(&TnuaForceField, &GlobalTransform)>,
+    mut query: Query<(
+        &GlobalTransform,
+        &mut TnuaExternalAcceleration,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+) {
+    for (transform, mut external_acceleration, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let position = transform.translation().adjust_precision();
+        external_acceleration.0 = fields_query
+            .iter()
+            .filter(|(field, field_transform)| field.shape.contains(field_transform, position))
+            .map(|(field, _)| field.acceleration)
+            .sum();
+    }
+}
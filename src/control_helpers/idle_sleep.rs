@@ -0,0 +1,227 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaSleepState, TnuaToggle,
+};
+
+pub struct TnuaIdleSleepPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaIdleSleepPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaIdleSleepPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaIdleSleep`] work.
+impl Plugin for TnuaIdleSleepPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaIdleSleep>();
+        app.add_systems(
+            self.schedule,
+            update_idle_sleep
+                .in_set(TnuaPipelineStages::Logic)
+                .before(crate::controller::apply_controller_system),
+        );
+    }
+}
+
+/// Puts the character to sleep when it stands still, to save the cost of updating it.
+///
+/// A character is idle when its basis is a [`TnuaBuiltinWalk`] with zero `desired_velocity`, it
+/// has no actions, it does not move, and it has been standing on the same static ground for
+/// [`frames_before_sleep`](Self::frames_before_sleep) frames. When that happens, its
+/// [`TnuaSleepState`] is set to [`Asleep`](TnuaSleepState::Asleep) - the proximity sensor is no
+/// longer updated, the controller does not run, and the motor is not applied. The physics engine
+/// keeps applying the forces from the last time the motor was applied, so the character keeps
+/// floating.
+///
+/// The only thing checked every frame is whether the character needs to wake up - because it was
+/// given input (any action, or a different `desired_velocity` or `desired_forward`) or because it
+/// started moving (e.g. something pushed it). Once every
+/// [`probe_interval`](Self::probe_interval) frames the proximity sensor is also updated, and the
+/// character wakes up if the ground under it has changed or started moving.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaIdleSleepPlugin`].
+/// 2. Adding [`TnuaIdleSleep`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+#[require(TnuaSleepState)]
+pub struct TnuaIdleSleep {
+    /// How many consecutive frames the character needs to be idle before falling asleep.
+    pub frames_before_sleep: u32,
+
+    /// While asleep, update the proximity sensor once every this many frames, to check if the
+    /// ground has changed.
+    pub probe_interval: u32,
+
+    /// The character is not considered idle (and wakes up when asleep) if it - or the ground under
+    /// it - moves faster than this, or rotates faster than this many radians per second.
+    pub wake_speed: Float,
+
+    /// The character is not considered idle (and wakes up when asleep) if the distance of the
+    /// ground changes by more than this.
+    pub proximity_tolerance: Float,
+
+    idle_frames: u32,
+    frames_since_probe: u32,
+    #[reflect(ignore)]
+    rest: Option<RestState>,
+}
+
+impl Default for TnuaIdleSleep {
+    fn default() -> Self {
+        Self {
+            frames_before_sleep: 30,
+            probe_interval: 10,
+            wake_speed: 0.05,
+            proximity_tolerance: 0.01,
+            idle_frames: 0,
+            frames_since_probe: 0,
+            rest: None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct RestState {
+    ground_entity: Entity,
+    proximity: Float,
+    desired_forward: Option<Dir3>,
+}
+
+impl TnuaIdleSleep {
+    fn is_still(&self, velocity: Vector3, angvel: Vector3) -> bool {
+        velocity.length() <= self.wake_speed && angvel.length() <= self.wake_speed
+    }
+
+    /// The state the character rests in, or `None` if it is not idle.
+    fn rest_state(
+        &self,
+        controller: &TnuaController,
+        tracker: &TnuaRigidBodyTracker,
+        sensor: &TnuaProximitySensor,
+    ) -> Option<RestState> {
+        if !controller.is_settled() || controller.is_airborne().unwrap_or(true) {
+            return None;
+        }
+        let (walk, _) = controller.concrete_basis::<TnuaBuiltinWalk>()?;
+        if walk.desired_velocity != Vector3::ZERO {
+            return None;
+        }
+        if !self.is_still(tracker.velocity, tracker.angvel) {
+            return None;
+        }
+        let ground = sensor.output.as_ref()?;
+        if !self.is_still(ground.entity_linvel, ground.entity_angvel) {
+            return None;
+        }
+        Some(RestState {
+            ground_entity: ground.entity,
+            proximity: ground.proximity,
+            desired_forward: walk.desired_forward,
+        })
+    }
+
+    fn same_rest(&self, a: &RestState, b: &RestState) -> bool {
+        a.ground_entity == b.ground_entity
+            && (a.proximity - b.proximity).abs() <= self.proximity_tolerance
+            && a.desired_forward == b.desired_forward
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_idle_sleep(
+    mut query: Query<(
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        &mut TnuaIdleSleep,
+        &mut TnuaSleepState,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (controller, tracker, sensor, mut idle_sleep, mut sleep_state, tnua_toggle) in
+        query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let idle_sleep = idle_sleep.as_mut();
+        match *sleep_state {
+            TnuaSleepState::Awake => {
+                let rest = idle_sleep.rest_state(controller, tracker, sensor);
+                let continues_resting = match (&rest, &idle_sleep.rest) {
+                    (Some(rest), Some(previous_rest)) => idle_sleep.same_rest(rest, previous_rest),
+                    _ => false,
+                };
+                idle_sleep.idle_frames = if continues_resting {
+                    idle_sleep.idle_frames + 1
+                } else {
+                    0
+                };
+                idle_sleep.rest = rest;
+                if idle_sleep.rest.is_some()
+                    && idle_sleep.frames_before_sleep <= idle_sleep.idle_frames
+                {
+                    *sleep_state = TnuaSleepState::Asleep;
+                    idle_sleep.frames_since_probe = 0;
+                }
+            }
+            TnuaSleepState::Probing | TnuaSleepState::Asleep => {
+                let should_stay_asleep = if let Some(rest) = idle_sleep.rest.as_ref() {
+                    let input_unchanged = controller.is_settled()
+                        && controller.concrete_basis::<TnuaBuiltinWalk>().is_some_and(
+                            |(walk, _)| {
+                                walk.desired_velocity == Vector3::ZERO
+                                    && walk.desired_forward == rest.desired_forward
+                            },
+                        );
+                    let ground_unchanged = *sleep_state == TnuaSleepState::Asleep
+                        || sensor.output.as_ref().is_some_and(|ground| {
+                            ground.entity == rest.ground_entity
+                                && (ground.proximity - rest.proximity).abs()
+                                    <= idle_sleep.proximity_tolerance
+                                && idle_sleep.is_still(ground.entity_linvel, ground.entity_angvel)
+                        });
+                    input_unchanged
+                        && ground_unchanged
+                        && idle_sleep.is_still(tracker.velocity, tracker.angvel)
+                } else {
+                    false
+                };
+                if should_stay_asleep {
+                    idle_sleep.frames_since_probe += 1;
+                    *sleep_state = if idle_sleep.probe_interval <= idle_sleep.frames_since_probe {
+                        // The sensor will be updated in the next frame, and checked here.
+                        idle_sleep.frames_since_probe = 0;
+                        TnuaSleepState::Probing
+                    } else {
+                        TnuaSleepState::Asleep
+                    };
+                } else {
+                    *sleep_state = TnuaSleepState::Awake;
+                    idle_sleep.idle_frames = 0;
+                    idle_sleep.rest = None;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,675 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::sync::Mutex;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::builtins::*;
+use crate::controller::TnuaController;
+use crate::math::Float;
+use crate::{TnuaAction, TnuaBasis, TnuaToggle, TnuaUserControlsSystemSet};
+
+pub struct TnuaInputRecordingPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaInputRecordingPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaInputRecordingPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaInputRecorder`] and [`TnuaInputReplayer`] work.
+///
+/// The schedule should be the same one
+/// [`TnuaControllerPlugin`](crate::controller::TnuaControllerPlugin) uses.
+impl Plugin for TnuaInputRecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            start_recorded_frames.before(TnuaUserControlsSystemSet),
+        );
+        app.add_systems(
+            self.schedule,
+            replay_recorded_frames.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+macro_rules! recorded_builtins {
+    (
+        $(#[$attr:meta])*
+        $enum_name:ident, $feed:path, $mode:ty, { $($variant:ident($builtin:ty)),* $(,)? }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum $enum_name {
+            $($variant($builtin),)*
+        }
+
+        $(
+            impl From<$builtin> for $enum_name {
+                fn from(builtin: $builtin) -> Self {
+                    Self::$variant(builtin)
+                }
+            }
+        )*
+
+        impl $enum_name {
+            fn feed(&self, controller: &mut TnuaController, name: &'static str, mode: $mode) {
+                match self {
+                    $(Self::$variant(builtin) => $feed(controller, name, builtin, mode),)*
+                }
+            }
+
+            // Only needed for bases, for matching the previous profile's basis to the current one.
+            #[allow(dead_code)]
+            fn as_any(&self) -> &dyn Any {
+                match self {
+                    $(Self::$variant(builtin) => builtin,)*
+                }
+            }
+        }
+    };
+}
+
+recorded_builtins!(
+    /// A builtin basis, as stored in a [`TnuaInputRecording`].
+    TnuaRecordedBasis, feed_basis, BasisFeedMode, {
+        Climb(TnuaBuiltinClimb),
+        Fly(TnuaBuiltinFly),
+        Skate(TnuaBuiltinSkate),
+        Swim(TnuaBuiltinSwim),
+        Vehicle(TnuaBuiltinVehicle),
+        Walk(TnuaBuiltinWalk),
+    }
+);
+
+recorded_builtins!(
+    /// A builtin action, as stored in a [`TnuaInputRecording`].
+    TnuaRecordedAction, feed_action, ActionFeedMode, {
+        Crouch(TnuaBuiltinCrouch),
+        Dash(TnuaBuiltinDash),
+        Glide(TnuaBuiltinGlide),
+        GroundPound(TnuaBuiltinGroundPound),
+        Jump(TnuaBuiltinJump),
+        Knockback(TnuaBuiltinKnockback),
+        LedgeGrab(TnuaBuiltinLedgeGrab),
+        Ski(TnuaBuiltinSki),
+        Sprint(TnuaBuiltinSprint),
+        Swing(TnuaBuiltinSwing),
+        WallJump(TnuaBuiltinWallJump),
+        WallRun(TnuaBuiltinWallRun),
+        WallSlide(TnuaBuiltinWallSlide),
+    }
+);
+
+#[derive(Clone, Copy)]
+enum BasisFeedMode<'a> {
+    Basis,
+    FromProfile {
+        previous_profile_basis: Option<&'a TnuaRecordedBasis>,
+    },
+}
+
+fn feed_basis<B: TnuaBasis>(
+    controller: &mut TnuaController,
+    name: &'static str,
+    basis: &B,
+    mode: BasisFeedMode,
+) {
+    match mode {
+        BasisFeedMode::Basis => controller.named_basis(name, basis.clone()),
+        BasisFeedMode::FromProfile {
+            previous_profile_basis,
+        } => {
+            let previous_profile_basis = previous_profile_basis
+                .and_then(|previous| previous.as_any().downcast_ref::<B>())
+                .unwrap_or(basis);
+            // The controller calls `make_basis` with the active profile's configuration first,
+            // and then - during a crossfade - with the previous profile's configuration.
+            let calls = Cell::new(0);
+            controller.named_basis_from_profile(name, |_: &B| {
+                let call = calls.replace(calls.get() + 1);
+                if call == 0 {
+                    basis.clone()
+                } else {
+                    previous_profile_basis.clone()
+                }
+            });
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ActionFeedMode {
+    Action,
+    Buffered { buffer_time: Float },
+    FromProfile,
+}
+
+fn feed_action<A: TnuaAction>(
+    controller: &mut TnuaController,
+    name: &'static str,
+    action: &A,
+    mode: ActionFeedMode,
+) {
+    match mode {
+        ActionFeedMode::Action => controller.named_action(name, action.clone()),
+        ActionFeedMode::Buffered { buffer_time } => {
+            controller.named_buffered_action(name, action.clone(), buffer_time)
+        }
+        ActionFeedMode::FromProfile => {
+            controller.named_action_from_profile(name, |_: &A| action.clone())
+        }
+    }
+}
+
+/// A single command fed to the controller, as stored in a [`TnuaInputRecording`].
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaRecordedFeed {
+    Basis {
+        name: Cow<'static, str>,
+        basis: TnuaRecordedBasis,
+    },
+    /// A basis fed with [`TnuaController::basis_from_profile`], stored after the configuration
+    /// from the profile was applied.
+    BasisFromProfile {
+        name: Cow<'static, str>,
+        basis: TnuaRecordedBasis,
+        /// The basis made from the previous profile's configuration, if the profiles were being
+        /// crossfaded.
+        previous_profile_basis: Option<Box<TnuaRecordedBasis>>,
+    },
+    NeutralizeBasis,
+    Action {
+        name: Cow<'static, str>,
+        action: TnuaRecordedAction,
+    },
+    BufferedAction {
+        name: Cow<'static, str>,
+        action: TnuaRecordedAction,
+        buffer_time: Float,
+    },
+    /// An action fed with [`TnuaController::action_from_profile`], stored after the
+    /// configuration from the profile was applied.
+    ActionFromProfile {
+        name: Cow<'static, str>,
+        action: TnuaRecordedAction,
+    },
+    SetActiveProfile {
+        name: Cow<'static, str>,
+        crossfade: Float,
+    },
+}
+
+impl TnuaRecordedFeed {
+    fn feed(&self, controller: &mut TnuaController) {
+        match self {
+            TnuaRecordedFeed::Basis { name, basis } => {
+                basis.feed(controller, static_name(name.clone()), BasisFeedMode::Basis)
+            }
+            TnuaRecordedFeed::BasisFromProfile {
+                name,
+                basis,
+                previous_profile_basis,
+            } => basis.feed(
+                controller,
+                static_name(name.clone()),
+                BasisFeedMode::FromProfile {
+                    previous_profile_basis: previous_profile_basis.as_deref(),
+                },
+            ),
+            TnuaRecordedFeed::NeutralizeBasis => controller.neutralize_basis(),
+            TnuaRecordedFeed::Action { name, action } => action.feed(
+                controller,
+                static_name(name.clone()),
+                ActionFeedMode::Action,
+            ),
+            TnuaRecordedFeed::BufferedAction {
+                name,
+                action,
+                buffer_time,
+            } => action.feed(
+                controller,
+                static_name(name.clone()),
+                ActionFeedMode::Buffered {
+                    buffer_time: *buffer_time,
+                },
+            ),
+            TnuaRecordedFeed::ActionFromProfile { name, action } => action.feed(
+                controller,
+                static_name(name.clone()),
+                ActionFeedMode::FromProfile,
+            ),
+            TnuaRecordedFeed::SetActiveProfile { name, crossfade } => {
+                controller.set_active_profile(static_name(name.clone()), *crossfade)
+            }
+        }
+    }
+
+    fn name_mut(&mut self) -> Option<&mut Cow<'static, str>> {
+        match self {
+            TnuaRecordedFeed::Basis { name, .. } => Some(name),
+            TnuaRecordedFeed::BasisFromProfile { name, .. } => Some(name),
+            TnuaRecordedFeed::NeutralizeBasis => None,
+            TnuaRecordedFeed::Action { name, .. } => Some(name),
+            TnuaRecordedFeed::BufferedAction { name, .. } => Some(name),
+            TnuaRecordedFeed::ActionFromProfile { name, .. } => Some(name),
+            TnuaRecordedFeed::SetActiveProfile { name, .. } => Some(name),
+        }
+    }
+}
+
+/// The controller needs `&'static str` names, but names of deserialized recordings (and
+/// simulation states) are owned strings. These are leaked - but only once for each distinct name.
+pub(crate) fn static_name(name: Cow<'static, str>) -> &'static str {
+    static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+    match name {
+        Cow::Borrowed(name) => name,
+        Cow::Owned(name) => {
+            let mut names = NAMES.lock().unwrap_or_else(|err| err.into_inner());
+            if let Some(existing) = names.iter().find(|existing| **existing == name.as_str()) {
+                existing
+            } else {
+                let leaked: &'static str = name.leak();
+                names.push(leaked);
+                leaked
+            }
+        }
+    }
+}
+
+/// The inputs fed to a [`TnuaController`], frame by frame, as recorded by [`TnuaInputRecorder`].
+///
+/// The recording stores the fed bases and actions as data, so with the `serde` feature it can be
+/// serialized - e.g. to save demos or movement regression tests to files.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaInputRecording {
+    frames: Vec<Vec<TnuaRecordedFeed>>,
+}
+
+impl TnuaInputRecording {
+    /// The number of frames in the recording.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether the recording has any frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The bases and actions fed in each frame of the recording.
+    pub fn frames(&self) -> &[Vec<TnuaRecordedFeed>] {
+        &self.frames
+    }
+}
+
+/// Records the basis and the actions fed to a [`TnuaController`], so that they can be fed back
+/// later with [`TnuaInputReplayer`] - e.g. for ghost racers, demo playback, or for checking that a
+/// change in the movement configuration does not change how the character moves.
+///
+/// Only the builtin bases and actions can be recorded (see [`TnuaRecordedBasis`] and
+/// [`TnuaRecordedAction`]). The recorder has a wrapper for each of the [`TnuaController`] methods
+/// that feed it input, except for the ones that configure it - like
+/// [`add_profile`](TnuaController::add_profile), [`profile_mut`](TnuaController::profile_mut) and
+/// [`set_basis_blend_duration`](TnuaController::set_basis_blend_duration) - which should be called
+/// on the controller directly, the same way, both when recording and when replaying.
+///
+/// For [`basis_from_profile`](Self::basis_from_profile) and
+/// [`action_from_profile`](Self::action_from_profile), the recording stores the bases and actions
+/// after the configuration from the profile was applied. Changing the configuration of a profile
+/// will not affect how they are replayed.
+///
+/// The controls system needs to feed the controller through the recorder:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaInputRecorder;
+/// # use bevy_tnua::math::Vector3;
+/// # let mut controller = TnuaController::default();
+/// # let mut recorder = TnuaInputRecorder::default();
+/// # let (direction, jump_pressed) = (Vector3::ZERO, false);
+/// recorder.basis(
+///     &mut controller,
+///     TnuaBuiltinWalk {
+///         desired_velocity: direction * 10.0,
+///         float_height: 1.5,
+///         ..Default::default()
+///     },
+/// );
+/// if jump_pressed {
+///     recorder.action(
+///         &mut controller,
+///         TnuaBuiltinJump {
+///             height: 4.0,
+///             ..Default::default()
+///         },
+///     );
+/// }
+/// ```
+///
+/// Note that the recording is per frame, not per second - so replaying it will only reproduce the
+/// same movement if both the recording and the replaying run in a fixed timestep schedule (e.g.
+/// `FixedUpdate`).
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaInputRecordingPlugin`].
+/// 2. Adding [`TnuaInputRecorder`] as a component to the character entity, and feeding the
+///    controller through it.
+#[derive(Component, Clone, Default)]
+pub struct TnuaInputRecorder {
+    recording: Option<TnuaInputRecording>,
+}
+
+impl TnuaInputRecorder {
+    /// Start recording, discarding the frames recorded so far.
+    pub fn start(&mut self) {
+        self.recording = Some(TnuaInputRecording::default());
+    }
+
+    /// Stop recording, and return the recorded frames.
+    ///
+    /// Returns `None` if the recorder was not recording.
+    pub fn stop(&mut self) -> Option<TnuaInputRecording> {
+        self.recording.take()
+    }
+
+    /// Whether the recorder is currently recording.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Feed a basis to the controller with [its default name](TnuaBasis::NAME), and record it if
+    /// recording.
+    pub fn basis<B: TnuaBasis + Into<TnuaRecordedBasis>>(
+        &mut self,
+        controller: &mut TnuaController,
+        basis: B,
+    ) {
+        self.named_basis(controller, B::NAME, basis);
+    }
+
+    /// Feed a basis to the controller with a custom name, and record it if recording.
+    pub fn named_basis<B: TnuaBasis + Into<TnuaRecordedBasis>>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        basis: B,
+    ) {
+        if let Some(frame) = self.current_frame() {
+            frame.push(TnuaRecordedFeed::Basis {
+                name: Cow::Borrowed(name),
+                basis: basis.clone().into(),
+            });
+        }
+        controller.named_basis(name, basis);
+    }
+
+    /// Feed an action to the controller with [its default name](TnuaAction::NAME), and record it
+    /// if recording.
+    pub fn action<A: TnuaAction + Into<TnuaRecordedAction>>(
+        &mut self,
+        controller: &mut TnuaController,
+        action: A,
+    ) {
+        self.named_action(controller, A::NAME, action);
+    }
+
+    /// Feed an action to the controller with a custom name, and record it if recording.
+    pub fn named_action<A: TnuaAction + Into<TnuaRecordedAction>>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        action: A,
+    ) {
+        if let Some(frame) = self.current_frame() {
+            frame.push(TnuaRecordedFeed::Action {
+                name: Cow::Borrowed(name),
+                action: action.clone().into(),
+            });
+        }
+        controller.named_action(name, action);
+    }
+
+    /// Feed a basis with [its default name](TnuaBasis::NAME) to the controller, based on the
+    /// configuration from the active profile, and record it if recording.
+    ///
+    /// See [`TnuaController::basis_from_profile`].
+    pub fn basis_from_profile<B: TnuaBasis + Into<TnuaRecordedBasis>>(
+        &mut self,
+        controller: &mut TnuaController,
+        make_basis: impl Fn(&B) -> B,
+    ) {
+        self.named_basis_from_profile(controller, B::NAME, make_basis);
+    }
+
+    /// Feed a basis with a custom name to the controller, based on the configuration from the
+    /// active profile, and record it if recording.
+    pub fn named_basis_from_profile<B: TnuaBasis + Into<TnuaRecordedBasis>>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        make_basis: impl Fn(&B) -> B,
+    ) {
+        let is_recording = self.is_recording();
+        let made_bases = RefCell::new(Vec::new());
+        controller.named_basis_from_profile(name, |config: &B| {
+            let basis = make_basis(config);
+            if is_recording {
+                made_bases.borrow_mut().push(basis.clone());
+            }
+            basis
+        });
+        let mut made_bases = made_bases.into_inner().into_iter();
+        if let (Some(frame), Some(basis)) = (self.current_frame(), made_bases.next()) {
+            frame.push(TnuaRecordedFeed::BasisFromProfile {
+                name: Cow::Borrowed(name),
+                basis: basis.into(),
+                previous_profile_basis: made_bases.next().map(|basis| Box::new(basis.into())),
+            });
+        }
+    }
+
+    /// Instruct the basis to pretend the user provided no input this frame, and record it if
+    /// recording.
+    ///
+    /// See [`TnuaController::neutralize_basis`].
+    pub fn neutralize_basis(&mut self, controller: &mut TnuaController) {
+        if let Some(frame) = self.current_frame() {
+            frame.push(TnuaRecordedFeed::NeutralizeBasis);
+        }
+        controller.neutralize_basis();
+    }
+
+    /// Keep feeding an action with [its default name](TnuaAction::NAME) to the controller for the
+    /// next `buffer_time` seconds or until it starts, and record it if recording.
+    ///
+    /// See [`TnuaController::buffered_action`].
+    pub fn buffered_action<A: TnuaAction + Into<TnuaRecordedAction>>(
+        &mut self,
+        controller: &mut TnuaController,
+        action: A,
+        buffer_time: Float,
+    ) {
+        self.named_buffered_action(controller, A::NAME, action, buffer_time);
+    }
+
+    /// Keep feeding an action with a custom name to the controller for the next `buffer_time`
+    /// seconds or until it starts, and record it if recording.
+    pub fn named_buffered_action<A: TnuaAction + Into<TnuaRecordedAction>>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        action: A,
+        buffer_time: Float,
+    ) {
+        if let Some(frame) = self.current_frame() {
+            frame.push(TnuaRecordedFeed::BufferedAction {
+                name: Cow::Borrowed(name),
+                action: action.clone().into(),
+                buffer_time,
+            });
+        }
+        controller.named_buffered_action(name, action, buffer_time);
+    }
+
+    /// Feed an action with [its default name](TnuaAction::NAME) to the controller, based on the
+    /// configuration from the active profile, and record it if recording.
+    ///
+    /// See [`TnuaController::action_from_profile`].
+    pub fn action_from_profile<A: TnuaAction + Into<TnuaRecordedAction>>(
+        &mut self,
+        controller: &mut TnuaController,
+        make_action: impl FnOnce(&A) -> A,
+    ) {
+        self.named_action_from_profile(controller, A::NAME, make_action);
+    }
+
+    /// Feed an action with a custom name to the controller, based on the configuration from the
+    /// active profile, and record it if recording.
+    pub fn named_action_from_profile<A: TnuaAction + Into<TnuaRecordedAction>>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        make_action: impl FnOnce(&A) -> A,
+    ) {
+        let is_recording = self.is_recording();
+        let mut made_action = None;
+        controller.named_action_from_profile(name, |config: &A| {
+            let action = make_action(config);
+            if is_recording {
+                made_action = Some(action.clone());
+            }
+            action
+        });
+        if let (Some(frame), Some(action)) = (self.current_frame(), made_action) {
+            frame.push(TnuaRecordedFeed::ActionFromProfile {
+                name: Cow::Borrowed(name),
+                action: action.into(),
+            });
+        }
+    }
+
+    /// Switch the controller to a different movement profile, and record it if recording.
+    ///
+    /// See [`TnuaController::set_active_profile`].
+    pub fn set_active_profile(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        crossfade: Float,
+    ) {
+        if let Some(frame) = self.current_frame() {
+            frame.push(TnuaRecordedFeed::SetActiveProfile {
+                name: Cow::Borrowed(name),
+                crossfade,
+            });
+        }
+        controller.set_active_profile(name, crossfade);
+    }
+
+    fn current_frame(&mut self) -> Option<&mut Vec<TnuaRecordedFeed>> {
+        let recording = self.recording.as_mut()?;
+        if recording.frames.is_empty() {
+            // Fed before the plugin's system got to run
+            recording.frames.push(Vec::new());
+        }
+        recording.frames.last_mut()
+    }
+}
+
+/// Feeds a [`TnuaInputRecording`] to the [`TnuaController`] of the character entity, one frame at
+/// a time.
+///
+/// The character's controls system should not feed the controller while it is being replayed.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaInputRecordingPlugin`].
+/// 2. Adding [`TnuaInputReplayer`] as a component to the character entity.
+#[derive(Component, Clone)]
+pub struct TnuaInputReplayer {
+    recording: TnuaInputRecording,
+    next_frame: usize,
+}
+
+impl TnuaInputReplayer {
+    /// Create a new replayer, to be added as a component to the character entity.
+    pub fn new(mut recording: TnuaInputRecording) -> Self {
+        for name in recording
+            .frames
+            .iter_mut()
+            .flatten()
+            .filter_map(TnuaRecordedFeed::name_mut)
+        {
+            *name = Cow::Borrowed(static_name(std::mem::take(name)));
+        }
+        Self {
+            recording,
+            next_frame: 0,
+        }
+    }
+
+    /// Whether all the frames of the recording were already fed.
+    pub fn is_finished(&self) -> bool {
+        self.recording.frames.len() <= self.next_frame
+    }
+
+    /// Replay the recording again from its first frame.
+    pub fn restart(&mut self) {
+        self.next_frame = 0;
+    }
+}
+
+fn start_recorded_frames(mut query: Query<(&mut TnuaInputRecorder, Option<&TnuaToggle>)>) {
+    for (mut recorder, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if let Some(recording) = recorder.recording.as_mut() {
+            recording.frames.push(Vec::new());
+        }
+    }
+}
+
+fn replay_recorded_frames(
+    mut query: Query<(
+        &mut TnuaInputReplayer,
+        &mut TnuaController,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (mut replayer, mut controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let Some(frame) = replayer.recording.frames.get(replayer.next_frame) else {
+            continue;
+        };
+        for feed in frame.iter() {
+            feed.feed(controller.as_mut());
+        }
+        replayer.next_frame += 1;
+    }
+}
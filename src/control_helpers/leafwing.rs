@@ -0,0 +1,233 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+use leafwing_input_manager::prelude::*;
+
+use crate::builtins::{TnuaBuiltinCrouch, TnuaBuiltinDash, TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::control_helpers::TnuaCrouchEnforcer;
+use crate::controller::TnuaController;
+use crate::{TnuaToggle, TnuaUserControlsSystemSet};
+
+pub struct TnuaLeafwingPlugin<A: Actionlike> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<A>,
+}
+
+impl<A: Actionlike> TnuaLeafwingPlugin<A> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<A: Actionlike> Default for TnuaLeafwingPlugin<A> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaLeafwingBindings`] work.
+///
+/// The plugin only reads the leafwing-input-manager [`ActionState`] - the
+/// [`InputManagerPlugin`] for the same action type must be added separately.
+impl<A: Actionlike> Plugin for TnuaLeafwingPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            apply_leafwing_bindings::<A>.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// Feeds the [`TnuaController`] from the leafwing-input-manager [`ActionState`] of the same
+/// entity.
+///
+/// Every frame, [`TnuaLeafwingPlugin`] feeds the [`walk`](Self::walk) basis with a
+/// `desired_velocity` taken from the [`move_axis`](Self::move_axis) action, and feeds each of the
+/// [`jump`](Self::jump), [`dash`](Self::dash) and [`crouch`](Self::crouch) actions for as long as
+/// its button is pressed. Bindings that are `None` are not fed, so a control system in
+/// [`TnuaUserControlsSystemSet`] can still feed them (or any other action) itself.
+///
+/// If the entity has a [`TnuaCrouchEnforcer`], the crouch action is passed through it.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::builtins::{TnuaBuiltinCrouch, TnuaBuiltinDash};
+/// # use bevy_tnua::control_helpers::TnuaLeafwingBindings;
+/// # use bevy_tnua::math::Vector3;
+/// # use bevy_tnua::prelude::*;
+/// # use leafwing_input_manager::prelude::*;
+/// #[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+/// enum PlayerAction {
+///     #[actionlike(DualAxis)]
+///     Move,
+///     Jump,
+///     Dash,
+///     Crouch,
+/// }
+///
+/// # let mut commands: Commands = panic!();
+/// # let mut cmd = commands.spawn_empty();
+/// cmd.insert(TnuaLeafwingBindings {
+///     move_axis: Some(PlayerAction::Move),
+///     speed: 10.0,
+///     walk: TnuaBuiltinWalk {
+///         float_height: 1.5,
+///         ..Default::default()
+///     },
+///     jump: Some((
+///         PlayerAction::Jump,
+///         TnuaBuiltinJump {
+///             height: 4.0,
+///             ..Default::default()
+///         },
+///     )),
+///     dash: Some((
+///         PlayerAction::Dash,
+///         TnuaBuiltinDash {
+///             displacement: Vector3::new(10.0, 0.0, 0.0),
+///             ..Default::default()
+///         },
+///     )),
+///     crouch: Some((
+///         PlayerAction::Crouch,
+///         TnuaBuiltinCrouch {
+///             float_offset: -0.9,
+///             ..Default::default()
+///         },
+///     )),
+///     ..Default::default()
+/// });
+/// ```
+///
+/// Using it requires three things:
+///
+/// 1. Enabling the `leafwing` feature of Tnua.
+/// 2. Adding the plugin [`TnuaLeafwingPlugin`], with the same action type as the
+///    [`InputManagerPlugin`].
+/// 3. Adding [`TnuaLeafwingBindings`] as a component to the character entity (which should also
+///    have an [`InputManagerBundle`] of the same action type).
+#[derive(Component, Clone)]
+pub struct TnuaLeafwingBindings<A: Actionlike> {
+    /// A dual-axis action for moving the character.
+    pub move_axis: Option<A>,
+
+    /// The direction the character moves in when the X of [`move_axis`](Self::move_axis) is
+    /// positive.
+    pub axis_x_direction: Vector3,
+
+    /// The direction the character moves in when the Y of [`move_axis`](Self::move_axis) is
+    /// positive.
+    ///
+    /// For a side-scroller, set it to zero so that pressing up or down does not move the
+    /// character.
+    pub axis_y_direction: Vector3,
+
+    /// The speed of the character when [`move_axis`](Self::move_axis) is fully tilted.
+    pub speed: Float,
+
+    /// Turn the character to the direction it moves in.
+    pub face_movement: bool,
+
+    /// The configuration of the walk basis. Its `desired_velocity` is ignored, and so is its
+    /// `desired_forward` when [`face_movement`](Self::face_movement) is set.
+    pub walk: TnuaBuiltinWalk,
+
+    /// A button action for jumping, and the configuration of the jump.
+    pub jump: Option<(A, TnuaBuiltinJump)>,
+
+    /// A button action for dashing, and the configuration of the dash.
+    ///
+    /// The dash goes in the direction of [`move_axis`](Self::move_axis), for the length of the
+    /// configuration's `displacement`. It is not started when there is no movement input.
+    pub dash: Option<(A, TnuaBuiltinDash)>,
+
+    /// A button action for crouching, and the configuration of the crouch.
+    pub crouch: Option<(A, TnuaBuiltinCrouch)>,
+}
+
+impl<A: Actionlike> Default for TnuaLeafwingBindings<A> {
+    fn default() -> Self {
+        Self {
+            move_axis: None,
+            axis_x_direction: Vector3::X,
+            axis_y_direction: Vector3::NEG_Z,
+            speed: 10.0,
+            face_movement: true,
+            walk: Default::default(),
+            jump: None,
+            dash: None,
+            crouch: None,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_leafwing_bindings<A: Actionlike>(
+    mut query: Query<(
+        &TnuaLeafwingBindings<A>,
+        &ActionState<A>,
+        &mut TnuaController,
+        Option<&mut TnuaCrouchEnforcer>,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (bindings, action_state, mut controller, crouch_enforcer, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let direction = if let Some(move_axis) = bindings.move_axis.as_ref() {
+            let axis = action_state.clamped_axis_pair(move_axis).adjust_precision();
+            bindings.axis_x_direction * axis.x + bindings.axis_y_direction * axis.y
+        } else {
+            Vector3::ZERO
+        };
+        let direction_to_face = if bindings.face_movement {
+            Dir3::new(direction.f32()).ok()
+        } else {
+            bindings.walk.desired_forward
+        };
+
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: direction * bindings.speed,
+            desired_forward: direction_to_face,
+            ..bindings.walk.clone()
+        });
+
+        if let Some((crouch_action, crouch)) = bindings.crouch.as_ref() {
+            if action_state.pressed(crouch_action) {
+                if let Some(mut crouch_enforcer) = crouch_enforcer {
+                    controller.action(crouch_enforcer.enforcing(crouch.clone()));
+                } else {
+                    controller.action(crouch.clone());
+                }
+            }
+        }
+
+        if let Some((jump_action, jump)) = bindings.jump.as_ref() {
+            if action_state.pressed(jump_action) {
+                controller.action(jump.clone());
+            }
+        }
+
+        if let Some((dash_action, dash)) = bindings.dash.as_ref() {
+            if action_state.pressed(dash_action) && direction != Vector3::ZERO {
+                controller.action(TnuaBuiltinDash {
+                    displacement: direction.normalize() * dash.displacement.length(),
+                    desired_forward: if bindings.face_movement {
+                        direction_to_face
+                    } else {
+                        dash.desired_forward
+                    },
+                    ..dash.clone()
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use super::sensor_modifier::SensorModifier;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaToggle, TnuaUpdateTimer,
+};
+
+pub struct TnuaLedgeSensorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaLedgeSensorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaLedgeSensorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaLedgeSensor`] work.
+impl Plugin for TnuaLedgeSensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaLedgeSensor>();
+        app.register_type::<TnuaLedge>();
+        app.add_systems(
+            self.schedule,
+            update_ledge_sensors.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Detects ledges in front of the character, for actions like
+/// [`TnuaBuiltinLedgeGrab`](crate::builtins::TnuaBuiltinLedgeGrab).
+///
+/// This will create two child entities with proximity sensors:
+///
+/// * A forward sensor, cast from above the character's head in the direction of the character's
+///   negative Z axis. If it hits something, there is no room to climb up there.
+/// * A downward sensor, cast from above the character's head and ahead of it. If it hits
+///   something (and the forward sensor didn't) - that's a ledge.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaLedgeSensorPlugin`].
+/// 2. Adding [`TnuaLedgeSensor`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaLedgeSensor {
+    #[reflect(ignore)]
+    forward_sensor_entity: Option<Entity>,
+    #[reflect(ignore)]
+    down_sensor_entity: Option<Entity>,
+    head_origin: Vector3,
+    reach: Float,
+    grab_depth: Float,
+    #[reflect(ignore)]
+    modify_sensor: SensorModifier,
+    #[reflect(ignore)]
+    ledge: Option<TnuaLedge>,
+}
+
+impl TnuaLedgeSensor {
+    /// Create a new ledge sensor, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `head_origin` - the origin of both proximity sensors, in the character's coord system.
+    ///   Should be placed a little above the top of the collider.
+    /// * `reach` - how far ahead of the character a ledge can be detected. The downward sensor is
+    ///   cast from that distance ahead of the `head_origin`.
+    /// * `grab_depth` - how far below the `head_origin` the top of the ledge can be.
+    /// * `modify_sensor` - a function called with the command that creates each sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        head_origin: Vector3,
+        reach: Float,
+        grab_depth: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            forward_sensor_entity: None,
+            down_sensor_entity: None,
+            head_origin,
+            reach,
+            grab_depth,
+            modify_sensor: SensorModifier::new(modify_sensor),
+            ledge: None,
+        }
+    }
+
+    /// The ledge detected during the last update.
+    pub fn ledge(&self) -> Option<&TnuaLedge> {
+        self.ledge.as_ref()
+    }
+}
+
+/// A ledge detected by [`TnuaLedgeSensor`].
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaLedge {
+    /// The entity of the ledge.
+    pub entity: Entity,
+    /// A point on the top of the ledge, in world coordinates.
+    pub position: Vector3,
+    /// The normal of the top of the ledge.
+    pub normal: Dir3,
+    /// The horizontal direction from the character to the ledge.
+    pub forward: Dir3,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_ledge_sensors(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut TnuaLedgeSensor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, transform, mut ledge_sensor, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let ledge_sensor = ledge_sensor.as_mut();
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        let rotation = rotation.adjust_precision();
+        let forward = Dir3::new((rotation * Vector3::NEG_Z).f32()).unwrap_or(Dir3::NEG_Z);
+        let down = Dir3::new((rotation * Vector3::NEG_Y).f32()).unwrap_or(Dir3::NEG_Y);
+        let down_origin = ledge_sensor.head_origin + Vector3::NEG_Z * ledge_sensor.reach;
+
+        let mut update_sensor = |sensor_entity: &mut Option<Entity>,
+                                 cast_origin: Vector3,
+                                 cast_direction: Dir3,
+                                 cast_range: Float|
+         -> Option<Option<TnuaProximitySensorOutput>> {
+            if let Some((mut sensor, true)) =
+                sensor_entity.and_then(|entity| sensors_query.get_mut(entity).ok())
+            {
+                sensor.cast_origin = cast_origin;
+                sensor.cast_direction = cast_direction;
+                sensor.cast_range = cast_range;
+                Some(sensor.output.clone())
+            } else {
+                let mut cmd = commands.spawn((
+                    Transform::default(),
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin,
+                        cast_direction,
+                        cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                ledge_sensor.modify_sensor.apply(&mut cmd);
+                *sensor_entity = Some(cmd.id());
+                None
+            }
+        };
+
+        let forward_output = update_sensor(
+            &mut ledge_sensor.forward_sensor_entity,
+            ledge_sensor.head_origin,
+            forward,
+            ledge_sensor.reach,
+        );
+        let down_output = update_sensor(
+            &mut ledge_sensor.down_sensor_entity,
+            down_origin,
+            down,
+            ledge_sensor.grab_depth,
+        );
+
+        ledge_sensor.ledge =
+            if let (Some(None), Some(Some(down_output))) = (forward_output, down_output) {
+                Some(TnuaLedge {
+                    entity: down_output.entity,
+                    position: transform
+                        .transform_point(down_origin.f32())
+                        .adjust_precision()
+                        + down.adjust_precision() * down_output.proximity,
+                    normal: down_output.normal,
+                    forward,
+                })
+            } else {
+                // Either there is an obstacle above the ledge, there is no ledge, or the sensors were
+                // just created.
+                None
+            };
+    }
+}
@@ -0,0 +1,140 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Vector2, Vector3};
+
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaToggle, TnuaUpdateTimer};
+
+pub struct TnuaLockOnPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaLockOnPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaLockOnPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaLockOn`] work.
+impl Plugin for TnuaLockOnPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaLockOn>();
+        app.add_systems(
+            self.schedule,
+            update_lock_on.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Keeps track of the direction to a target entity, for lock-on (strafing) controls.
+///
+/// While [`target`](Self::target) is set, [`look_direction`](Self::look_direction) points at it
+/// and [`strafe`](Self::strafe) converts the input to movement relative to it - so that moving
+/// sideways circles around the target. Feed them to the walk basis:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaLockOn;
+/// # use bevy_tnua::math::{Vector2, Vector3};
+/// # let mut controller = TnuaController::default();
+/// # let lock_on = TnuaLockOn::default();
+/// # let input = Vector2::ZERO;
+/// # let direction = Vector3::ZERO;
+/// controller.basis(TnuaBuiltinWalk {
+///     desired_velocity: lock_on.strafe(input).unwrap_or(direction) * 5.0,
+///     desired_look: lock_on.look_direction(),
+///     look_angvel: 15.0,
+///     float_height: 1.5,
+///     ..Default::default()
+/// });
+/// ```
+///
+/// The [`look_angvel`](crate::builtins::TnuaBuiltinWalk::look_angvel) limits how fast the
+/// character turns toward the target, independently of its regular turning.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaLockOnPlugin`].
+/// 2. Adding [`TnuaLockOn`] as a component to the character entity.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct TnuaLockOn {
+    /// The entity to lock on. Set to `None` to release the lock.
+    pub target: Option<Entity>,
+
+    #[reflect(ignore)]
+    look_direction: Option<Dir3>,
+    #[reflect(ignore)]
+    right: Vector3,
+}
+
+impl TnuaLockOn {
+    /// Create a new lock-on locked on the specified target.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target: Some(target),
+            look_direction: None,
+            right: Vector3::ZERO,
+        }
+    }
+
+    /// The direction (orthogonal to the up direction) from the character to the target.
+    ///
+    /// `None` when there is no target, or when the target no longer exists.
+    pub fn look_direction(&self) -> Option<Dir3> {
+        self.look_direction
+    }
+
+    /// Convert an input, where positive `x` is to the right and positive `y` is toward the target,
+    /// to a world space direction.
+    ///
+    /// `None` when there is no [`look_direction`](Self::look_direction).
+    pub fn strafe(&self, input: Vector2) -> Option<Vector3> {
+        let forward = self.look_direction?.adjust_precision();
+        Some(forward * input.y + self.right * input.x)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_lock_on(
+    mut query: Query<(
+        &GlobalTransform,
+        &TnuaProximitySensor,
+        &mut TnuaLockOn,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    targets_query: Query<&GlobalTransform>,
+) {
+    for (transform, proximity_sensor, mut lock_on, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let up = -proximity_sensor.cast_direction.adjust_precision();
+        let look_direction = lock_on
+            .target
+            .and_then(|target| targets_query.get(target).ok())
+            .and_then(|target_transform| {
+                let to_target =
+                    (target_transform.translation() - transform.translation()).adjust_precision();
+                Dir3::new(to_target.reject_from(up).f32()).ok()
+            });
+        lock_on.look_direction = look_direction;
+        lock_on.right = look_direction.map_or(Vector3::ZERO, |look_direction| {
+            look_direction.adjust_precision().cross(up)
+        });
+    }
+}
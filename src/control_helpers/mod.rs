@@ -6,10 +6,55 @@
 //! customizability as it can provide. This, however, means that some of the advanced features can
 //! be complex to use. This module provides helpers that allow using these features in an easier
 //! although less flexible way.
+mod ai_input;
 mod air_actions_tracking;
+mod ceiling_sensor;
 mod crouch_enforcer;
+mod custom_gravity;
+mod edge_sensor;
+mod footsteps;
+mod force_field;
+mod idle_sleep;
+mod input_recording;
+#[cfg(feature = "leafwing")]
+mod leafwing;
+mod ledge_sensor;
+mod lock_on;
+mod multi_jump;
+mod multi_ray_sensor;
+mod path_following;
+mod push_control;
+mod reconciliation;
+mod sensor_modifier;
 mod simple_fall_through_platforms;
+mod step_up;
+mod visual_interpolation;
+mod volume_shape;
+mod wall_sensor;
+mod water_sensor;
 
+pub use ai_input::*;
 pub use air_actions_tracking::*;
+pub use ceiling_sensor::*;
 pub use crouch_enforcer::*;
+pub use custom_gravity::*;
+pub use edge_sensor::*;
+pub use footsteps::*;
+pub use force_field::*;
+pub use idle_sleep::*;
+pub use input_recording::*;
+#[cfg(feature = "leafwing")]
+pub use leafwing::*;
+pub use ledge_sensor::*;
+pub use lock_on::*;
+pub use multi_jump::*;
+pub use multi_ray_sensor::*;
+pub use path_following::*;
+pub use push_control::*;
+pub use reconciliation::*;
 pub use simple_fall_through_platforms::*;
+pub use step_up::*;
+pub use visual_interpolation::*;
+pub use volume_shape::*;
+pub use wall_sensor::*;
+pub use water_sensor::*;
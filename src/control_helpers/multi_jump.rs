@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+
+use crate::builtins::TnuaBuiltinJump;
+use crate::math::Float;
+use crate::prelude::*;
+
+use super::{TnuaAirActionsTracker, TnuaAirActionsUpdate};
+
+/// An helper for implementing double jumps, triple jumps, and so on.
+///
+/// Unlike [`TnuaSimpleAirActionsCounter`](super::TnuaSimpleAirActionsCounter), which counts all
+/// the air actions together, this helper only counts jumps - so air dashes (or any other air
+/// action) will not consume air jumps.
+///
+/// It's [`update`](Self::update) must be called every frame - even when the character does not
+/// jump. The counter resets when the character touches the ground, and since it relies on the
+/// basis to determine that it plays well with coyote time - a jump during coyote time is
+/// considered a ground jump.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaMultiJumpHelper;
+/// # let mut controller = TnuaController::default();
+/// # let jump_pressed = true;
+/// let mut multi_jump = TnuaMultiJumpHelper::new(2, 0.8);
+/// multi_jump.update(&controller);
+/// if jump_pressed {
+///     controller.action(multi_jump.jump(TnuaBuiltinJump {
+///         height: 4.0,
+///         ..Default::default()
+///     }));
+/// }
+/// ```
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaMultiJumpHelper {
+    /// The number of jumps the character can perform in the air after jumping from the ground or
+    /// starting a free fall.
+    pub extra_jumps: usize,
+
+    /// A factor applied to the jump height for each consecutive air jump.
+    ///
+    /// With `1.0` all the air jumps will be as high as the ground jump. With `0.5` the first air
+    /// jump will be half the height of the ground jump, the second air jump will be a quarter of
+    /// the height, and so on.
+    pub height_scaling: Float,
+
+    tracker: TnuaAirActionsTracker,
+    current_jump: Option<usize>,
+    jumps_count: usize,
+}
+
+impl Default for TnuaMultiJumpHelper {
+    fn default() -> Self {
+        Self::new(1, 1.0)
+    }
+}
+
+impl TnuaMultiJumpHelper {
+    /// Create a new multi jump helper, to be added as a component to the character entity.
+    pub fn new(extra_jumps: usize, height_scaling: Float) -> Self {
+        Self {
+            extra_jumps,
+            height_scaling,
+            tracker: Default::default(),
+            current_jump: None,
+            jumps_count: 0,
+        }
+    }
+
+    /// Call this every frame to track the jumps.
+    pub fn update(&mut self, controller: &TnuaController) {
+        match self.tracker.update(controller) {
+            TnuaAirActionsUpdate::NoChange => {}
+            TnuaAirActionsUpdate::FreeFallStarted => {
+                // The free fall is considered the first jump
+                self.current_jump = None;
+                self.jumps_count += 1;
+            }
+            TnuaAirActionsUpdate::AirActionStarted(action_name) => {
+                if action_name == TnuaBuiltinJump::NAME {
+                    self.current_jump = Some(self.jumps_count);
+                    self.jumps_count += 1;
+                } else {
+                    self.current_jump = None;
+                    // Leaving the ground with a different action is like starting a free fall
+                    if self.jumps_count == 0 {
+                        self.jumps_count = 1;
+                    }
+                }
+            }
+            TnuaAirActionsUpdate::ActionFinishedInAir => {
+                self.current_jump = None;
+            }
+            TnuaAirActionsUpdate::JustLanded => {
+                self.current_jump = None;
+                self.jumps_count = 0;
+            }
+        }
+    }
+
+    /// Calculate the "air number" of the jump.
+    ///
+    /// The air number of a ground jump is 0, of the first air jump (double jump) is 1, of the
+    /// second (triple jump) is 2 and so on. The number will not change while the jump action
+    /// continues to be fed.
+    pub fn air_number(&self) -> usize {
+        self.current_jump.unwrap_or(self.jumps_count)
+    }
+
+    /// The number of air jumps the character can still perform.
+    pub fn air_jumps_left(&self) -> usize {
+        (self.extra_jumps + 1).saturating_sub(self.jumps_count.max(1))
+    }
+
+    /// Restore all the air jumps - for example, when the player picks up a floating token.
+    ///
+    /// This has no effect when the character is grounded.
+    pub fn reset_jumps(&mut self) {
+        if 0 < self.jumps_count {
+            self.jumps_count = 1;
+        }
+    }
+
+    /// Configure a jump action according to the current air number.
+    ///
+    /// This sets [`allow_in_air`](TnuaBuiltinJump::allow_in_air) if the character still has air
+    /// jumps left, and scales the [`height`](TnuaBuiltinJump::height) by
+    /// [`height_scaling`](Self::height_scaling) for each air jump.
+    pub fn jump(&self, jump: TnuaBuiltinJump) -> TnuaBuiltinJump {
+        let air_number = self.air_number();
+        TnuaBuiltinJump {
+            allow_in_air: air_number <= self.extra_jumps,
+            height: jump.height * self.height_scaling.powi(air_number as i32),
+            ..jump
+        }
+    }
+}
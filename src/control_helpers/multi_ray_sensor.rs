@@ -0,0 +1,203 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{
+    float_consts, AdjustPrecision, AsF32, Float, Quaternion, Vector3,
+};
+
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaToggle, TnuaUpdateTimer,
+};
+
+pub struct TnuaMultiRaySensorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaMultiRaySensorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaMultiRaySensorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaMultiRaySensor`] work.
+impl Plugin for TnuaMultiRaySensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaMultiRaySensor>();
+        app.add_systems(
+            self.schedule,
+            update_multi_ray_sensors.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Averages the ground detected by a ring of rays around the character's proximity sensor, for a
+/// stable ground normal and height on bumpy terrain.
+///
+/// With a single ray, every small bump in a noisy trimesh changes the normal and the height the
+/// spring sees, which makes the character jitter. This will create child entities with proximity
+/// sensors cast in the same direction as the main sensor, from points on a circle around its cast
+/// origin. The proximity and the normal of the main sensor's output get replaced with the average
+/// of all the hits - ignoring rays that did not hit anything, and rays whose hits are too far
+/// above or below the main one (e.g. when standing near a ledge).
+///
+/// If the main ray does not hit anything but some of the rays around it do, the closest of them
+/// will be used as the main hit - so that the character will not fall when the center of its
+/// collider is past a ledge.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaMultiRaySensorPlugin`].
+/// 2. Adding [`TnuaMultiRaySensor`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaMultiRaySensor {
+    /// The radius of the circle the rays are cast from.
+    pub radius: Float,
+
+    /// Hits whose proximity differs from the main hit's proximity by more than this will not be
+    /// included in the average.
+    pub max_proximity_difference: Float,
+
+    ray_count: usize,
+    #[reflect(ignore)]
+    sensor_entities: Vec<Option<Entity>>,
+    // Kept between frames so that collecting the hits will not allocate.
+    #[reflect(ignore)]
+    ring_hits: Vec<TnuaProximitySensorOutput>,
+}
+
+impl TnuaMultiRaySensor {
+    /// Create a new multi ray sensor, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `ray_count` - the number of rays to cast around the main sensor's ray.
+    /// * `radius` - see [`radius`](Self::radius).
+    /// * `max_proximity_difference` - see
+    ///   [`max_proximity_difference`](Self::max_proximity_difference).
+    pub fn new(ray_count: usize, radius: Float, max_proximity_difference: Float) -> Self {
+        Self {
+            radius,
+            max_proximity_difference,
+            ray_count,
+            sensor_entities: Vec::new(),
+            ring_hits: Vec::with_capacity(ray_count),
+        }
+    }
+
+    /// The number of rays cast around the main sensor's ray.
+    pub fn ray_count(&self) -> usize {
+        self.ray_count
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_multi_ray_sensors(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut TnuaMultiRaySensor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, transform, mut multi_ray, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let multi_ray = multi_ray.as_mut();
+
+        let Ok((main_sensor, false)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let main_cast_origin = main_sensor.cast_origin;
+        let cast_direction = main_sensor.cast_direction;
+        let cast_range = main_sensor.cast_range;
+        let main_output = main_sensor.output.clone();
+
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        let inverse_rotation = rotation.adjust_precision().inverse();
+        let (first_offset, _) = cast_direction.adjust_precision().any_orthonormal_pair();
+        let first_offset = first_offset * multi_ray.radius;
+
+        let ring_hits = &mut multi_ray.ring_hits;
+        ring_hits.clear();
+        multi_ray.sensor_entities.resize(multi_ray.ray_count, None);
+        for (index, sensor_entity) in multi_ray.sensor_entities.iter_mut().enumerate() {
+            #[allow(clippy::unnecessary_cast)]
+            let angle = float_consts::TAU * index as Float / multi_ray.ray_count as Float;
+            let offset = Quaternion::from_axis_angle(cast_direction.adjust_precision(), angle)
+                * first_offset;
+            let sensor_cast_origin = main_cast_origin + inverse_rotation * offset;
+
+            if let Some((mut sensor, true)) =
+                sensor_entity.and_then(|entity| sensors_query.get_mut(entity).ok())
+            {
+                ring_hits.extend(sensor.output.clone());
+                sensor.cast_origin = sensor_cast_origin;
+                sensor.cast_direction = cast_direction;
+                sensor.cast_range = cast_range;
+            } else {
+                let mut cmd = commands.spawn((
+                    Transform::default(),
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin: sensor_cast_origin,
+                        cast_direction,
+                        cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                *sensor_entity = Some(cmd.id());
+            }
+        }
+
+        let Some(base_output) = main_output.clone().or_else(|| {
+            ring_hits
+                .iter()
+                .min_by(|a, b| a.proximity.total_cmp(&b.proximity))
+                .cloned()
+        }) else {
+            continue;
+        };
+
+        // The base output is either the main output or one of the ring hits, so it is always
+        // counted exactly once.
+        let mut proximity_sum = 0.0;
+        let mut normal_sum = Vector3::ZERO;
+        let mut hits_count: Float = 0.0;
+        for hit in main_output.iter().chain(ring_hits.iter()) {
+            if multi_ray.max_proximity_difference < (hit.proximity - base_output.proximity).abs() {
+                continue;
+            }
+            proximity_sum += hit.proximity;
+            normal_sum += hit.normal.adjust_precision();
+            hits_count += 1.0;
+        }
+        let normal = Dir3::new(normal_sum.f32()).unwrap_or(base_output.normal);
+
+        if let Ok((mut main_sensor, false)) = sensors_query.get_mut(owner_entity) {
+            main_sensor.output = Some(TnuaProximitySensorOutput {
+                proximity: proximity_sum / hits_count,
+                normal,
+                ..base_output
+            });
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use super::{apply_ai_input, TnuaAiInput};
+use crate::builtins::TnuaBuiltinJump;
+use crate::controller::TnuaController;
+use crate::{
+    TnuaAction, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaToggle, TnuaUpdateTimer,
+    TnuaUserControlsSystemSet,
+};
+
+pub struct TnuaPathFollowingPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaPathFollowingPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaPathFollowingPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaPathFollower`] work.
+impl Plugin for TnuaPathFollowingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaPathFollower>();
+        app.register_type::<TnuaPathFollowerStatus>();
+        app.register_type::<TnuaPathWaypoint>();
+        app.register_type::<TnuaPathLink>();
+        app.add_event::<TnuaPathFollowerEvent>();
+        app.add_systems(
+            self.schedule,
+            follow_paths
+                .in_set(TnuaUserControlsSystemSet)
+                .before(apply_ai_input),
+        );
+    }
+}
+
+/// Moves the character along a path of waypoints - e.g. one generated by a navmesh library - by
+/// writing to its [`TnuaAiInput`].
+///
+/// The character moves toward the current waypoint at [`speed`](Self::speed), and faces the
+/// direction it moves in:
+///
+/// * Once the character is within [`waypoint_radius`](Self::waypoint_radius) of a waypoint it
+///   moves on to the next one, so larger radii cut corners.
+/// * Within [`arrival_distance`](Self::arrival_distance) of the last waypoint the character slows
+///   down, and it stops within [`arrival_tolerance`](Self::arrival_tolerance) of it.
+/// * If the character moves slower than [`stuck_speed`](Self::stuck_speed) for
+///   [`stuck_time`](Self::stuck_time) seconds it is considered stuck and stops.
+///
+/// A [`TnuaPathFollowerEvent`] is sent when the character arrives or gets stuck.
+///
+/// Only the horizontal distances are considered, so the waypoints do not need to be at the
+/// height of the character's center.
+///
+/// Waypoints with a [`TnuaPathLink::Jump`] link are reached by jumping - across gaps, up to
+/// higher platforms, or down from ledges. Once the character reaches the waypoint before the jump
+/// link, the follower feeds a [`TnuaBuiltinJump`] (based on [`jump`](Self::jump)) with a height
+/// and a horizontal speed computed to land on the jump link's waypoint. A jump link on the first
+/// waypoint is ignored, since there is no waypoint to jump from. Waypoints are the points
+/// on the ground the character walks over, so for the computation to be correct they should all
+/// be at the same height above the ground.
+///
+/// Using it requires three things:
+///
+/// 1. Adding the plugins [`TnuaPathFollowingPlugin`] and
+///    [`TnuaAiInputPlugin`](super::TnuaAiInputPlugin).
+/// 2. Adding [`TnuaAiInput`] as a component to the character entity.
+/// 3. Adding [`TnuaPathFollower`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaPathFollower {
+    /// The speed the character moves at along the path.
+    pub speed: Float,
+
+    /// How close the character needs to get to a waypoint before moving on to the next one.
+    pub waypoint_radius: Float,
+
+    /// The distance from the last waypoint where the character starts slowing down.
+    pub arrival_distance: Float,
+
+    /// How close the character needs to get to the last waypoint to arrive.
+    pub arrival_tolerance: Float,
+
+    /// When the character moves slower than this while following the path, it may be stuck.
+    pub stuck_speed: Float,
+
+    /// The time, in seconds, the character needs to move slower than
+    /// [`stuck_speed`](Self::stuck_speed) to be considered stuck.
+    pub stuck_time: Float,
+
+    /// The jump action to feed for jump links. Its [`height`](TnuaBuiltinJump::height) is ignored,
+    /// because it gets computed for each jump.
+    ///
+    /// The computation takes the gravity and the
+    /// [`fall_extra_gravity`](TnuaBuiltinJump::fall_extra_gravity) into account, but not the
+    /// other extra gravities, so setting them to `0.0` makes the jumps more accurate.
+    pub jump: TnuaBuiltinJump,
+
+    /// How much higher than the higher of the two waypoints of a jump link the character should
+    /// jump.
+    pub jump_clearance: Float,
+
+    waypoints: Vec<TnuaPathWaypoint>,
+    current_waypoint: usize,
+    status: TnuaPathFollowerStatus,
+    slow_for: Float,
+    #[reflect(ignore)]
+    jump_link: Option<JumpLinkState>,
+}
+
+impl Default for TnuaPathFollower {
+    fn default() -> Self {
+        Self {
+            speed: 5.0,
+            waypoint_radius: 0.5,
+            arrival_distance: 2.0,
+            arrival_tolerance: 0.2,
+            stuck_speed: 0.5,
+            stuck_time: 1.0,
+            jump: Default::default(),
+            jump_clearance: 0.5,
+            waypoints: Vec::new(),
+            current_waypoint: 0,
+            status: TnuaPathFollowerStatus::Idle,
+            slow_for: 0.0,
+            jump_link: None,
+        }
+    }
+}
+
+/// A waypoint in the path of a [`TnuaPathFollower`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaPathWaypoint {
+    /// The position of the waypoint, in the world space.
+    pub position: Vector3,
+    /// How the character gets to this waypoint from the previous one.
+    pub link: TnuaPathLink,
+}
+
+impl TnuaPathWaypoint {
+    /// A waypoint the character walks to.
+    pub fn walk(position: Vector3) -> Self {
+        Self {
+            position,
+            link: TnuaPathLink::Walk,
+        }
+    }
+
+    /// A waypoint the character jumps to from the previous waypoint.
+    pub fn jump(position: Vector3) -> Self {
+        Self {
+            position,
+            link: TnuaPathLink::Jump,
+        }
+    }
+}
+
+impl From<Vector3> for TnuaPathWaypoint {
+    fn from(position: Vector3) -> Self {
+        Self::walk(position)
+    }
+}
+
+/// How a [`TnuaPathFollower`] gets to a waypoint from the previous one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaPathLink {
+    /// Walk to the waypoint.
+    #[default]
+    Walk,
+    /// Jump to the waypoint. Used for gap jumps and for jumping up to or down from platforms.
+    Jump,
+}
+
+#[derive(Clone)]
+struct JumpLinkState {
+    target: usize,
+    height: Float,
+    speed: Float,
+    took_off: bool,
+    landed: bool,
+}
+
+/// The status of a [`TnuaPathFollower`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum TnuaPathFollowerStatus {
+    /// There is no path to follow.
+    Idle,
+    /// The character is moving along the path.
+    Following,
+    /// The character has reached the last waypoint.
+    Arrived,
+    /// The character could not make progress along the path.
+    Stuck,
+}
+
+/// Sent by [`TnuaPathFollowingPlugin`] when a [`TnuaPathFollower`] stops following its path.
+#[derive(Event, Debug, Clone)]
+pub enum TnuaPathFollowerEvent {
+    /// The character has reached the last waypoint.
+    Arrived {
+        /// The character entity.
+        entity: Entity,
+    },
+    /// The character could not make progress along the path.
+    Stuck {
+        /// The character entity.
+        entity: Entity,
+        /// The index of the waypoint the character was moving toward.
+        waypoint: usize,
+    },
+}
+
+impl TnuaPathFollower {
+    /// Start following a new path, replacing the current one.
+    ///
+    /// The waypoints can be [`TnuaPathWaypoint`]s or plain positions (which the character walks
+    /// to).
+    pub fn set_path(&mut self, waypoints: impl IntoIterator<Item = impl Into<TnuaPathWaypoint>>) {
+        self.waypoints = waypoints.into_iter().map(Into::into).collect();
+        self.current_waypoint = 0;
+        self.slow_for = 0.0;
+        self.jump_link = None;
+        self.status = if self.waypoints.is_empty() {
+            TnuaPathFollowerStatus::Idle
+        } else {
+            TnuaPathFollowerStatus::Following
+        };
+    }
+
+    /// Stop following the path.
+    pub fn clear_path(&mut self) {
+        self.set_path(Vec::<TnuaPathWaypoint>::new());
+    }
+
+    /// The waypoints of the current path.
+    pub fn waypoints(&self) -> &[TnuaPathWaypoint] {
+        &self.waypoints
+    }
+
+    /// The index of the waypoint the character is moving toward.
+    pub fn current_waypoint(&self) -> usize {
+        self.current_waypoint
+    }
+
+    /// Whether the character is following the path, and if not - why.
+    pub fn status(&self) -> TnuaPathFollowerStatus {
+        self.status
+    }
+
+    /// Whether the character is currently traversing a jump link.
+    pub fn is_jumping(&self) -> bool {
+        self.jump_link
+            .as_ref()
+            .is_some_and(|jump_link| jump_link.target == self.current_waypoint && !jump_link.landed)
+    }
+
+    /// Calculate the jump height and the horizontal speed for jumping `distance` away to a point
+    /// `height_difference` higher than the takeoff point (or lower, when negative).
+    fn plan_jump(
+        &self,
+        distance: Float,
+        height_difference: Float,
+        gravity: Float,
+    ) -> (Float, Float) {
+        let fall_gravity = gravity + self.jump.fall_extra_gravity;
+        let flight_time = |height: Float| {
+            (2.0 * height / gravity).sqrt()
+                + (2.0 * (height - height_difference) / fall_gravity).sqrt()
+        };
+        let mut height = height_difference.max(0.0) + self.jump_clearance;
+        if distance <= self.speed * flight_time(height) {
+            return (height, distance / flight_time(height));
+        }
+        // Too far to jump at the follower's speed with the minimal height - jump higher to stay
+        // in the air longer. The flight time grows with the height, so a binary search will find
+        // the height where the follower's speed is enough.
+        let mut max_height = height * 2.0;
+        while self.speed * flight_time(max_height) < distance {
+            max_height *= 2.0;
+        }
+        for _ in 0..20 {
+            let middle = 0.5 * (height + max_height);
+            if self.speed * flight_time(middle) < distance {
+                height = middle;
+            } else {
+                max_height = middle;
+            }
+        }
+        (max_height, self.speed)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn follow_paths(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        &mut TnuaPathFollower,
+        &mut TnuaAiInput,
+        &mut TnuaController,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut writer: EventWriter<TnuaPathFollowerEvent>,
+) {
+    for (
+        entity,
+        tracker,
+        proximity_sensor,
+        mut follower,
+        mut ai_input,
+        mut controller,
+        tnua_toggle,
+        update_timer,
+    ) in query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let frame_duration = match update_timer {
+            Some(update_timer) if !update_timer.is_update_frame() => continue,
+            Some(update_timer) => update_timer.delta(),
+            None => time.delta(),
+        }
+        .as_secs_f64() as Float;
+        if follower.status != TnuaPathFollowerStatus::Following {
+            continue;
+        }
+        let up = -proximity_sensor.cast_direction.adjust_precision();
+        let to_waypoint = |waypoint: &TnuaPathWaypoint| -> Vector3 {
+            (waypoint.position - tracker.translation).reject_from(up)
+        };
+        let airborne = controller.is_airborne().unwrap_or(false);
+
+        if let Some(jump_link) = follower.jump_link.as_mut() {
+            if controller.action_name() == Some(TnuaBuiltinJump::NAME) {
+                jump_link.took_off = true;
+            } else if jump_link.took_off && !airborne {
+                jump_link.landed = true;
+            }
+        }
+
+        let last_waypoint = follower.waypoints.len() - 1;
+        while !follower.is_jumping()
+            && follower.current_waypoint < last_waypoint
+            && to_waypoint(&follower.waypoints[follower.current_waypoint]).length()
+                <= follower.waypoint_radius
+        {
+            follower.current_waypoint += 1;
+        }
+
+        let current = follower.current_waypoint;
+        let to_current = to_waypoint(&follower.waypoints[current]);
+        if 0 < current
+            && follower.waypoints[current].link == TnuaPathLink::Jump
+            && follower
+                .jump_link
+                .as_ref()
+                .is_none_or(|jump_link| jump_link.target != current)
+        {
+            let gravity = tracker.gravity.dot(-up);
+            let height_difference = (follower.waypoints[current].position
+                - follower.waypoints[current - 1].position)
+                .dot(up);
+            follower.jump_link = if 0.0 < gravity {
+                let (height, speed) =
+                    follower.plan_jump(to_current.length(), height_difference, gravity);
+                Some(JumpLinkState {
+                    target: current,
+                    height,
+                    speed,
+                    took_off: false,
+                    landed: false,
+                })
+            } else {
+                // Without gravity, the character will just float there.
+                None
+            };
+        }
+
+        let jumping = follower.is_jumping();
+        let jump_link = follower.jump_link.clone().filter(|_| jumping);
+        if let Some(jump_link) = &jump_link {
+            controller.action(TnuaBuiltinJump {
+                height: jump_link.height,
+                ..follower.jump.clone()
+            });
+        }
+
+        let distance_to_end = if jumping {
+            // Slowing down in the air would make the character miss the landing.
+            Float::INFINITY
+        } else if current == last_waypoint {
+            let distance = to_current.length();
+            if distance <= follower.arrival_tolerance {
+                follower.status = TnuaPathFollowerStatus::Arrived;
+                ai_input.desired_velocity = Vector3::ZERO;
+                writer.send(TnuaPathFollowerEvent::Arrived { entity });
+                continue;
+            }
+            distance
+        } else {
+            Float::INFINITY
+        };
+
+        // Only count the time the character was asked to move faster than the stuck speed, so
+        // that slowing down for the arrival does not count, and not the time in the air during a
+        // jump link.
+        if !jump_link
+            .as_ref()
+            .is_some_and(|jump_link| jump_link.took_off)
+            && follower.stuck_speed < ai_input.desired_velocity.length()
+            && tracker.velocity.reject_from(up).length() < follower.stuck_speed
+        {
+            follower.slow_for += frame_duration;
+            if follower.stuck_time <= follower.slow_for {
+                follower.status = TnuaPathFollowerStatus::Stuck;
+                ai_input.desired_velocity = Vector3::ZERO;
+                writer.send(TnuaPathFollowerEvent::Stuck {
+                    entity,
+                    waypoint: follower.current_waypoint,
+                });
+                continue;
+            }
+        } else {
+            follower.slow_for = 0.0;
+        }
+
+        let speed_factor = if 0.0 < follower.arrival_distance {
+            (distance_to_end / follower.arrival_distance).min(1.0)
+        } else {
+            1.0
+        };
+        let speed = if let Some(jump_link) = &jump_link {
+            jump_link.speed
+        } else {
+            follower.speed
+        };
+        let direction = to_current.normalize_or_zero();
+        ai_input.desired_velocity = direction * speed * speed_factor;
+        if let Ok(direction) = Dir3::new(direction.f32()) {
+            ai_input.desired_facing = Some(direction);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use super::sensor_modifier::SensorModifier;
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{TnuaMotor, TnuaPipelineStages, TnuaProximitySensor, TnuaToggle, TnuaUpdateTimer};
+
+pub struct TnuaPushControlPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaPushControlPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaPushControlPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaPushControl`] work.
+impl Plugin for TnuaPushControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaPushControl>();
+        app.register_type::<TnuaPushable>();
+        app.add_systems(
+            self.schedule,
+            update_push_control_sensors.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+        app.add_systems(
+            self.schedule,
+            apply_push_control
+                .in_set(TnuaPipelineStages::Logic)
+                .after(crate::controller::apply_controller_system),
+        );
+    }
+}
+
+/// Limits how strongly a character walking with [`TnuaBuiltinWalk`] pushes dynamic bodies.
+///
+/// Without this, the character keeps accelerating toward its
+/// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) with the full
+/// [`acceleration`](TnuaBuiltinWalk::acceleration) even when a dynamic body is in its way - which
+/// the physics engine translates to a force that bulldozes light bodies.
+///
+/// This will create a child entity with a proximity sensor cast in the direction the character
+/// walks. When that sensor detects an entity with a [`TnuaPushable`] component, the acceleration
+/// toward it gets limited to [`push_acceleration`](Self::push_acceleration) - or cancelled
+/// altogether if the body is heavier than [`max_push_mass`](Self::max_push_mass), so that the
+/// character will stand against it like it would against a wall. Entities without
+/// [`TnuaPushable`] are not affected.
+///
+/// Using it requires three things:
+///
+/// 1. Adding the plugin [`TnuaPushControlPlugin`].
+/// 2. Adding [`TnuaPushControl`] as a component to the character entity.
+/// 3. Adding [`TnuaPushable`] as a component to the dynamic bodies that the character can push.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaPushControl {
+    /// The maximum acceleration the character can apply toward a body it pushes.
+    pub push_acceleration: Float,
+
+    /// The maximum [`mass`](TnuaPushable::mass) of a body the character can push.
+    pub max_push_mass: Float,
+
+    #[reflect(ignore)]
+    sensor_entity: Option<Entity>,
+    cast_origin: Vector3,
+    cast_range: Float,
+    #[reflect(ignore)]
+    modify_sensor: SensorModifier,
+    #[reflect(ignore)]
+    pushing: Option<(Entity, Dir3)>,
+}
+
+impl TnuaPushControl {
+    /// Create a new push control, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `push_acceleration` - see [`push_acceleration`](Self::push_acceleration).
+    /// * `max_push_mass` - see [`max_push_mass`](Self::max_push_mass).
+    /// * `cast_origin` - the origin of the proximity sensor, in the character's coord system.
+    /// * `cast_range` - how far from the `cast_origin` a body can be to get pushed. Should be a
+    ///   little bigger than the distance from the `cast_origin` to the side of the collider.
+    /// * `modify_sensor` - a function called with the command that creates the sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        push_acceleration: Float,
+        max_push_mass: Float,
+        cast_origin: Vector3,
+        cast_range: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            push_acceleration,
+            max_push_mass,
+            sensor_entity: None,
+            cast_origin,
+            cast_range,
+            modify_sensor: SensorModifier::new(modify_sensor),
+            pushing: None,
+        }
+    }
+
+    /// The [`TnuaPushable`] entity the character is currently walking into.
+    pub fn pushing_entity(&self) -> Option<Entity> {
+        Some(self.pushing?.0)
+    }
+}
+
+/// A marker for dynamic bodies that [`TnuaPushControl`] should limit the pushing of.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct TnuaPushable {
+    /// The mass of the body, compared against [`TnuaPushControl::max_push_mass`].
+    ///
+    /// This is not read from the physics backend, so it does not have to be the body's actual
+    /// mass.
+    pub mass: Float,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_push_control_sensors(
+    mut query: Query<(
+        Entity,
+        &TnuaController,
+        &mut TnuaPushControl,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    pushables_query: Query<(), With<TnuaPushable>>,
+    mut commands: Commands,
+) {
+    for (owner_entity, controller, mut push_control, tnua_toggle, update_timer) in query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let push_control = push_control.as_mut();
+        push_control.pushing = None;
+
+        let Ok((main_sensor, false)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let up = -main_sensor.cast_direction.adjust_precision();
+
+        let push_direction = controller
+            .concrete_basis::<TnuaBuiltinWalk>()
+            .and_then(|(walk, _)| Dir3::new(walk.desired_velocity.reject_from(up).f32()).ok());
+
+        if let Some((mut sensor, true)) = push_control
+            .sensor_entity
+            .and_then(|entity| sensors_query.get_mut(entity).ok())
+        {
+            if let (Some(output), Some(push_direction)) = (sensor.output.as_ref(), push_direction) {
+                // The output is from the direction the sensor was cast at in the previous frame.
+                if 0.0 < sensor.cast_direction.dot(*push_direction)
+                    && pushables_query.contains(output.entity)
+                {
+                    push_control.pushing = Some((output.entity, sensor.cast_direction));
+                }
+            }
+            sensor.cast_origin = push_control.cast_origin;
+            if let Some(push_direction) = push_direction {
+                sensor.cast_direction = push_direction;
+                sensor.cast_range = push_control.cast_range;
+            } else {
+                // Turn it off
+                sensor.cast_range = 0.0;
+            }
+        } else {
+            let mut cmd = commands.spawn((
+                Transform::default(),
+                TnuaSubservientSensor { owner_entity },
+                TnuaProximitySensor {
+                    cast_origin: push_control.cast_origin,
+                    cast_direction: push_direction.unwrap_or(Dir3::NEG_Z),
+                    cast_range: if push_direction.is_some() {
+                        push_control.cast_range
+                    } else {
+                        0.0
+                    },
+                    ..Default::default()
+                },
+            ));
+            cmd.set_parent(owner_entity);
+            push_control.modify_sensor.apply(&mut cmd);
+            push_control.sensor_entity = Some(cmd.id());
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_push_control(
+    mut query: Query<(
+        &TnuaPushControl,
+        &TnuaController,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    pushables_query: Query<&TnuaPushable>,
+) {
+    for (push_control, controller, mut motor, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        if controller.basis_name().is_none() {
+            // Without a basis the motor does not get overwritten every frame, so changing it
+            // would accumulate.
+            continue;
+        }
+        let Some((pushed_entity, push_direction)) = push_control.pushing else {
+            continue;
+        };
+        let Ok(pushable) = pushables_query.get(pushed_entity) else {
+            continue;
+        };
+        let push_direction = push_direction.adjust_precision();
+
+        let too_heavy = push_control.max_push_mass < pushable.mass;
+
+        let max_acceleration = if too_heavy {
+            0.0
+        } else {
+            push_control.push_acceleration
+        };
+        let acceleration_toward = motor.lin.acceleration.dot(push_direction);
+        if max_acceleration < acceleration_toward {
+            motor.lin.acceleration -= (acceleration_toward - max_acceleration) * push_direction;
+        }
+
+        if too_heavy {
+            let boost_toward = motor.lin.boost.dot(push_direction);
+            if 0.0 < boost_toward {
+                motor.lin.boost -= boost_toward * push_direction;
+            }
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+use super::input_recording::static_name;
+use super::{TnuaInputRecording, TnuaInputReplayer};
+use crate::builtins::*;
+use crate::controller::TnuaController;
+use crate::math::Float;
+use crate::{
+    TnuaAction, TnuaBasis, TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput,
+    TnuaRigidBodyTracker,
+};
+
+/// A simulation state of a character that can be restored with [`tnua_resimulate`].
+///
+/// Implemented by [`TnuaSimulationSnapshot`] and [`TnuaSimulationState`].
+pub trait TnuaRestorableSimulation {
+    /// Overwrite the simulation state of a character entity with this one.
+    fn restore(&self, world: &mut World, entity: Entity);
+}
+
+/// The simulation state of a character, for client side prediction and server reconciliation.
+///
+/// This holds clones of all the components Tnua uses to simulate the character - including the
+/// state of the basis and the actions inside the [`TnuaController`]. The physics backend's own
+/// components (position, velocity, etc.) are not a part of it, and should be restored separately,
+/// usually with the values the server has sent.
+///
+/// The snapshot should be captured after the physics step, so that restoring it and running the
+/// next tick continues from the same point.
+///
+/// The snapshot cannot be serialized. For sending the simulation state over the network, use
+/// [`TnuaSimulationState`] instead.
+#[derive(Clone)]
+pub struct TnuaSimulationSnapshot {
+    pub controller: TnuaController,
+    pub motor: TnuaMotor,
+    pub rigid_body_tracker: TnuaRigidBodyTracker,
+    pub proximity_sensor: TnuaProximitySensor,
+}
+
+impl TnuaSimulationSnapshot {
+    /// Capture the simulation state of a character entity.
+    ///
+    /// Returns `None` if the entity does not have a [`TnuaController`] (or the components it
+    /// requires).
+    pub fn capture(world: &World, entity: Entity) -> Option<Self> {
+        let entity = world.get_entity(entity).ok()?;
+        Some(Self {
+            controller: entity.get::<TnuaController>()?.clone(),
+            motor: entity.get::<TnuaMotor>()?.clone(),
+            rigid_body_tracker: entity.get::<TnuaRigidBodyTracker>()?.clone(),
+            proximity_sensor: entity.get::<TnuaProximitySensor>()?.clone(),
+        })
+    }
+}
+
+impl TnuaRestorableSimulation for TnuaSimulationSnapshot {
+    fn restore(&self, world: &mut World, entity: Entity) {
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        entity.insert((
+            self.controller.clone(),
+            self.motor.clone(),
+            self.rigid_body_tracker.clone(),
+            self.proximity_sensor.clone(),
+        ));
+    }
+}
+
+macro_rules! simulated_builtins {
+    (
+        $(#[$attr:meta])*
+        $enum_name:ident, $trait:ident, $concrete:ident, $restore:ident,
+        { $($variant:ident($builtin:ty)),* $(,)? }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum $enum_name {
+            $($variant($builtin, <$builtin as $trait>::State),)*
+        }
+
+        impl $enum_name {
+            fn capture(controller: &TnuaController) -> Option<Self> {
+                $(
+                    if let Some((input, state)) = controller.$concrete::<$builtin>() {
+                        return Some(Self::$variant(input.clone(), state.clone()));
+                    }
+                )*
+                None
+            }
+
+            fn restore(&self, controller: &mut TnuaController, name: &'static str) {
+                match self {
+                    $(
+                        Self::$variant(input, state) => {
+                            controller.$restore(name, input.clone(), state.clone())
+                        }
+                    )*
+                }
+            }
+        }
+    };
+}
+
+simulated_builtins!(
+    /// A builtin basis, together with its state, as stored in a [`TnuaSimulationState`].
+    TnuaSimulatedBasis, TnuaBasis, concrete_basis, restore_basis, {
+        Climb(TnuaBuiltinClimb),
+        Fly(TnuaBuiltinFly),
+        Skate(TnuaBuiltinSkate),
+        Swim(TnuaBuiltinSwim),
+        Vehicle(TnuaBuiltinVehicle),
+        Walk(TnuaBuiltinWalk),
+    }
+);
+
+simulated_builtins!(
+    /// A builtin action, together with its state, as stored in a [`TnuaSimulationState`].
+    TnuaSimulatedAction, TnuaAction, concrete_action, restore_action, {
+        Crouch(TnuaBuiltinCrouch),
+        Dash(TnuaBuiltinDash),
+        Glide(TnuaBuiltinGlide),
+        GroundPound(TnuaBuiltinGroundPound),
+        Jump(TnuaBuiltinJump),
+        Knockback(TnuaBuiltinKnockback),
+        LedgeGrab(TnuaBuiltinLedgeGrab),
+        Ski(TnuaBuiltinSki),
+        Sprint(TnuaBuiltinSprint),
+        Swing(TnuaBuiltinSwing),
+        WallJump(TnuaBuiltinWallJump),
+        WallRun(TnuaBuiltinWallRun),
+        WallSlide(TnuaBuiltinWallSlide),
+    }
+);
+
+/// The simulation state of a character, as data - so that with the `serde` feature it can be
+/// sent over the network (e.g. from the server, for reconciliation) or saved.
+///
+/// Unlike [`TnuaSimulationSnapshot`], this does not hold the whole [`TnuaController`] - only the
+/// current basis and action (with their states) and the state the controller uses for tracking the
+/// ground. The configuration of the controller (like its
+/// [profiles](TnuaController::add_profile) and
+/// [basis blend duration](TnuaController::set_basis_blend_duration)) is kept from the controller
+/// the state is restored into. Restoring it also discards:
+///
+/// * Bases that are still being blended out, carried momentum and profile crossfades - so the
+///   restored character may not move smoothly on the first frames after restoring it in the
+///   middle of such a transition.
+/// * Actions that are waiting to start (e.g. a jump pressed just before landing) and buffered
+///   actions.
+/// * Bases and actions that are not builtin, which cannot be captured. If the basis is not a
+///   builtin, it will start over (with a default state) when it is fed again.
+///
+/// Entities (e.g. the ground entity in the [`proximity_sensor`](Self::proximity_sensor)) are
+/// stored as they are, so when sending the state to another [`World`] they need to be mapped.
+///
+/// The physics backend's own components (position, velocity, etc.) are not a part of it, and
+/// should be restored separately.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaSimulationState {
+    basis: Option<(Cow<'static, str>, TnuaSimulatedBasis)>,
+    action: Option<(Cow<'static, str>, TnuaSimulatedAction)>,
+    held_actions: Vec<(Cow<'static, str>, Option<Timer>)>,
+    ground: Option<TnuaProximitySensorOutput>,
+    time_since_leaving_ground: Option<Float>,
+    relative_vertical_velocity: Option<Float>,
+    pub motor: TnuaMotor,
+    pub rigid_body_tracker: TnuaRigidBodyTracker,
+    pub proximity_sensor: TnuaProximitySensor,
+}
+
+impl TnuaSimulationState {
+    /// Capture the simulation state of a character entity.
+    ///
+    /// Returns `None` if the entity does not have a [`TnuaController`] (or the components it
+    /// requires).
+    pub fn capture(world: &World, entity: Entity) -> Option<Self> {
+        let entity = world.get_entity(entity).ok()?;
+        let controller = entity.get::<TnuaController>()?;
+        let mut held_actions = controller
+            .held_actions()
+            .map(|(name, rescheduled_in)| (Cow::Borrowed(name), rescheduled_in.cloned()))
+            .collect::<Vec<_>>();
+        held_actions.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Some(Self {
+            basis: controller.basis_name().and_then(|name| {
+                Some((
+                    Cow::Borrowed(name),
+                    TnuaSimulatedBasis::capture(controller)?,
+                ))
+            }),
+            action: controller.action_name().and_then(|name| {
+                Some((
+                    Cow::Borrowed(name),
+                    TnuaSimulatedAction::capture(controller)?,
+                ))
+            }),
+            held_actions,
+            ground: controller.ground().cloned(),
+            time_since_leaving_ground: controller.time_since_leaving_ground(),
+            relative_vertical_velocity: controller.relative_vertical_velocity().ok(),
+            motor: entity.get::<TnuaMotor>()?.clone(),
+            rigid_body_tracker: entity.get::<TnuaRigidBodyTracker>()?.clone(),
+            proximity_sensor: entity.get::<TnuaProximitySensor>()?.clone(),
+        })
+    }
+}
+
+impl TnuaRestorableSimulation for TnuaSimulationState {
+    fn restore(&self, world: &mut World, entity: Entity) {
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        let Some(mut controller) = entity.get_mut::<TnuaController>() else {
+            return;
+        };
+        controller.reset_for_restore(
+            self.held_actions
+                .iter()
+                .map(|(name, rescheduled_in)| (static_name(name.clone()), rescheduled_in.clone())),
+            self.ground.clone(),
+            self.time_since_leaving_ground,
+            self.relative_vertical_velocity,
+        );
+        if let Some((name, basis)) = &self.basis {
+            basis.restore(controller.as_mut(), static_name(name.clone()));
+        }
+        if let Some((name, action)) = &self.action {
+            action.restore(controller.as_mut(), static_name(name.clone()));
+        }
+        entity.insert((
+            self.motor.clone(),
+            self.rigid_body_tracker.clone(),
+            self.proximity_sensor.clone(),
+        ));
+    }
+}
+
+/// Restore a character from a corrected snapshot (either a [`TnuaSimulationSnapshot`] or a
+/// [`TnuaSimulationState`]), and re-simulate it with the inputs that were
+/// fed to it since.
+///
+/// `run_tick` is called once for each frame of `inputs`, and should run a single tick of the
+/// simulation - usually `|world| world.run_schedule(FixedUpdate)` (note that this runs the tick
+/// for all the entities in the world, not just for the reconciled character). During these ticks
+/// the inputs are fed with a [`TnuaInputReplayer`], so this requires the
+/// [`TnuaInputRecordingPlugin`](super::TnuaInputRecordingPlugin) and the character's controls
+/// system should skip characters that have a [`TnuaInputReplayer`].
+///
+/// The simulation is only reproduced if the inputs were recorded (with
+/// [`TnuaInputRecorder`](super::TnuaInputRecorder)) and are replayed in a fixed timestep schedule.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::{
+/// #     tnua_resimulate, TnuaInputRecording, TnuaSimulationSnapshot,
+/// # };
+/// # let mut world = World::new();
+/// # let character = Entity::PLACEHOLDER;
+/// # let (corrected_snapshot, inputs_since): (TnuaSimulationSnapshot, TnuaInputRecording) =
+/// #     unimplemented!();
+/// // Restore the physics backend's components with the server's values, and then:
+/// tnua_resimulate(
+///     &mut world,
+///     character,
+///     &corrected_snapshot,
+///     &inputs_since,
+///     |world| world.run_schedule(FixedUpdate),
+/// );
+/// ```
+pub fn tnua_resimulate(
+    world: &mut World,
+    entity: Entity,
+    snapshot: &impl TnuaRestorableSimulation,
+    inputs: &TnuaInputRecording,
+    mut run_tick: impl FnMut(&mut World),
+) {
+    snapshot.restore(world, entity);
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let previous_replayer = entity_mut.take::<TnuaInputReplayer>();
+    entity_mut.insert(TnuaInputReplayer::new(inputs.clone()));
+    for _ in 0..inputs.len() {
+        run_tick(world);
+    }
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if let Some(previous_replayer) = previous_replayer {
+        entity_mut.insert(previous_replayer);
+    } else {
+        entity_mut.remove::<TnuaInputReplayer>();
+    }
+}
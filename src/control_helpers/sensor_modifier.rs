@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use bevy::ecs::system::EntityCommands;
+
+/// The function a control helper calls with the command that creates its sensor entity.
+///
+/// Defaults to a function that does nothing, so that the components holding it can be created
+/// through reflection - e.g. when spawning them from a scene.
+#[derive(Clone)]
+pub(crate) struct SensorModifier(Arc<dyn Send + Sync + Fn(&mut EntityCommands)>);
+
+impl SensorModifier {
+    pub(crate) fn new(modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands)) -> Self {
+        Self(Arc::new(modify_sensor))
+    }
+
+    pub(crate) fn apply(&self, cmd: &mut EntityCommands) {
+        (self.0)(cmd);
+    }
+}
+
+impl Default for SensorModifier {
+    fn default() -> Self {
+        Self::new(|_| {})
+    }
+}
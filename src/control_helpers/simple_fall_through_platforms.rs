@@ -13,8 +13,11 @@ use crate::{TnuaGhostSensor, TnuaProximitySensor};
 /// [`TnuaUserControlsSystemSet`](crate::TnuaUserControlsSystemSet) (typically the player controls
 /// system) use [`with`](Self::with) and call one of the methods of [the returned handle
 /// object](TnuaHandleForSimpleFallThroughPlatformsHelper) every frame. See the description of
-/// these methods to determine which one to call.
-#[derive(Component, Default)]
+/// these methods to determine which one to call - or use
+/// [`apply_scheme`](TnuaHandleForSimpleFallThroughPlatformsHelper::apply_scheme) with a
+/// [`TnuaFallThroughControlScheme`] to let it decide.
+#[derive(Component, Clone, Default, Reflect)]
+#[reflect(Component)]
 pub struct TnuaSimpleFallThroughPlatformsHelper {
     currently_falling_through: HashSet<Entity>,
 }
@@ -84,6 +87,43 @@ impl TnuaHandleForSimpleFallThroughPlatformsHelper<'_> {
     /// Returns `true` if actually dropping through a platform, to help determining if the
     /// character should be crouching (since these buttons are usually the same)
     pub fn try_falling(&mut self, just_pressed: bool) -> bool {
+        self.try_falling_through_at_most(just_pressed, None)
+    }
+
+    /// Operate the helper according to a [`TnuaFallThroughControlScheme`].
+    ///
+    /// `fall_pressed` and `fall_just_pressed` are the state of the button the player uses for
+    /// falling through platforms (usually the crouch button)
+    ///
+    /// Returns `true` if actually dropping through a platform, to help determining if the
+    /// character should be crouching (since these buttons are usually the same)
+    pub fn apply_scheme(
+        &mut self,
+        scheme: &TnuaFallThroughControlScheme,
+        fall_pressed: bool,
+        fall_just_pressed: bool,
+    ) -> bool {
+        if !fall_pressed {
+            self.dont_fall();
+            return false;
+        }
+        match scheme {
+            TnuaFallThroughControlScheme::JumpThroughOnly => {
+                self.dont_fall();
+                false
+            }
+            TnuaFallThroughControlScheme::SingleFall { max_platforms } => {
+                self.try_falling_through_at_most(fall_just_pressed, *max_platforms)
+            }
+            TnuaFallThroughControlScheme::KeepFalling => self.try_falling(true),
+        }
+    }
+
+    fn try_falling_through_at_most(
+        &mut self,
+        just_pressed: bool,
+        max_platforms: Option<usize>,
+    ) -> bool {
         if !just_pressed && !self.parent.currently_falling_through.is_empty() {
             for ghost_platform in self.ghost_sensor.iter() {
                 if self.min_proximity <= ghost_platform.proximity
@@ -99,13 +139,63 @@ impl TnuaHandleForSimpleFallThroughPlatformsHelper<'_> {
             return true;
         }
         self.parent.currently_falling_through.clear();
-        for ghost_platform in self.ghost_sensor.iter() {
-            if self.min_proximity <= ghost_platform.proximity {
-                self.parent
-                    .currently_falling_through
-                    .insert(ghost_platform.entity);
+        for ghost_platform in self
+            .ghost_sensor
+            .iter()
+            .filter(|ghost_platform| self.min_proximity <= ghost_platform.proximity)
+            .take(max_platforms.unwrap_or(usize::MAX))
+        {
+            self.parent
+                .currently_falling_through
+                .insert(ghost_platform.entity);
+        }
+        if let Some(max_platforms) = max_platforms {
+            // The platforms beyond the limit should be stood on - just like platforms
+            // discovered after the fall has started.
+            if let Some(ghost_platform) = self
+                .ghost_sensor
+                .iter()
+                .filter(|ghost_platform| self.min_proximity <= ghost_platform.proximity)
+                .nth(max_platforms)
+            {
+                self.proximity_sensor.output = Some(ghost_platform.clone());
             }
         }
         !self.parent.currently_falling_through.is_empty()
     }
 }
+
+/// A configurable way for the player to control falling through platforms with
+/// [`TnuaSimpleFallThroughPlatformsHelper`].
+///
+/// Can be placed as a component on the character entity, and passed to
+/// [`apply_scheme`](TnuaHandleForSimpleFallThroughPlatformsHelper::apply_scheme) together with the
+/// state of the fall-through button.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaFallThroughControlScheme {
+    /// The player cannot make the character fall through platforms - they can only be jumped
+    /// through from below.
+    JumpThroughOnly,
+    /// Pressing the button makes the character fall through the platforms it stands on. Releasing
+    /// the button will not make it climb back up, and holding it will not make it fall through
+    /// platforms further below - the button needs to be pressed again for that.
+    SingleFall {
+        /// The maximum number of overlapping platforms to fall through with a single press.
+        ///
+        /// When `None`, the character will fall through all the platforms detected by the ghost
+        /// sensor when the button was pressed.
+        max_platforms: Option<usize>,
+    },
+    /// As long as the button is held, the character will keep falling through platforms.
+    KeepFalling,
+}
+
+impl Default for TnuaFallThroughControlScheme {
+    fn default() -> Self {
+        Self::SingleFall {
+            max_platforms: None,
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Vector3};
+
+use super::sensor_modifier::SensorModifier;
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaToggle, TnuaUpdateTimer,
+};
+
+pub struct TnuaStepUpPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaStepUpPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaStepUpPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaStepUp`] work.
+impl Plugin for TnuaStepUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaStepUp>();
+        app.add_systems(
+            self.schedule,
+            update_step_up.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Makes characters walking with [`TnuaBuiltinWalk`] step up on stairs and small ledges smoothly.
+///
+/// Without this, the character only starts to rise when its proximity sensor is already above the
+/// step - which, for steps taller than the gap between the bottom of the collider and the ground,
+/// means the collider bumps into the step first and the spring has to pop the character over it.
+///
+/// This will create a child entity with a proximity sensor cast downward from a point ahead of the
+/// character, in the direction of the walk basis'
+/// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity). When that sensor detects ground that is
+/// higher than the ground under the character by no more than the maximum step height, the main
+/// proximity sensor's output is replaced with it - so the spring lifts the character onto the step
+/// before the collider reaches it.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaStepUpPlugin`].
+/// 2. Adding [`TnuaStepUp`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaStepUp {
+    /// The maximum height of a step the character can step up on.
+    pub max_step_height: Float,
+
+    /// How far ahead of the character a step can be detected. Should be a little bigger than the
+    /// distance from the center of the collider to its side.
+    pub reach: Float,
+
+    #[reflect(ignore)]
+    sensor_entity: Option<Entity>,
+    #[reflect(ignore)]
+    modify_sensor: SensorModifier,
+    #[reflect(ignore)]
+    step_height: Option<Float>,
+}
+
+impl TnuaStepUp {
+    /// Create a new step up helper, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `max_step_height` - see [`max_step_height`](Self::max_step_height).
+    /// * `reach` - see [`reach`](Self::reach).
+    /// * `modify_sensor` - a function called with the command that creates the sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        max_step_height: Float,
+        reach: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            max_step_height,
+            reach,
+            sensor_entity: None,
+            modify_sensor: SensorModifier::new(modify_sensor),
+            step_height: None,
+        }
+    }
+
+    /// The height of the step the character is stepping up on, if it's stepping up on one.
+    pub fn step_height(&self) -> Option<Float> {
+        self.step_height
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_step_up(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &TnuaController,
+        &mut TnuaStepUp,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, transform, controller, mut step_up, tnua_toggle, update_timer) in
+        query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let step_up = step_up.as_mut();
+        step_up.step_height = None;
+
+        let Ok((main_sensor, false)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let main_cast_origin = main_sensor.cast_origin;
+        let cast_direction = main_sensor.cast_direction;
+        let cast_range = main_sensor.cast_range;
+        let main_output = main_sensor.output.clone();
+
+        let up = -cast_direction.adjust_precision();
+        let step_direction = match (controller.concrete_basis::<TnuaBuiltinWalk>(), &main_output) {
+            (Some((walk, _)), Some(_)) if matches!(controller.is_airborne(), Ok(false)) => {
+                walk.desired_velocity.reject_from(up).normalize_or_zero()
+            }
+            // Only step up while walking on the ground.
+            _ => Vector3::ZERO,
+        };
+
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let rotation = rotation.adjust_precision();
+        let sensor_cast_origin =
+            main_cast_origin + rotation.inverse() * step_direction * step_up.reach;
+        let sensor_cast_range = if step_direction == Vector3::ZERO {
+            // Turn it off
+            0.0
+        } else {
+            cast_range
+        };
+
+        let ahead_output = if let Some((mut sensor, true)) = step_up
+            .sensor_entity
+            .and_then(|entity| sensors_query.get_mut(entity).ok())
+        {
+            let output = sensor.output.clone();
+            sensor.cast_origin = sensor_cast_origin;
+            sensor.cast_direction = cast_direction;
+            sensor.cast_range = sensor_cast_range;
+            output
+        } else {
+            let mut cmd = commands.spawn((
+                Transform::default(),
+                TnuaSubservientSensor { owner_entity },
+                TnuaProximitySensor {
+                    cast_origin: sensor_cast_origin,
+                    cast_direction,
+                    cast_range: sensor_cast_range,
+                    ..Default::default()
+                },
+            ));
+            cmd.set_parent(owner_entity);
+            step_up.modify_sensor.apply(&mut cmd);
+            step_up.sensor_entity = Some(cmd.id());
+            None
+        };
+
+        let (Some(main_output), Some(ahead_output)) = (main_output, ahead_output) else {
+            continue;
+        };
+        if step_direction == Vector3::ZERO {
+            // The sensor's output is from before it was turned off.
+            continue;
+        }
+
+        // Where the ground ahead would have been if it continued the ground under the character -
+        // so that walking up a slope will not be considered as stepping up.
+        let translation = translation.adjust_precision();
+        let main_origin = translation + rotation * main_cast_origin;
+        let ahead_origin = main_origin + step_direction * step_up.reach;
+        let main_hit_point =
+            main_origin + cast_direction.adjust_precision() * main_output.proximity;
+        let main_normal = main_output.normal.adjust_precision();
+        let cast_dot_normal = cast_direction.adjust_precision().dot(main_normal);
+        if cast_dot_normal.abs() < 0.001 {
+            continue;
+        }
+        let expected_proximity = (main_hit_point - ahead_origin).dot(main_normal) / cast_dot_normal;
+
+        let step_height = expected_proximity - ahead_output.proximity;
+        if step_height <= 0.0 || step_up.max_step_height < step_height {
+            continue;
+        }
+        step_up.step_height = Some(step_height);
+
+        if let Ok((mut main_sensor, false)) = sensors_query.get_mut(owner_entity) {
+            main_sensor.output = Some(TnuaProximitySensorOutput {
+                proximity: main_output.proximity - step_height,
+                ..ahead_output
+            });
+        }
+    }
+}
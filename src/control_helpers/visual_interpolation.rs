@@ -0,0 +1,110 @@
+use bevy::ecs::entity::{VisitEntities, VisitEntitiesMut};
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+pub struct TnuaVisualInterpolationPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaVisualInterpolationPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaVisualInterpolationPlugin {
+    fn default() -> Self {
+        Self::new(FixedLast)
+    }
+}
+
+/// A plugin required for making [`TnuaVisualInterpolation`] work.
+///
+/// Unlike other Tnua plugins, the schedule of this plugin is not the one the Tnua pipeline runs in,
+/// but the one where the transforms of the characters get recorded. It must be a part of the fixed
+/// loop, and run after the physics backend has updated the transforms - which is why the default
+/// is `FixedLast`.
+impl Plugin for TnuaVisualInterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaVisualInterpolation>();
+        app.add_systems(self.schedule, record_visual_interpolation_ticks);
+        app.add_systems(
+            PostUpdate,
+            apply_visual_interpolation.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Smoothly moves a render entity after a character that is simulated in `FixedUpdate`.
+///
+/// When Tnua and the physics backend run in `FixedUpdate` at a tick rate lower than the frame
+/// rate, the character will only move on the frames where a tick happened - which looks like
+/// stuttering. To avoid that, the character's visual model can be spawned as a separate entity
+/// (not as a child of the character entity!) with this component. The render entity's
+/// [`Transform`] will be interpolated between the character's transforms in the last two ticks,
+/// according to how much time has passed since the last tick.
+///
+/// Note that this means the render entity will lag one tick behind the actual character.
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaVisualInterpolationPlugin`].
+/// 2. Adding [`TnuaVisualInterpolation`] as a component to the render entity.
+#[derive(Component, Clone, Reflect, VisitEntities, VisitEntitiesMut)]
+#[reflect(Component, MapEntities)]
+pub struct TnuaVisualInterpolation {
+    /// The character entity to follow.
+    ///
+    /// Its [`Transform`] is used, so it should not be a child of a moving entity.
+    pub source: Entity,
+    #[reflect(ignore)]
+    #[visit_entities(ignore)]
+    previous: Option<Transform>,
+    #[reflect(ignore)]
+    #[visit_entities(ignore)]
+    current: Option<Transform>,
+}
+
+impl TnuaVisualInterpolation {
+    /// Create a new visual interpolation that follows the `source` entity.
+    pub fn new(source: Entity) -> Self {
+        Self {
+            source,
+            previous: None,
+            current: None,
+        }
+    }
+}
+
+fn record_visual_interpolation_ticks(
+    mut query: Query<&mut TnuaVisualInterpolation>,
+    sources_query: Query<&Transform>,
+) {
+    for mut interpolation in query.iter_mut() {
+        let Ok(source_transform) = sources_query.get(interpolation.source) else {
+            continue;
+        };
+        interpolation.previous = interpolation.current.or(Some(*source_transform));
+        interpolation.current = Some(*source_transform);
+    }
+}
+
+fn apply_visual_interpolation(
+    time: Res<Time<Fixed>>,
+    mut query: Query<(&TnuaVisualInterpolation, &mut Transform)>,
+) {
+    let fraction = time.overstep_fraction();
+    for (interpolation, mut transform) in query.iter_mut() {
+        let (Some(previous), Some(current)) = (interpolation.previous, interpolation.current)
+        else {
+            continue;
+        };
+        transform.translation = previous.translation.lerp(current.translation, fraction);
+        transform.rotation = previous.rotation.slerp(current.rotation, fraction);
+        transform.scale = previous.scale.lerp(current.scale, fraction);
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Vector3};
+
+/// The shape of a volume that affects the characters inside it, like
+/// [`TnuaForceField`](super::TnuaForceField) and [`TnuaWaterVolume`](super::TnuaWaterVolume).
+///
+/// The shape is positioned and rotated by the [`GlobalTransform`] of the entity it is added to.
+/// Tnua only checks if the character's center is inside the shape, so it does not depend on the
+/// physics backend.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum TnuaVolumeShape {
+    /// A box, centered on the entity's position.
+    Cuboid { half_extents: Vector3 },
+    /// A ball, centered on the entity's position.
+    Sphere { radius: Float },
+}
+
+impl TnuaVolumeShape {
+    /// Check if a point (in world space) is inside the shape.
+    pub fn contains(&self, transform: &GlobalTransform, point: Vector3) -> bool {
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let local_point =
+            rotation.adjust_precision().inverse() * (point - translation.adjust_precision());
+        match self {
+            Self::Cuboid { half_extents } => local_point.abs().cmple(*half_extents).all(),
+            Self::Sphere { radius } => local_point.length() <= *radius,
+        }
+    }
+
+    /// The height, along the `up` direction, of the highest point of the shape.
+    pub fn top_height(&self, transform: &GlobalTransform, up: Vector3) -> Float {
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let center_height = translation.adjust_precision().dot(up);
+        match self {
+            Self::Cuboid { half_extents } => {
+                let local_up = rotation.adjust_precision().inverse() * up;
+                center_height + local_up.abs().dot(*half_extents)
+            }
+            Self::Sphere { radius } => center_height + radius,
+        }
+    }
+}
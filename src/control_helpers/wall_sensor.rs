@@ -0,0 +1,257 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{
+    float_consts, AdjustPrecision, AsF32, Float, Quaternion, Vector3,
+};
+
+use super::sensor_modifier::SensorModifier;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaToggle, TnuaUpdateTimer,
+};
+
+pub struct TnuaWallSensorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaWallSensorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaWallSensorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaWallSensor`] work.
+impl Plugin for TnuaWallSensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaWallSensor>();
+        app.register_type::<TnuaWallSensorOutput>();
+        app.add_systems(
+            self.schedule,
+            update_wall_sensors.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Detects walls next to the character, for actions like
+/// [`TnuaBuiltinWallJump`](crate::builtins::TnuaBuiltinWallJump).
+///
+/// This will create a child entity with a proximity sensor for each of the configured directions.
+/// The physics backend updates these sensors like it updates any other proximity sensor, and
+/// `TnuaWallSensor` collects the results so that the control system can query them with
+/// [`closest_wall`](Self::closest_wall) - or, for things like choosing a "pushing against a wall"
+/// animation, check each direction with [`wall_in_direction`](Self::wall_in_direction) and
+/// [`walls`](Self::walls).
+///
+/// To let the player wall jump shortly after sliding off a wall, set
+/// [`coyote_time`](Self::coyote_time) and take the wall from [`recent_wall`](Self::recent_wall)
+/// instead of [`closest_wall`](Self::closest_wall).
+///
+/// Using it requires two things:
+///
+/// 1. Adding the plugin [`TnuaWallSensorPlugin`].
+/// 2. Adding [`TnuaWallSensor`] as a component to the character entity.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaWallSensor {
+    /// Treat the directions as being in the character's coord system, so that they rotate with the
+    /// character - e.g. for forward/back/left/right sensors.
+    pub rotate_with_character: bool,
+
+    /// A duration, in seconds, after the sensors stop detecting a wall during which
+    /// [`recent_wall`](Self::recent_wall) will still return it.
+    pub coyote_time: Float,
+
+    #[reflect(ignore)]
+    sensor_entities: Vec<Entity>,
+    directions: Vec<Dir3>,
+    cast_origin: Vector3,
+    cast_range: Float,
+    #[reflect(ignore)]
+    modify_sensor: SensorModifier,
+    #[reflect(ignore)]
+    walls: Vec<Option<TnuaWallSensorOutput>>,
+    #[reflect(ignore)]
+    last_wall: Option<TnuaWallSensorOutput>,
+    #[reflect(ignore)]
+    time_since_last_wall: Float,
+}
+
+impl TnuaWallSensor {
+    /// Create a new wall sensor, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `directions` - the directions, in world space, to cast the sensors in. For 2D games this
+    ///   would usually be `[Dir3::X, Dir3::NEG_X]`. For sensors all around the character, use
+    ///   [`radial_directions`](Self::radial_directions).
+    /// * `cast_origin` - the origin of the proximity sensors, in the character's coord system.
+    /// * `cast_range` - how far from the `cast_origin` a wall can be to get detected. Should be a
+    ///   little bigger than the distance from the `cast_origin` to the side of the collider.
+    /// * `modify_sensor` - a function called with the command that creates each sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        directions: impl IntoIterator<Item = Dir3>,
+        cast_origin: Vector3,
+        cast_range: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            rotate_with_character: false,
+            coyote_time: 0.0,
+            sensor_entities: Vec::new(),
+            directions: directions.into_iter().collect(),
+            cast_origin,
+            cast_range,
+            modify_sensor: SensorModifier::new(modify_sensor),
+            walls: Vec::new(),
+            last_wall: None,
+            time_since_last_wall: 0.0,
+        }
+    }
+
+    /// `count` directions, evenly spread on the plane perpendicular to `up`.
+    pub fn radial_directions(up: Dir3, count: usize) -> impl Iterator<Item = Dir3> {
+        let up = up.adjust_precision();
+        let (first_direction, _) = up.any_orthonormal_pair();
+        (0..count).map(move |index| {
+            #[allow(clippy::unnecessary_cast)]
+            let angle = float_consts::TAU * index as Float / count as Float;
+            let direction = Quaternion::from_axis_angle(up, angle) * first_direction;
+            Dir3::new(direction.f32()).expect("rotated unit vector should be a valid direction")
+        })
+    }
+
+    /// The closest wall detected by any of the sensors during the last update.
+    pub fn closest_wall(&self) -> Option<&TnuaWallSensorOutput> {
+        self.walls().min_by(|a, b| {
+            a.sensor_output
+                .proximity
+                .total_cmp(&b.sensor_output.proximity)
+        })
+    }
+
+    /// The closest wall detected during the last update, or - if no wall was detected - the last
+    /// wall that was detected, if it was less than [`coyote_time`](Self::coyote_time) seconds ago.
+    pub fn recent_wall(&self) -> Option<&TnuaWallSensorOutput> {
+        if let Some(closest_wall) = self.closest_wall() {
+            Some(closest_wall)
+        } else if self.time_since_last_wall < self.coyote_time {
+            self.last_wall.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// The wall detected by the sensor of the direction in the given index (of the directions
+    /// passed to [`new`](Self::new)) during the last update.
+    pub fn wall_in_direction(&self, index: usize) -> Option<&TnuaWallSensorOutput> {
+        self.walls.get(index)?.as_ref()
+    }
+
+    /// All the walls detected by the sensors during the last update.
+    pub fn walls(&self) -> impl Iterator<Item = &TnuaWallSensorOutput> {
+        self.walls.iter().flatten()
+    }
+}
+
+/// A wall detected by [`TnuaWallSensor`].
+#[derive(Debug, Clone, Reflect)]
+pub struct TnuaWallSensorOutput {
+    /// The direction of the sensor that detected the wall, in world space.
+    pub direction: Dir3,
+    /// The output of the sensor that detected the wall.
+    ///
+    /// The [`normal`](TnuaProximitySensorOutput::normal) of that output is what should usually be
+    /// passed to [`TnuaBuiltinWallJump::wall_normal`](crate::builtins::TnuaBuiltinWallJump::wall_normal).
+    pub sensor_output: TnuaProximitySensorOutput,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_wall_sensors(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut TnuaWallSensor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, transform, mut wall_sensor, tnua_toggle, update_timer) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let frame_duration = match update_timer {
+            Some(update_timer) if !update_timer.is_update_frame() => continue,
+            Some(update_timer) => update_timer.delta(),
+            None => time.delta(),
+        }
+        .as_secs_f64() as Float;
+        let wall_sensor = wall_sensor.as_mut();
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        wall_sensor.walls.clear();
+        wall_sensor.walls.resize(wall_sensor.directions.len(), None);
+        for (index, direction) in wall_sensor.directions.iter().copied().enumerate() {
+            let direction = if wall_sensor.rotate_with_character {
+                rotation * direction
+            } else {
+                direction
+            };
+            if let Some((mut sensor, true)) = wall_sensor
+                .sensor_entities
+                .get(index)
+                .and_then(|entity| sensors_query.get_mut(*entity).ok())
+            {
+                // The output is from the direction the sensor was cast at in the previous frame.
+                wall_sensor.walls[index] =
+                    sensor.output.as_ref().map(|output| TnuaWallSensorOutput {
+                        direction: sensor.cast_direction,
+                        sensor_output: output.clone(),
+                    });
+                sensor.cast_origin = wall_sensor.cast_origin;
+                sensor.cast_direction = direction;
+                sensor.cast_range = wall_sensor.cast_range;
+            } else {
+                let mut cmd = commands.spawn((
+                    Transform::default(),
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin: wall_sensor.cast_origin,
+                        cast_direction: direction,
+                        cast_range: wall_sensor.cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                wall_sensor.modify_sensor.apply(&mut cmd);
+                let sensor_entity = cmd.id();
+                if index < wall_sensor.sensor_entities.len() {
+                    wall_sensor.sensor_entities[index] = sensor_entity;
+                } else {
+                    wall_sensor.sensor_entities.push(sensor_entity);
+                }
+            }
+        }
+
+        if let Some(closest_wall) = wall_sensor.closest_wall() {
+            wall_sensor.last_wall = Some(closest_wall.clone());
+            wall_sensor.time_since_last_wall = 0.0;
+        } else {
+            wall_sensor.time_since_last_wall += frame_duration;
+        }
+    }
+}
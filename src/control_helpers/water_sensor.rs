@@ -0,0 +1,215 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Vector3};
+
+use super::TnuaVolumeShape;
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaToggle, TnuaUpdateTimer};
+
+pub struct TnuaWaterSensorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaWaterSensorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaWaterSensorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaWaterSensor`] work.
+impl Plugin for TnuaWaterSensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaWaterSensor>();
+        app.register_type::<TnuaWaterVolume>();
+        app.add_event::<TnuaWaterEvent>();
+        app.add_systems(
+            self.schedule,
+            update_water_sensors.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Marks a volume of water, to be detected by [`TnuaWaterSensor`].
+///
+/// The water surface is the top of the [`shape`](Self::shape), so the shape should usually be an
+/// upright box.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaWaterVolume {
+    /// The volume, relative to the entity's transform, that contains the water.
+    pub shape: TnuaVolumeShape,
+}
+
+/// Detects when the character enters or leaves a [`TnuaWaterVolume`].
+///
+/// The sensor checks if a point ([`offset`](Self::offset) from the character's center) is inside
+/// a water volume, and sends a [`TnuaWaterEvent`] when this changes. The controls system can use
+/// [`surface_height`](Self::surface_height) to decide whether to feed
+/// [`TnuaBuiltinSwim`](crate::builtins::TnuaBuiltinSwim) (with the surface height as its
+/// [`water_surface_height`](crate::builtins::TnuaBuiltinSwim::water_surface_height)) or the
+/// walk basis:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::TnuaBuiltinSwim;
+/// # use bevy_tnua::control_helpers::TnuaWaterSensor;
+/// # use bevy_tnua::math::Vector3;
+/// # let mut controller = TnuaController::default();
+/// # let water_sensor = TnuaWaterSensor::default();
+/// # let direction = Vector3::ZERO;
+/// if let Some(water_surface_height) = water_sensor.surface_height() {
+///     controller.basis(TnuaBuiltinSwim {
+///         desired_velocity: direction * 5.0,
+///         water_surface_height,
+///         ..Default::default()
+///     });
+/// } else {
+///     controller.basis(TnuaBuiltinWalk {
+///         desired_velocity: direction * 10.0,
+///         float_height: 1.5,
+///         ..Default::default()
+///     });
+/// }
+/// ```
+///
+/// Using it requires three things:
+///
+/// 1. Adding the plugin [`TnuaWaterSensorPlugin`].
+/// 2. Adding [`TnuaWaterVolume`] as a component to the water entities.
+/// 3. Adding [`TnuaWaterSensor`] as a component to the character entity.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct TnuaWaterSensor {
+    /// The point that needs to be in the water for the character to be considered in it, relative
+    /// to the character's center and in the character's coord system.
+    ///
+    /// Its height above the character's center should be lower than the
+    /// [`float_depth`](crate::builtins::TnuaBuiltinSwim::float_depth) of the swim basis, so that
+    /// the point will remain in the water while the character floats at the surface.
+    pub offset: Vector3,
+
+    #[reflect(ignore)]
+    water: Option<TnuaWaterSensorOutput>,
+}
+
+/// The water detected by a [`TnuaWaterSensor`].
+#[derive(Debug, Clone)]
+pub struct TnuaWaterSensorOutput {
+    /// The [`TnuaWaterVolume`] entity.
+    pub entity: Entity,
+
+    /// The height of the water surface, along the up direction, in world coordinates.
+    pub surface_height: Float,
+}
+
+impl TnuaWaterSensor {
+    /// Create a new water sensor, to be added as a component to the character entity.
+    ///
+    /// See [`offset`](Self::offset).
+    pub fn new(offset: Vector3) -> Self {
+        Self {
+            offset,
+            water: None,
+        }
+    }
+
+    /// The water the character is in, if it is in water.
+    pub fn water(&self) -> Option<&TnuaWaterSensorOutput> {
+        self.water.as_ref()
+    }
+
+    /// The height of the surface of the water the character is in, if it is in water.
+    pub fn surface_height(&self) -> Option<Float> {
+        Some(self.water.as_ref()?.surface_height)
+    }
+}
+
+/// Sent by [`TnuaWaterSensorPlugin`] when a character enters or leaves a [`TnuaWaterVolume`].
+#[derive(Event, Debug, Clone)]
+pub enum TnuaWaterEvent {
+    /// The character has entered the water.
+    Entered {
+        /// The character entity.
+        entity: Entity,
+        /// The [`TnuaWaterVolume`] entity.
+        water_entity: Entity,
+    },
+    /// The character has left the water.
+    Left {
+        /// The character entity.
+        entity: Entity,
+        /// The [`TnuaWaterVolume`] entity.
+        water_entity: Entity,
+    },
+}
+
+#[allow(clippy::type_complexity)]
+fn update_water_sensors(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &TnuaProximitySensor,
+        &mut TnuaWaterSensor,
+        Option<&TnuaToggle>,
+        Option<&TnuaUpdateTimer>,
+    )>,
+    water_query: Query<(Entity, &TnuaWaterVolume, &GlobalTransform)>,
+    mut writer: EventWriter<TnuaWaterEvent>,
+) {
+    for (entity, transform, proximity_sensor, mut water_sensor, tnua_toggle, update_timer) in
+        query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if !update_timer.is_none_or(TnuaUpdateTimer::is_update_frame) {
+            continue;
+        }
+        let up = -proximity_sensor.cast_direction.adjust_precision();
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let point =
+            translation.adjust_precision() + rotation.adjust_precision() * water_sensor.offset;
+
+        // With overlapping volumes, the highest surface is the one the character floats at.
+        let water = water_query
+            .iter()
+            .filter(|(_, water_volume, water_transform)| {
+                water_volume.shape.contains(water_transform, point)
+            })
+            .map(
+                |(water_entity, water_volume, water_transform)| TnuaWaterSensorOutput {
+                    entity: water_entity,
+                    surface_height: water_volume.shape.top_height(water_transform, up),
+                },
+            )
+            .max_by(|a, b| a.surface_height.total_cmp(&b.surface_height));
+
+        let old_entity = water_sensor.water.as_ref().map(|water| water.entity);
+        let new_entity = water.as_ref().map(|water| water.entity);
+        if old_entity != new_entity {
+            if let Some(water_entity) = old_entity {
+                writer.send(TnuaWaterEvent::Left {
+                    entity,
+                    water_entity,
+                });
+            }
+            if let Some(water_entity) = new_entity {
+                writer.send(TnuaWaterEvent::Entered {
+                    entity,
+                    water_entity,
+                });
+            }
+        }
+        water_sensor.water = water;
+    }
+}
@@ -1,23 +1,37 @@
+use std::any::{Any, TypeId};
+use std::sync::Arc;
+
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
-use bevy::utils::{Entry, HashMap};
-use bevy_tnua_physics_integration_layer::math::{AsF32, Float};
+use bevy::utils::{Entry, HashMap, Parallel};
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
 
 use crate::basis_action_traits::{
     BoxableAction, BoxableBasis, DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext,
     TnuaActionInitiationDirective, TnuaActionLifecycleDirective, TnuaActionLifecycleStatus,
-    TnuaBasisContext,
+    TnuaBasisContext, TnuaMomentumPolicy, TnuaStaminaCost,
+};
+use crate::control_helpers::{
+    TnuaFallThroughControlScheme, TnuaMultiJumpHelper, TnuaSimpleAirActionsCounter,
+    TnuaSimpleFallThroughPlatformsHelper,
 };
+use crate::subservient_sensors::TnuaSubservientSensor;
 use crate::{
-    TnuaBasis, TnuaMotor, TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker,
-    TnuaSystemSet, TnuaToggle, TnuaUserControlsSystemSet,
+    TnuaBasis, TnuaGhostPlatform, TnuaGhostSensor, TnuaMotor, TnuaPipelineStages,
+    TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker, TnuaSensorFilter,
+    TnuaSleepState, TnuaSurfaceUserData, TnuaSystemSet, TnuaToggle, TnuaUpdateRate,
+    TnuaUpdateTimer, TnuaUserControlsSystemSet, TnuaVelChange,
 };
 
 /// The main for supporting Tnua character controller.
 ///
 /// Will not work without a physics backend plugin (like `TnuaRapier2dPlugin` or
 /// `TnuaRapier3dPlugin`)
+///
+/// By default the [`TnuaSystemSet`] runs in `Update`. Use [`new`](Self::new) to place it in any
+/// other schedule (e.g. `FixedUpdate`) - in which case the physics backend plugin must be
+/// registered in the same schedule.
 pub struct TnuaControllerPlugin {
     schedule: InternedScheduleLabel,
 }
@@ -50,18 +64,588 @@ impl Plugin for TnuaControllerPlugin {
                 .chain()
                 .in_set(TnuaSystemSet),
         );
+        app.add_event::<TnuaControllerEvent>();
+        app.register_type::<TnuaToggle>();
+        app.register_type::<TnuaSleepState>();
+        app.register_type::<TnuaUpdateRate>();
+        app.register_type::<TnuaUpdateTimer>();
+        app.register_type::<TnuaRigidBodyTracker>();
+        app.register_type::<TnuaProximitySensor>();
+        app.register_type::<TnuaMotor>();
+        app.register_type::<TnuaGhostSensor>();
+        app.register_type::<TnuaGhostPlatform>();
+        app.register_type::<TnuaSensorFilter>();
+        app.register_type::<TnuaSurfaceUserData>();
+        app.register_type::<TnuaSubservientSensor>();
+        app.register_type::<TnuaController>();
+        app.register_type::<TnuaUpDirection>();
+        app.register_type::<TnuaSurfaceTraction>();
+        app.register_type::<TnuaConveyor>();
+        app.register_type::<TnuaExternalAcceleration>();
+        app.register_type::<TnuaStamina>();
+        app.register_type::<TnuaMomentumPolicy>();
+        app.register_type::<TnuaMultiJumpHelper>();
+        app.register_type::<TnuaSimpleAirActionsCounter>();
+        app.register_type::<TnuaSimpleFallThroughPlatformsHelper>();
+        app.register_type::<TnuaFallThroughControlScheme>();
+        crate::builtins::register_types(app);
         app.add_systems(
             self.schedule,
             apply_controller_system.in_set(TnuaPipelineStages::Logic),
         );
+        app.add_systems(
+            self.schedule,
+            advance_update_timers_system
+                .in_set(TnuaSystemSet)
+                .before(TnuaPipelineStages::Sensors),
+        );
+    }
+}
+
+/// Sent by [`TnuaControllerPlugin`] when the status of a character controlled by
+/// [`TnuaController`] changes.
+///
+/// Useful for things like sounds, particles and camera shakes, which would otherwise need to poll
+/// the controller every frame.
+///
+/// The events of each frame are ordered by the [character entity](Self::entity), and the events of
+/// each character are in the order they have happened - so the order is deterministic, even though
+/// the characters are processed in parallel.
+#[derive(Event, Debug, Clone)]
+pub enum TnuaControllerEvent {
+    /// The character has landed on the ground.
+    Landed {
+        /// The character entity.
+        entity: Entity,
+        /// The speed in which the character was falling, relative to the ground, right before it
+        /// landed.
+        impact_speed: Float,
+        /// The entity the character landed on.
+        ground_entity: Entity,
+    },
+    /// The character is no longer standing on the ground - either because it jumped or because
+    /// it walked off a ledge.
+    ///
+    /// Note that this is sent when the coyote time starts, not when it ends.
+    LeftGround {
+        /// The character entity.
+        entity: Entity,
+    },
+    /// The character has started a [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump).
+    ///
+    /// This is sent in addition to the [`ActionStarted`](Self::ActionStarted) event.
+    Jumped {
+        /// The character entity.
+        entity: Entity,
+        /// The name of the jump action - which may be different than
+        /// [`TnuaBuiltinJump::NAME`](TnuaAction::NAME) if it was fed with
+        /// [`named_action`](TnuaController::named_action).
+        action_name: &'static str,
+    },
+    /// An action has started.
+    ActionStarted {
+        /// The character entity.
+        entity: Entity,
+        /// The name of the action.
+        action_name: &'static str,
+    },
+    /// An action has ended - either because it has finished or because it was cancelled into
+    /// another action.
+    ActionEnded {
+        /// The character entity.
+        entity: Entity,
+        /// The name of the action.
+        action_name: &'static str,
+    },
+    /// The character's [`TnuaStamina`] has run out.
+    StaminaExhausted {
+        /// The character entity.
+        entity: Entity,
+    },
+    /// The character's [`TnuaStamina`] has regenerated up to its
+    /// [`recovery_threshold`](TnuaStamina::recovery_threshold) after being exhausted, so actions
+    /// that cost stamina can start again.
+    StaminaRecovered {
+        /// The character entity.
+        entity: Entity,
+    },
+}
+
+impl TnuaControllerEvent {
+    /// The character entity the event was sent for.
+    pub fn entity(&self) -> Entity {
+        match self {
+            Self::Landed { entity, .. }
+            | Self::LeftGround { entity }
+            | Self::Jumped { entity, .. }
+            | Self::ActionStarted { entity, .. }
+            | Self::ActionEnded { entity, .. }
+            | Self::StaminaExhausted { entity }
+            | Self::StaminaRecovered { entity } => *entity,
+        }
+    }
+}
+
+/// Overrides the direction a character controlled by [`TnuaController`] considers as "up".
+///
+/// By default, the up direction is the opposite of the gravity the physics backend applies to the
+/// character. With this component, it can be set to any direction - e.g. for walking on walls or
+/// ceilings. The basis, the actions, the ground proximity sensor and the tilt correction all use
+/// this direction.
+///
+/// Note that this does not change the gravity itself. The basis compensates for the full gravity
+/// while the character stands on the ground, but in the air the character will still fall in the
+/// direction of the gravity, so games that want the character to fall "down" according to this
+/// direction should also change the gravity the physics backend applies to it.
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct TnuaUpDirection(pub Dir3);
+
+/// Modifies the traction of characters that stand on the entity it is added to - e.g. for mud,
+/// sand or gravel.
+///
+/// [`TnuaController`] reads it from the ground entity detected by the character's proximity
+/// sensor, and passes it to the basis in
+/// [`TnuaBasisContext::surface_traction`](crate::TnuaBasisContext::surface_traction). The
+/// [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk) basis multiplies its ground
+/// acceleration by these factors, and its tilt correction by the deceleration factor.
+///
+/// For ice and other slippery surfaces, use [`TnuaSurfaceTraction::slippery`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TnuaSurfaceTraction {
+    /// Multiplies the acceleration used for speeding up or for changing direction.
+    pub acceleration_factor: Float,
+
+    /// Multiplies the acceleration used for slowing down.
+    pub deceleration_factor: Float,
+
+    /// Let the character slide down sloped ground.
+    ///
+    /// Normally the basis compensates for all the gravity while the character stands on the
+    /// ground, so it does not move when standing still on a slope. When this is set, only the
+    /// part of the gravity that pushes the character into the ground is compensated, and the rest
+    /// makes it slide down the slope. The velocity gained from sliding is not braked by the walk
+    /// while the character keeps sliding or is in the air - only once it reaches flat ground or a
+    /// surface that does not slide.
+    pub slide_down_slopes: bool,
+}
+
+impl Default for TnuaSurfaceTraction {
+    fn default() -> Self {
+        Self {
+            acceleration_factor: 1.0,
+            deceleration_factor: 1.0,
+            slide_down_slopes: false,
+        }
+    }
+}
+
+/// A continuous acceleration applied to a character by something other than its own movement -
+/// e.g. wind.
+///
+/// [`TnuaController`] adds it to the motor after the basis and the action, and passes it to them
+/// in [`TnuaBasisContext::external_acceleration`](crate::TnuaBasisContext::external_acceleration)
+/// so that they will not try to correct the velocity it causes. It can be set manually, or by
+/// [`TnuaForceField`](crate::control_helpers::TnuaForceField)s.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TnuaExternalAcceleration(pub Vector3);
+
+/// Makes the entity it is added to carry the characters that stand on it as if it was moving with
+/// the specified velocity - e.g. for conveyor belts - even if the entity itself is static.
+///
+/// [`TnuaController`] adds the velocity to the
+/// [`entity_linvel`](crate::TnuaProximitySensorOutput::entity_linvel) of the ground detected by
+/// the character's proximity sensor (in the copy it passes to the basis and the actions - the
+/// [`TnuaProximitySensor`] component itself is not modified), so the basis treats it like the
+/// velocity of a moving platform. The conveyor is also passed to them in
+/// [`TnuaBasisContext::ground_conveyor`](crate::TnuaBasisContext::ground_conveyor).
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TnuaConveyor {
+    /// The velocity, in world space, the characters standing on the entity will move at.
+    pub velocity: Vector3,
+
+    /// Whether characters that jump off the conveyor keep its velocity.
+    ///
+    /// When `false`, [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump) cancels the part of
+    /// the conveyor's velocity that is perpendicular to the up direction when the character takes
+    /// off, so the character jumps as if it was standing on static ground.
+    pub inherit_on_jump: bool,
+}
+
+impl TnuaConveyor {
+    /// A conveyor that moves characters at the specified velocity, and lets them keep it when
+    /// they jump.
+    pub fn new(velocity: Vector3) -> Self {
+        Self {
+            velocity,
+            inherit_on_jump: true,
+        }
+    }
+}
+
+/// Stamina that [actions](TnuaAction) can cost, for a character controlled by [`TnuaController`].
+///
+/// The actions declare their costs with [`TnuaAction::stamina_cost`]. When the character has this
+/// component, the controller will:
+///
+/// * Not start an action the character cannot afford - it'll be rejected like with
+///   [`TnuaActionInitiationDirective::Reject`].
+/// * Spend the costs while the actions run, ending an action that costs stamina per second once
+///   the stamina runs out.
+/// * Regenerate the stamina once no stamina was spent for
+///   [`regeneration_delay`](Self::regeneration_delay) seconds.
+///
+/// Once the stamina runs out the character becomes exhausted, and no action that costs stamina
+/// can start until the stamina regenerates up to [`recovery_threshold`](Self::recovery_threshold).
+/// [`TnuaControllerEvent::StaminaExhausted`] and [`TnuaControllerEvent::StaminaRecovered`] are
+/// sent when this changes.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaStamina {
+    /// The maximum amount of stamina.
+    pub max: Float,
+
+    /// The current amount of stamina.
+    pub current: Float,
+
+    /// How much stamina is regenerated every second.
+    pub regeneration: Float,
+
+    /// The time, in seconds, after stamina was spent before it starts regenerating.
+    pub regeneration_delay: Float,
+
+    /// The amount of stamina that needs to be regenerated after the character was exhausted
+    /// before actions that cost stamina can start again.
+    pub recovery_threshold: Float,
+
+    time_since_spent: Float,
+    exhausted: bool,
+}
+
+impl TnuaStamina {
+    /// Create a full stamina with the specified maximum.
+    pub fn new(max: Float) -> Self {
+        Self {
+            max,
+            current: max,
+            regeneration: 0.25 * max,
+            regeneration_delay: 1.0,
+            recovery_threshold: 0.25 * max,
+            time_since_spent: Float::INFINITY,
+            exhausted: false,
+        }
+    }
+
+    /// Check if the stamina has run out and did not yet regenerate up to the
+    /// [`recovery_threshold`](Self::recovery_threshold).
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Check if an action with the specified cost can start.
+    pub fn can_afford(&self, cost: &TnuaStaminaCost) -> bool {
+        if cost.is_free() {
+            true
+        } else if self.exhausted {
+            false
+        } else {
+            cost.on_start <= self.current && (cost.per_second <= 0.0 || 0.0 < self.current)
+        }
+    }
+
+    fn update(
+        &mut self,
+        entity: Entity,
+        spent: Float,
+        frame_duration: Float,
+    ) -> Option<TnuaControllerEvent> {
+        if 0.0 < spent {
+            self.time_since_spent = 0.0;
+            self.current = (self.current - spent).max(0.0);
+            if self.current == 0.0 && !self.exhausted {
+                self.exhausted = true;
+                return Some(TnuaControllerEvent::StaminaExhausted { entity });
+            }
+            return None;
+        }
+        self.time_since_spent += frame_duration;
+        if self.regeneration_delay <= self.time_since_spent {
+            self.current = (self.current + self.regeneration * frame_duration).min(self.max);
+        }
+        if self.exhausted && self.recovery_threshold <= self.current {
+            self.exhausted = false;
+            return Some(TnuaControllerEvent::StaminaRecovered { entity });
+        }
+        None
+    }
+}
+
+impl TnuaSurfaceTraction {
+    /// Traction for slippery surfaces like ice.
+    ///
+    /// Both the acceleration and the braking are multiplied by `traction_factor` (which should be
+    /// well below `1.0`), so that the character keeps sliding when it stops walking, and it'll
+    /// slide down slopes.
+    pub fn slippery(traction_factor: Float) -> Self {
+        Self {
+            acceleration_factor: traction_factor,
+            deceleration_factor: traction_factor,
+            slide_down_slopes: true,
+        }
     }
 }
 
+#[derive(Clone)]
 struct FedEntry {
     fed_this_frame: bool,
     rescheduled_in: Option<Timer>,
 }
 
+/// A basis that was replaced by a basis of a different type, and is still being blended out.
+#[derive(Clone)]
+struct OutgoingBasis {
+    basis: Box<dyn DynamicBasis>,
+    /// The profile the outgoing basis keeps getting its configuration from, when blending out
+    /// because of a profile switch.
+    profile: Option<&'static str>,
+    motor: TnuaMotor,
+    blend_timer: Timer,
+}
+
+/// Boxes of bases and actions that are no longer in use, kept so that feeding a basis or an action
+/// of a type that was already used will reuse its allocation instead of making a new one.
+#[derive(Default)]
+struct SpareBoxes {
+    bases: HashMap<TypeId, Box<dyn DynamicBasis>>,
+    actions: HashMap<TypeId, Box<dyn DynamicAction>>,
+}
+
+impl Clone for SpareBoxes {
+    fn clone(&self) -> Self {
+        // The content of the spare boxes does not affect the controller's behavior, so there is
+        // no point in cloning them (which would allocate).
+        Self::default()
+    }
+}
+
+impl SpareBoxes {
+    fn basis<B: TnuaBasis>(&mut self, basis: B) -> Box<dyn DynamicBasis> {
+        if let Some(mut boxed) = self.bases.remove(&TypeId::of::<BoxableBasis<B>>()) {
+            *boxed
+                .as_mut_any()
+                .downcast_mut::<BoxableBasis<B>>()
+                .expect("spare bases are stored by their type") = BoxableBasis::new(basis);
+            boxed
+        } else {
+            Box::new(BoxableBasis::new(basis))
+        }
+    }
+
+    fn recycle_basis(&mut self, basis: Box<dyn DynamicBasis>) {
+        self.bases.insert(basis.as_any().type_id(), basis);
+    }
+
+    fn action<A: TnuaAction>(&mut self, action: A) -> Box<dyn DynamicAction> {
+        if let Some(mut boxed) = self.actions.remove(&TypeId::of::<BoxableAction<A>>()) {
+            *boxed
+                .as_mut_any()
+                .downcast_mut::<BoxableAction<A>>()
+                .expect("spare actions are stored by their type") = BoxableAction::new(action);
+            boxed
+        } else {
+            Box::new(BoxableAction::new(action))
+        }
+    }
+
+    fn recycle_action(&mut self, action: Box<dyn DynamicAction>) {
+        self.actions.insert(action.as_any().type_id(), action);
+    }
+}
+
+#[derive(Clone)]
+struct BufferedEntry {
+    feed: Arc<dyn Send + Sync + Fn(&mut TnuaController)>,
+    expires_in: Timer,
+}
+
+/// The horizontal velocity an action with a [`TnuaMomentumPolicy`] has left the character with.
+#[derive(Clone)]
+struct CarriedMomentum {
+    policy: TnuaMomentumPolicy,
+    velocity: Vector3,
+    elapsed: Float,
+}
+
+impl CarriedMomentum {
+    /// Prevent the basis from slowing the character down more than the policy allows.
+    ///
+    /// Returns `false` once the policy no longer has any effect.
+    fn apply(
+        &mut self,
+        frame_duration: Float,
+        tracker: &TnuaRigidBodyTracker,
+        is_airborne: bool,
+        motor: &mut TnuaMotor,
+    ) -> bool {
+        self.elapsed += frame_duration;
+        let speed = self.velocity.length();
+        let min_speed = match self.policy {
+            TnuaMomentumPolicy::Keep | TnuaMomentumPolicy::Clamp { .. } if !is_airborne => {
+                return false;
+            }
+            TnuaMomentumPolicy::Keep => speed,
+            TnuaMomentumPolicy::Decay { duration } => {
+                if duration <= self.elapsed {
+                    return false;
+                }
+                speed * (1.0 - self.elapsed / duration)
+            }
+            TnuaMomentumPolicy::Clamp { max_speed } => speed.min(max_speed),
+        };
+        let direction = self.velocity.normalize_or_zero();
+        if direction == Vector3::ZERO {
+            return false;
+        }
+
+        let boost = crate::util::calc_boost(&motor.lin, frame_duration).dot(direction);
+        if boost < 0.0 {
+            let speed_after_boost = tracker.velocity.dot(direction) + boost;
+            if speed_after_boost < min_speed {
+                motor.lin.boost += (min_speed - speed_after_boost).min(-boost) * direction;
+            }
+        }
+        true
+    }
+}
+
+/// A set of configurations for the bases and actions of a character.
+///
+/// Games often need to change how a character moves depending on its situation - e.g. make it
+/// slower when it's encumbered, or make it jump higher when it's powered up. Instead of changing
+/// the fields of the basis and the actions one by one, the different configurations can be placed
+/// in profiles which are added to the [`TnuaController`] with
+/// [`add_profile`](TnuaController::add_profile). The game code then feeds the basis and the
+/// actions with [`basis_from_profile`](TnuaController::basis_from_profile) and
+/// [`action_from_profile`](TnuaController::action_from_profile), and switches between the
+/// profiles with [`set_active_profile`](TnuaController::set_active_profile):
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::controller::TnuaMovementProfile;
+/// # use bevy_tnua::math::Vector3;
+/// # let mut controller = TnuaController::default();
+/// # let (direction, jump_pressed, is_encumbered) = (Vector3::ZERO, false, false);
+/// controller.add_profile(
+///     "normal",
+///     TnuaMovementProfile::new()
+///         .with(TnuaBuiltinWalk {
+///             float_height: 1.5,
+///             ..Default::default()
+///         })
+///         .with(TnuaBuiltinJump {
+///             height: 4.0,
+///             ..Default::default()
+///         }),
+/// );
+/// controller.add_profile(
+///     "encumbered",
+///     TnuaMovementProfile::new()
+///         .with(TnuaBuiltinWalk {
+///             float_height: 1.5,
+///             acceleration: 30.0,
+///             ..Default::default()
+///         })
+///         .with(TnuaBuiltinJump {
+///             height: 1.0,
+///             ..Default::default()
+///         }),
+/// );
+///
+/// // Every frame:
+/// controller.set_active_profile(if is_encumbered { "encumbered" } else { "normal" }, 0.5);
+/// controller.basis_from_profile(|walk: &TnuaBuiltinWalk| TnuaBuiltinWalk {
+///     desired_velocity: direction * 10.0,
+///     ..walk.clone()
+/// });
+/// if jump_pressed {
+///     controller.action_from_profile(|jump: &TnuaBuiltinJump| jump.clone());
+/// }
+/// ```
+///
+/// A profile holds at most one configuration of each type.
+#[derive(Default, Clone)]
+pub struct TnuaMovementProfile {
+    configs: HashMap<TypeId, Box<dyn ProfileConfig>>,
+}
+
+// NOTE: The blanket implementation also covers boxes (e.g. `Box<dyn DynamicBasis>`, or even
+// `Box<dyn ProfileConfig>` itself), so the methods must have names that do not clash with those of
+// `DynamicBasis` and `DynamicAction`, and must be called on the boxed value and not on the box.
+trait ProfileConfig: Send + Sync {
+    fn config_as_any(&self) -> &dyn Any;
+    fn config_as_mut_any(&mut self) -> &mut dyn Any;
+    fn clone_config_box(&self) -> Box<dyn ProfileConfig>;
+}
+
+impl<T: 'static + Send + Sync + Clone> ProfileConfig for T {
+    fn config_as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn config_as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_config_box(&self) -> Box<dyn ProfileConfig> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn ProfileConfig> {
+    fn clone(&self) -> Self {
+        self.as_ref().clone_config_box()
+    }
+}
+
+impl TnuaMovementProfile {
+    /// Create an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a configuration to the profile, replacing the existing configuration of the same type.
+    pub fn with<T: 'static + Send + Sync + Clone>(mut self, config: T) -> Self {
+        self.set(config);
+        self
+    }
+
+    /// Set a configuration in the profile, replacing the existing configuration of the same type.
+    pub fn set<T: 'static + Send + Sync + Clone>(&mut self, config: T) {
+        self.configs.insert(TypeId::of::<T>(), Box::new(config));
+    }
+
+    /// The configuration of type `T`, if the profile has one.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.configs
+            .get(&TypeId::of::<T>())?
+            .as_ref()
+            .config_as_any()
+            .downcast_ref()
+    }
+
+    /// The configuration of type `T`, if the profile has one, for modifying it.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.configs
+            .get_mut(&TypeId::of::<T>())?
+            .as_mut()
+            .config_as_mut_any()
+            .downcast_mut()
+    }
+}
+
 /// The main component used for interaction with the controls and animation code.
 ///
 /// Every frame, the game code should feed input this component on every controlled entity. What
@@ -84,14 +668,49 @@ struct FedEntry {
 ///   `TnuaAction`](crate::TnuaAction#implementors) for more information.
 ///
 /// Without [`TnuaControllerPlugin`] this component will not do anything.
-#[derive(Component, Default)]
+///
+/// Cloning the controller clones the state of its basis and actions too, so it can be stored in
+/// the snapshots of a rollback netcode (e.g. GGRS) and restored from them - together with
+/// [`TnuaMotor`], [`TnuaRigidBodyTracker`], [`TnuaProximitySensor`] and the components of any
+/// control helpers in use, which are all [`Clone`] as well. Tnua's logic only advances by the
+/// [`Time`] delta of its schedule and does not use the wall clock or any randomness, so running it
+/// in a fixed timestep schedule (e.g. `FixedUpdate`) and re-simulating from the restored
+/// components reproduces the same results.
+#[derive(Component, Default, Clone, Reflect)]
+#[reflect(Component, Default)]
 #[require(TnuaMotor, TnuaRigidBodyTracker, TnuaProximitySensor)]
 pub struct TnuaController {
+    #[reflect(ignore)]
     current_basis: Option<(&'static str, Box<dyn DynamicBasis>)>,
+    basis_blend_duration: Float,
+    #[reflect(ignore)]
+    outgoing_basis: Option<OutgoingBasis>,
+    #[reflect(ignore)]
     actions_being_fed: HashMap<&'static str, FedEntry>,
+    #[reflect(ignore)]
     current_action: Option<(&'static str, Box<dyn DynamicAction>)>,
+    #[reflect(ignore)]
     contender_action: Option<(&'static str, Box<dyn DynamicAction>, Stopwatch)>,
+    #[reflect(ignore)]
     action_flow_status: TnuaActionFlowStatus,
+    #[reflect(ignore)]
+    carried_momentum: Option<CarriedMomentum>,
+    #[reflect(ignore)]
+    buffered_actions: HashMap<&'static str, BufferedEntry>,
+    #[reflect(ignore)]
+    profiles: HashMap<&'static str, TnuaMovementProfile>,
+    #[reflect(ignore)]
+    active_profile: Option<&'static str>,
+    #[reflect(ignore)]
+    pending_profile_crossfade: Option<(&'static str, Float)>,
+    #[reflect(ignore)]
+    ground: Option<TnuaProximitySensorOutput>,
+    #[reflect(ignore)]
+    time_since_leaving_ground: Option<Float>,
+    #[reflect(ignore)]
+    relative_vertical_velocity: Option<Float>,
+    #[reflect(ignore)]
+    spare_boxes: SpareBoxes,
 }
 
 impl TnuaController {
@@ -105,6 +724,7 @@ impl TnuaController {
     /// This should only be used if the same basis type needs to be used with different names to
     /// allow, for example, different animations. Otherwise prefer to use the default name with
     /// [`basis`](Self::basis).
+    #[allow(clippy::unnecessary_cast)]
     pub fn named_basis<B: TnuaBasis>(&mut self, name: &'static str, basis: B) {
         if let Some((existing_name, existing_basis)) =
             self.current_basis.as_mut().and_then(|(n, b)| {
@@ -115,8 +735,187 @@ impl TnuaController {
             *existing_name = name;
             existing_basis.input = basis;
         } else {
-            self.current_basis = Some((name, Box::new(BoxableBasis::new(basis))));
+            let basis = self.spare_boxes.basis(basis);
+            let outgoing = match self.current_basis.replace((name, basis)) {
+                Some((_, basis)) if 0.0 < self.basis_blend_duration => Some(OutgoingBasis {
+                    basis,
+                    profile: None,
+                    motor: TnuaMotor::default(),
+                    blend_timer: Timer::from_seconds(
+                        self.basis_blend_duration as f32,
+                        TimerMode::Once,
+                    ),
+                }),
+                Some((_, basis)) => {
+                    self.spare_boxes.recycle_basis(basis);
+                    None
+                }
+                None => None,
+            };
+            self.set_outgoing_basis(outgoing);
+        }
+    }
+
+    fn set_outgoing_basis(&mut self, outgoing_basis: Option<OutgoingBasis>) {
+        if let Some(previous) = std::mem::replace(&mut self.outgoing_basis, outgoing_basis) {
+            self.spare_boxes.recycle_basis(previous.basis);
+        }
+    }
+
+    /// Set the duration, in seconds, of the transition between bases of different types.
+    ///
+    /// By default this is 0, which means that when a basis of a different type is fed (e.g. when
+    /// switching from walking to swimming) the new basis takes over immediately. With a positive
+    /// duration, the old basis will keep running (with the last input it was fed) during the
+    /// transition, and the motor will be interpolated from its output to the new basis' output -
+    /// so that the switch will not cause a sudden change in velocity.
+    ///
+    /// Note that the new basis is still considered the current basis from the moment it is fed,
+    /// so queries like [`is_airborne`](Self::is_airborne) only consider it.
+    pub fn set_basis_blend_duration(&mut self, duration: Float) {
+        self.basis_blend_duration = duration;
+    }
+
+    /// Add a [movement profile](TnuaMovementProfile), replacing the existing profile with the
+    /// same name.
+    ///
+    /// The first profile added becomes the active profile.
+    pub fn add_profile(&mut self, name: &'static str, profile: TnuaMovementProfile) {
+        self.profiles.insert(name, profile);
+        self.active_profile.get_or_insert(name);
+    }
+
+    /// The [movement profile](TnuaMovementProfile) with the specified name.
+    pub fn profile(&self, name: &str) -> Option<&TnuaMovementProfile> {
+        self.profiles.get(name)
+    }
+
+    /// The [movement profile](TnuaMovementProfile) with the specified name, for modifying it.
+    pub fn profile_mut(&mut self, name: &str) -> Option<&mut TnuaMovementProfile> {
+        self.profiles.get_mut(name)
+    }
+
+    /// The name of the [movement profile](TnuaMovementProfile) that
+    /// [`basis_from_profile`](Self::basis_from_profile) and
+    /// [`action_from_profile`](Self::action_from_profile) take the configuration from.
+    pub fn active_profile_name(&self) -> Option<&'static str> {
+        self.active_profile
+    }
+
+    /// Switch to a different [movement profile](TnuaMovementProfile).
+    ///
+    /// `crossfade` is the duration, in seconds, of the transition between the profiles. When it's
+    /// positive, the basis will keep running with the old profile's configuration alongside the
+    /// basis with the new profile's configuration, and the motor will be interpolated from the
+    /// former to the latter - similar to how
+    /// [`set_basis_blend_duration`](Self::set_basis_blend_duration) blends between different
+    /// bases. Actions are not crossfaded - they take their configuration from the new profile
+    /// immediately.
+    ///
+    /// Setting the profile that is already active does nothing, so this can be called every
+    /// frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no profile with that name was [added](Self::add_profile).
+    pub fn set_active_profile(&mut self, name: &'static str, crossfade: Float) {
+        if self.active_profile == Some(name) {
+            return;
+        }
+        if !self.profiles.contains_key(name) {
+            panic!("No movement profile named {name:?}");
+        }
+        let previous_profile = self.active_profile.replace(name);
+        self.pending_profile_crossfade = previous_profile
+            .filter(|_| 0.0 < crossfade)
+            .map(|previous_profile| (previous_profile, crossfade));
+    }
+
+    /// Feed a basis with [its default name](TnuaBasis::NAME), based on the configuration from the
+    /// [active profile](Self::set_active_profile).
+    ///
+    /// `make_basis` receives the basis configuration of the active profile and should return it
+    /// with the input fields (e.g. `desired_velocity`) set. During a profile crossfade it will also
+    /// be called with the previous profile's configuration.
+    ///
+    /// If the active profile does not have a configuration for this basis type, nothing will be
+    /// fed.
+    pub fn basis_from_profile<B: TnuaBasis>(&mut self, make_basis: impl Fn(&B) -> B) {
+        self.named_basis_from_profile(B::NAME, make_basis);
+    }
+
+    /// Feed a basis with a custom name, based on the configuration from the [active
+    /// profile](Self::set_active_profile).
+    ///
+    /// See [`basis_from_profile`](Self::basis_from_profile).
+    #[allow(clippy::unnecessary_cast)]
+    pub fn named_basis_from_profile<B: TnuaBasis>(
+        &mut self,
+        name: &'static str,
+        make_basis: impl Fn(&B) -> B,
+    ) {
+        let Some(config) = self
+            .active_profile
+            .and_then(|profile| self.profiles.get(profile)?.get::<B>())
+        else {
+            return;
+        };
+        let basis = make_basis(config);
+
+        if let Some((previous_profile, crossfade)) = self.pending_profile_crossfade.take() {
+            let same_basis_type = self
+                .current_basis
+                .as_ref()
+                .is_some_and(|(_, basis)| basis.as_any().is::<BoxableBasis<B>>());
+            let previous_config = self
+                .profiles
+                .get(previous_profile)
+                .and_then(|profile| profile.get::<B>());
+            if let (true, Some(previous_config)) = (same_basis_type, previous_config) {
+                let (current_name, current) = self
+                    .current_basis
+                    .as_mut()
+                    .expect("same_basis_type can only be true if current_basis is Some");
+                // Both the outgoing basis and the current one continue from the current state, so
+                // that the profile switch will not reset things like the coyote time.
+                let current = current
+                    .as_mut_any()
+                    .downcast_mut::<BoxableBasis<B>>()
+                    .expect("same_basis_type checked the type");
+                let mut outgoing = self.spare_boxes.basis(make_basis(previous_config));
+                outgoing
+                    .as_mut_any()
+                    .downcast_mut::<BoxableBasis<B>>()
+                    .expect("the box was just created for this type")
+                    .state = current.state.clone();
+                current.input = basis;
+                *current_name = name;
+                self.set_outgoing_basis(Some(OutgoingBasis {
+                    basis: outgoing,
+                    profile: Some(previous_profile),
+                    motor: TnuaMotor::default(),
+                    blend_timer: Timer::from_seconds(crossfade as f32, TimerMode::Once),
+                }));
+                return;
+            }
+        }
+
+        if let Some(outgoing_basis) = self.outgoing_basis.as_mut() {
+            // Keep the outgoing basis up to date with the input, so that the crossfade will only
+            // blend the configuration.
+            let previous_config = outgoing_basis
+                .profile
+                .and_then(|profile| self.profiles.get(profile)?.get::<B>());
+            let outgoing = outgoing_basis
+                .basis
+                .as_mut_any()
+                .downcast_mut::<BoxableBasis<B>>();
+            if let (Some(previous_config), Some(outgoing)) = (previous_config, outgoing) {
+                outgoing.input = make_basis(previous_config);
+            }
         }
+
+        self.named_basis(name, basis);
     }
 
     /// Instruct the basis to pretend the user provided no input this frame.
@@ -156,11 +955,83 @@ impl TnuaController {
         Some((&boxable_basis.input, &boxable_basis.state))
     }
 
-    /// Feed an action with [its default name](TnuaBasis::NAME).
+    /// Feed an action with [its default name](TnuaAction::NAME).
+    ///
+    /// Like the basis, the action needs to be fed every frame for as long as it should be active.
+    /// If an action with the same name is already running, only its input gets updated and its
+    /// state is kept. Otherwise, it becomes a candidate for starting - see
+    /// [`initiation_decision`](TnuaAction::initiation_decision).
     pub fn action<A: TnuaAction>(&mut self, action: A) {
         self.named_action(A::NAME, action);
     }
 
+    /// Keep feeding an action, with [its default name](TnuaAction::NAME), for the next
+    /// `buffer_time` seconds or until it starts - whichever comes first.
+    ///
+    /// This is meant to be called once - when the button is pressed - to let the action start
+    /// once it can. For example, if the jump button is pressed right before the character lands,
+    /// the jump will start as soon as it does. While buffered, the action will not get rejected
+    /// by its [`initiation_decision`](TnuaAction::initiation_decision) - it'll be delayed and
+    /// checked again in the next frame.
+    ///
+    /// Once the action starts, it is no longer fed by the buffer - so if it needs to be fed for
+    /// longer (e.g. for a higher jump), the game code should keep feeding it with
+    /// [`action`](Self::action) as usual.
+    ///
+    /// A negative (or NaN) `buffer_time` is treated as zero.
+    pub fn buffered_action<A: TnuaAction>(&mut self, action: A, buffer_time: Float) {
+        self.named_buffered_action(A::NAME, action, buffer_time);
+    }
+
+    /// Like [`buffered_action`](Self::buffered_action), but with a custom name.
+    ///
+    /// See [`named_action`](Self::named_action) for when a custom name should be used.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn named_buffered_action<A: TnuaAction>(
+        &mut self,
+        name: &'static str,
+        action: A,
+        buffer_time: Float,
+    ) {
+        self.buffered_actions.insert(
+            name,
+            BufferedEntry {
+                feed: Arc::new(move |controller| controller.named_action(name, action.clone())),
+                // `max` also turns NaN into zero.
+                expires_in: Timer::from_seconds(buffer_time.max(0.0) as f32, TimerMode::Once),
+            },
+        );
+    }
+
+    /// Feed an action with [its default name](TnuaAction::NAME), based on the configuration from
+    /// the [active profile](Self::set_active_profile).
+    ///
+    /// `make_action` receives the action configuration of the active profile and should return it
+    /// with the input fields set. If the active profile does not have a configuration for this
+    /// action type, nothing will be fed.
+    pub fn action_from_profile<A: TnuaAction>(&mut self, make_action: impl FnOnce(&A) -> A) {
+        self.named_action_from_profile(A::NAME, make_action);
+    }
+
+    /// Feed an action with a custom name, based on the configuration from the [active
+    /// profile](Self::set_active_profile).
+    ///
+    /// See [`action_from_profile`](Self::action_from_profile).
+    pub fn named_action_from_profile<A: TnuaAction>(
+        &mut self,
+        name: &'static str,
+        make_action: impl FnOnce(&A) -> A,
+    ) {
+        let Some(config) = self
+            .active_profile
+            .and_then(|profile| self.profiles.get(profile)?.get::<A>())
+        else {
+            return;
+        };
+        let action = make_action(config);
+        self.named_action(name, action);
+    }
+
     /// Feed an action with a custom name.
     ///
     /// This should only be used if the same action type needs to be used with different names to
@@ -193,7 +1064,7 @@ impl TnuaController {
                     // no action is running - but this action is rescheduled and there is no
                     // already-existing contender that would have taken priority
                     self.contender_action =
-                        Some((name, Box::new(BoxableAction::new(action)), Stopwatch::new()));
+                        Some((name, self.spare_boxes.action(action), Stopwatch::new()));
                 } else {
                     // no action is running - will not set because button was already pressed.
                 }
@@ -220,8 +1091,13 @@ impl TnuaController {
                 ) {
                     contender_action.input = action;
                 } else {
-                    self.contender_action =
-                        Some((name, Box::new(BoxableAction::new(action)), Stopwatch::new()));
+                    let contender_action = self.spare_boxes.action(action);
+                    if let Some((_, previous_contender, _)) =
+                        self.contender_action
+                            .replace((name, contender_action, Stopwatch::new()))
+                    {
+                        self.spare_boxes.recycle_action(previous_contender);
+                    }
                 }
             }
         }
@@ -280,6 +1156,148 @@ impl TnuaController {
             None => Err(TnuaControllerHasNoBasis),
         }
     }
+
+    /// The entity the character is standing on.
+    ///
+    /// Returns `None` if the basis does not consider the character to be standing on anything -
+    /// even if the proximity sensor detects something (e.g. during coyote time)
+    pub fn ground_entity(&self) -> Option<Entity> {
+        Some(self.ground.as_ref()?.entity)
+    }
+
+    /// The normal of the ground the character is standing on.
+    ///
+    /// Returns `None` under the same conditions as [`ground_entity`](Self::ground_entity).
+    pub fn ground_normal(&self) -> Option<Dir3> {
+        Some(self.ground.as_ref()?.normal)
+    }
+
+    /// The velocity of the character along the up direction, relative to the entity it stands
+    /// on.
+    ///
+    /// This is based on the basis' [`effective_velocity`](TnuaBasis::effective_velocity), so if
+    /// the character is not standing on anything it'll be the actual vertical velocity.
+    pub fn relative_vertical_velocity(&self) -> Result<Float, TnuaControllerHasNoBasis> {
+        self.relative_vertical_velocity
+            .ok_or(TnuaControllerHasNoBasis)
+    }
+
+    /// The time, in seconds, since the character left the ground.
+    ///
+    /// Returns `None` if the character is standing on the ground. Note that unlike
+    /// [`is_airborne`](Self::is_airborne), this includes the coyote time.
+    pub fn time_since_leaving_ground(&self) -> Option<Float> {
+        self.time_since_leaving_ground
+    }
+
+    /// Whether the controller has no action (either running, fed or buffered) and is not in the
+    /// middle of a transition between bases or profiles.
+    pub(crate) fn is_settled(&self) -> bool {
+        self.current_action.is_none()
+            && self.contender_action.is_none()
+            && self
+                .actions_being_fed
+                .values()
+                .all(|fed_entry| !fed_entry.fed_this_frame)
+            && self.buffered_actions.is_empty()
+            && self.outgoing_basis.is_none()
+            && self.carried_momentum.is_none()
+            && self.pending_profile_crossfade.is_none()
+    }
+
+    /// The names of the actions that are being held (were fed in the last frame), and the timers of
+    /// the ones that were rescheduled.
+    pub(crate) fn held_actions(&self) -> impl Iterator<Item = (&'static str, Option<&Timer>)> + '_ {
+        self.actions_being_fed
+            .iter()
+            .map(|(name, fed_entry)| (*name, fed_entry.rescheduled_in.as_ref()))
+    }
+
+    /// Discard the basis, the actions and the transitions of the controller, and set the state
+    /// that is not a part of them - for restoring a
+    /// [`TnuaSimulationState`](crate::control_helpers::TnuaSimulationState).
+    ///
+    /// The basis and the action should be restored afterwards with
+    /// [`restore_basis`](Self::restore_basis) and [`restore_action`](Self::restore_action).
+    pub(crate) fn reset_for_restore(
+        &mut self,
+        held_actions: impl IntoIterator<Item = (&'static str, Option<Timer>)>,
+        ground: Option<TnuaProximitySensorOutput>,
+        time_since_leaving_ground: Option<Float>,
+        relative_vertical_velocity: Option<Float>,
+    ) {
+        if let Some((_, basis)) = self.current_basis.take() {
+            self.spare_boxes.recycle_basis(basis);
+        }
+        self.set_outgoing_basis(None);
+        if let Some((_, action)) = self.current_action.take() {
+            self.spare_boxes.recycle_action(action);
+        }
+        if let Some((_, action, _)) = self.contender_action.take() {
+            self.spare_boxes.recycle_action(action);
+        }
+        self.action_flow_status = TnuaActionFlowStatus::NoAction;
+        self.actions_being_fed.clear();
+        self.actions_being_fed
+            .extend(held_actions.into_iter().map(|(name, rescheduled_in)| {
+                (
+                    name,
+                    FedEntry {
+                        fed_this_frame: false,
+                        rescheduled_in,
+                    },
+                )
+            }));
+        self.carried_momentum = None;
+        self.buffered_actions.clear();
+        self.pending_profile_crossfade = None;
+        self.ground = ground;
+        self.time_since_leaving_ground = time_since_leaving_ground;
+        self.relative_vertical_velocity = relative_vertical_velocity;
+    }
+
+    /// Set the current basis, together with its state, after
+    /// [`reset_for_restore`](Self::reset_for_restore).
+    pub(crate) fn restore_basis<B: TnuaBasis>(
+        &mut self,
+        name: &'static str,
+        input: B,
+        state: B::State,
+    ) {
+        let mut basis = self.spare_boxes.basis(input);
+        basis
+            .as_mut_any()
+            .downcast_mut::<BoxableBasis<B>>()
+            .expect("the box was just created for this type")
+            .state = state;
+        if let Some((_, previous)) = self.current_basis.replace((name, basis)) {
+            self.spare_boxes.recycle_basis(previous);
+        }
+    }
+
+    /// Set the current action, together with its state, after
+    /// [`reset_for_restore`](Self::reset_for_restore).
+    pub(crate) fn restore_action<A: TnuaAction>(
+        &mut self,
+        name: &'static str,
+        input: A,
+        state: A::State,
+    ) {
+        let mut action = self.spare_boxes.action(input);
+        action
+            .as_mut_any()
+            .downcast_mut::<BoxableAction<A>>()
+            .expect("the box was just created for this type")
+            .state = state;
+        if let Some((_, previous)) = self.current_action.replace((name, action)) {
+            self.spare_boxes.recycle_action(previous);
+        }
+        self.action_flow_status = TnuaActionFlowStatus::ActionOngoing(name);
+    }
+
+    pub(crate) fn ground(&self) -> Option<&TnuaProximitySensorOutput> {
+        self.ground.as_ref()
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -345,29 +1363,107 @@ impl TnuaActionFlowStatus {
     }
 }
 
+fn advance_update_timers_system(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &TnuaUpdateRate,
+        &mut TnuaUpdateTimer,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (entity, update_rate, mut update_timer, tnua_toggle) in query.iter_mut() {
+        let update_rate = match tnua_toggle.copied().unwrap_or_default() {
+            // No time passes for suspended characters, so it should not count toward their next
+            // update.
+            TnuaToggle::SuspendedKeepState => TnuaUpdateRate::Paused,
+            TnuaToggle::Disabled => *update_rate,
+            TnuaToggle::SenseOnly => *update_rate,
+            TnuaToggle::Enabled => *update_rate,
+        };
+        update_timer.advance(update_rate, time.delta(), entity.index());
+    }
+}
+
 #[allow(clippy::type_complexity)]
-fn apply_controller_system(
+pub(crate) fn apply_controller_system(
     time: Res<Time>,
     mut query: Query<(
+        Entity,
         &mut TnuaController,
         &TnuaRigidBodyTracker,
         &mut TnuaProximitySensor,
         &mut TnuaMotor,
         Option<&TnuaToggle>,
+        Option<&TnuaSleepState>,
+        Option<&TnuaUpdateTimer>,
+        Option<&TnuaUpDirection>,
+        Option<&TnuaExternalAcceleration>,
+        Option<&mut TnuaStamina>,
     )>,
+    traction_query: Query<&TnuaSurfaceTraction>,
+    conveyor_query: Query<&TnuaConveyor>,
+    mut event_writer: EventWriter<TnuaControllerEvent>,
+    // The characters are processed in parallel, so their events are collected and sent afterward.
+    mut pending_events: Local<Parallel<Vec<TnuaControllerEvent>>>,
+    mut sorted_events: Local<Vec<TnuaControllerEvent>>,
 ) {
-    let frame_duration = time.delta().as_secs_f64() as Float;
-    if frame_duration == 0.0 {
-        return;
-    }
-    for (mut controller, tracker, mut sensor, mut motor, tnua_toggle) in query.iter_mut() {
+    query.par_iter_mut().for_each(|(
+        entity,
+        mut controller,
+        tracker,
+        mut sensor,
+        mut motor,
+        tnua_toggle,
+        sleep_state,
+        update_timer,
+        up_direction_override,
+        external_acceleration,
+        mut stamina,
+    )| {
+        let mut events = pending_events.borrow_local_mut();
+
         match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled => continue,
+            TnuaToggle::Disabled | TnuaToggle::SuspendedKeepState => return,
             TnuaToggle::SenseOnly => {}
             TnuaToggle::Enabled => {}
         }
+        if sleep_state.copied().unwrap_or_default() != TnuaSleepState::Awake {
+            return;
+        }
+        // Characters that are not updated every frame need to cover all the time since their
+        // previous update.
+        let delta = match update_timer {
+            Some(update_timer) if !update_timer.is_update_frame() => return,
+            Some(update_timer) => update_timer.delta(),
+            None => time.delta(),
+        };
+        let frame_duration = delta.as_secs_f64() as Float;
+        if frame_duration == 0.0 {
+            return;
+        }
 
         let controller = controller.as_mut();
+        let action_before = controller.action_name();
+
+        let buffered_actions = std::mem::take(&mut controller.buffered_actions);
+        for (name, buffered_entry) in buffered_actions.iter() {
+            if controller.action_name() != Some(name) {
+                (buffered_entry.feed)(controller);
+            }
+        }
+        controller.buffered_actions = buffered_actions;
+
+        if let (Some(stamina), Some((name, current_action))) =
+            (&stamina, &controller.current_action)
+        {
+            if stamina.is_exhausted() && 0.0 < current_action.stamina_cost().per_second {
+                // Treat the action as no longer fed, so that it'll end gracefully.
+                if let Some(fed_entry) = controller.actions_being_fed.get_mut(name) {
+                    fed_entry.fed_this_frame = false;
+                }
+            }
+        }
 
         match controller.action_flow_status {
             TnuaActionFlowStatus::NoAction | TnuaActionFlowStatus::ActionOngoing(_) => {}
@@ -384,47 +1480,140 @@ fn apply_controller_system(
         }
 
         if let Some((_, basis)) = controller.current_basis.as_mut() {
-            let up_direction = Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y);
+            let up_direction = if let Some(TnuaUpDirection(up_direction)) = up_direction_override {
+                *up_direction
+            } else {
+                Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y)
+            };
+            let ground_conveyor = sensor
+                .output
+                .as_ref()
+                .and_then(|output| conveyor_query.get(output.entity).ok())
+                .copied();
+            // The conveyor's velocity is only added to the copy of the sensor the basis and the
+            // actions see, so that the sensor component remains as the backend wrote it.
+            let conveyor_sensor = ground_conveyor.map(|conveyor| {
+                let mut conveyor_sensor = sensor.clone();
+                if let Some(sensor_output) = conveyor_sensor.output.as_mut() {
+                    sensor_output.entity_linvel += conveyor.velocity;
+                }
+                conveyor_sensor
+            });
+            let external_acceleration = external_acceleration.copied().unwrap_or_default().0;
+            let surface_traction = sensor
+                .output
+                .as_ref()
+                .and_then(|output| traction_query.get(output.entity).ok())
+                .copied()
+                .unwrap_or_default();
             let basis = basis.as_mut();
             basis.apply(
                 TnuaBasisContext {
                     frame_duration,
                     tracker,
-                    proximity_sensor: sensor.as_ref(),
+                    proximity_sensor: conveyor_sensor.as_ref().unwrap_or(sensor.as_ref()),
                     up_direction,
+                    surface_traction,
+                    external_acceleration,
+                    ground_conveyor,
                 },
                 motor.as_mut(),
             );
-            let sensor_cast_range_for_basis = basis.proximity_sensor_cast_range();
+            if let Some(outgoing_basis) = controller.outgoing_basis.as_mut() {
+                outgoing_basis.blend_timer.tick(delta);
+                if outgoing_basis.blend_timer.finished() {
+                    if let Some(finished) = controller.outgoing_basis.take() {
+                        controller.spare_boxes.recycle_basis(finished.basis);
+                    }
+                } else {
+                    outgoing_basis.basis.apply(
+                        TnuaBasisContext {
+                            frame_duration,
+                            tracker,
+                            proximity_sensor: conveyor_sensor.as_ref().unwrap_or(sensor.as_ref()),
+                            up_direction,
+                            surface_traction,
+                            external_acceleration,
+                            ground_conveyor,
+                        },
+                        &mut outgoing_basis.motor,
+                    );
+                    #[allow(clippy::unnecessary_cast)]
+                    let blend = outgoing_basis.blend_timer.fraction() as Float;
+                    motor.lin = blend_vel_changes(&outgoing_basis.motor.lin, &motor.lin, blend);
+                    motor.ang = blend_vel_changes(&outgoing_basis.motor.ang, &motor.ang, blend);
+                }
+            }
+            if let Some(carried_momentum) = controller.carried_momentum.as_mut() {
+                if !carried_momentum.apply(
+                    frame_duration,
+                    tracker,
+                    basis.is_airborne(),
+                    motor.as_mut(),
+                ) {
+                    controller.carried_momentum = None;
+                }
+            }
+            let sensor_cast_range_for_basis =
+                if let Some(outgoing_basis) = &controller.outgoing_basis {
+                    basis
+                        .proximity_sensor_cast_range()
+                        .max(outgoing_basis.basis.proximity_sensor_cast_range())
+                } else {
+                    basis.proximity_sensor_cast_range()
+                };
 
             // To streamline TnuaActionContext creation
-            let proximity_sensor = sensor.as_ref();
+            let proximity_sensor = conveyor_sensor.as_ref().unwrap_or(sensor.as_ref());
 
-            let has_valid_contender = if let Some((_, contender_action, being_fed_for)) =
-                &mut controller.contender_action
-            {
-                let initiation_decision = contender_action.initiation_decision(
-                    TnuaActionContext {
-                        frame_duration,
-                        tracker,
-                        proximity_sensor,
-                        basis,
-                        up_direction,
-                    },
-                    being_fed_for,
-                );
-                being_fed_for.tick(time.delta());
-                match initiation_decision {
-                    TnuaActionInitiationDirective::Reject => {
-                        controller.contender_action = None;
-                        false
+            let has_valid_contender =
+                if let Some((contender_name, contender_action, being_fed_for)) =
+                    &mut controller.contender_action
+                {
+                    let initiation_decision = contender_action.initiation_decision(
+                        TnuaActionContext {
+                            frame_duration,
+                            tracker,
+                            proximity_sensor,
+                            basis,
+                            up_direction,
+                            surface_traction,
+                            external_acceleration,
+                            ground_conveyor,
+                        },
+                        being_fed_for,
+                    );
+                    being_fed_for.tick(delta);
+                    let initiation_decision = if stamina.as_ref().is_some_and(|stamina| {
+                        !stamina.can_afford(&contender_action.stamina_cost())
+                    }) {
+                        TnuaActionInitiationDirective::Reject
+                    } else {
+                        initiation_decision
+                    };
+                    let initiation_decision =
+                        if matches!(initiation_decision, TnuaActionInitiationDirective::Reject)
+                            && controller.buffered_actions.contains_key(contender_name)
+                        {
+                            TnuaActionInitiationDirective::Delay
+                        } else {
+                            initiation_decision
+                        };
+                    match initiation_decision {
+                        TnuaActionInitiationDirective::Reject => {
+                            if let Some((_, rejected_action, _)) =
+                                controller.contender_action.take()
+                            {
+                                controller.spare_boxes.recycle_action(rejected_action);
+                            }
+                            false
+                        }
+                        TnuaActionInitiationDirective::Delay => false,
+                        TnuaActionInitiationDirective::Allow => true,
                     }
-                    TnuaActionInitiationDirective::Delay => false,
-                    TnuaActionInitiationDirective::Allow => true,
-                }
-            } else {
-                false
-            };
+                } else {
+                    false
+                };
 
             if let Some((name, current_action)) = controller.current_action.as_mut() {
                 let lifecycle_status = if has_valid_contender {
@@ -447,6 +1636,9 @@ fn apply_controller_system(
                         proximity_sensor,
                         basis,
                         up_direction,
+                        surface_traction,
+                        external_acceleration,
+                        ground_conveyor,
                     },
                     lifecycle_status,
                     motor.as_mut(),
@@ -454,6 +1646,7 @@ fn apply_controller_system(
                 if current_action.violates_coyote_time() {
                     basis.violate_coyote_time();
                 }
+                let momentum_policy = current_action.momentum_policy();
                 let reschedule_action =
                     |actions_being_fed: &mut HashMap<&'static str, FedEntry>,
                      after_seconds: Float| {
@@ -480,7 +1673,16 @@ fn apply_controller_system(
                         {
                             reschedule_action(&mut controller.actions_being_fed, after_seconds);
                         }
-                        controller.current_action = if has_valid_contender {
+                        if let Some(momentum_policy) = momentum_policy {
+                            controller.carried_momentum = Some(CarriedMomentum {
+                                policy: momentum_policy,
+                                velocity: tracker
+                                    .velocity
+                                    .reject_from(up_direction.adjust_precision()),
+                                elapsed: 0.0,
+                            });
+                        }
+                        let next_action = if has_valid_contender {
                             let (contender_name, mut contender_action, _) = controller.contender_action.take().expect("has_valid_contender can only be true if contender_action is Some");
                             if let Some(contender_fed_entry) =
                                 controller.actions_being_fed.get_mut(contender_name)
@@ -494,6 +1696,9 @@ fn apply_controller_system(
                                     proximity_sensor,
                                     basis,
                                     up_direction,
+                                    surface_traction,
+                                    external_acceleration,
+                                    ground_conveyor,
                                 },
                                 TnuaActionLifecycleStatus::CancelledFrom,
                                 motor.as_mut(),
@@ -519,6 +1724,7 @@ fn apply_controller_system(
                                     Some((contender_name, contender_action))
                                 }
                                 TnuaActionLifecycleDirective::Finished => {
+                                    controller.spare_boxes.recycle_action(contender_action);
                                     if matches!(
                                         controller.action_flow_status,
                                         TnuaActionFlowStatus::ActionOngoing(_)
@@ -529,6 +1735,7 @@ fn apply_controller_system(
                                     None
                                 }
                                 TnuaActionLifecycleDirective::Reschedule { after_seconds } => {
+                                    controller.spare_boxes.recycle_action(contender_action);
                                     if matches!(
                                         controller.action_flow_status,
                                         TnuaActionFlowStatus::ActionOngoing(_)
@@ -547,6 +1754,11 @@ fn apply_controller_system(
                             controller.action_flow_status = TnuaActionFlowStatus::ActionEnded(name);
                             None
                         };
+                        if let Some((_, finished_action)) =
+                            std::mem::replace(&mut controller.current_action, next_action)
+                        {
+                            controller.spare_boxes.recycle_action(finished_action);
+                        }
                     }
                 }
             } else if has_valid_contender {
@@ -561,6 +1773,9 @@ fn apply_controller_system(
                         proximity_sensor,
                         basis,
                         up_direction,
+                        surface_traction,
+                        external_acceleration,
+                        ground_conveyor,
                     },
                     TnuaActionLifecycleStatus::Initiated,
                     motor.as_mut(),
@@ -572,6 +1787,38 @@ fn apply_controller_system(
                 controller.current_action = Some((contender_name, contender_action));
             }
 
+            motor.lin.acceleration += external_acceleration;
+
+            let was_on_ground = controller.ground.is_some();
+            let was_in_air = controller.time_since_leaving_ground.is_some();
+            let previous_vertical_velocity = controller.relative_vertical_velocity;
+            if basis.displacement().is_some() {
+                controller.ground = sensor.output.clone();
+                controller.time_since_leaving_ground = None;
+            } else {
+                controller.ground = None;
+                *controller.time_since_leaving_ground.get_or_insert(0.0) += frame_duration;
+            }
+            match (&controller.ground, was_on_ground) {
+                (Some(ground), false) if was_in_air => {
+                    events.push(TnuaControllerEvent::Landed {
+                        entity,
+                        impact_speed: previous_vertical_velocity
+                            .map_or(0.0, |vertical_velocity| (-vertical_velocity).max(0.0)),
+                        ground_entity: ground.entity,
+                    });
+                }
+                (None, true) => {
+                    events.push(TnuaControllerEvent::LeftGround { entity });
+                }
+                _ => {}
+            }
+            controller.relative_vertical_velocity = Some(
+                basis
+                    .effective_velocity()
+                    .dot(up_direction.adjust_precision()),
+            );
+
             let sensor_case_range_for_action =
                 if let Some((_, current_action)) = &controller.current_action {
                     current_action.proximity_sensor_cast_range()
@@ -581,6 +1828,58 @@ fn apply_controller_system(
 
             sensor.cast_range = sensor_cast_range_for_basis.max(sensor_case_range_for_action);
             sensor.cast_direction = -up_direction;
+        } else {
+            controller.ground = None;
+            controller.time_since_leaving_ground = None;
+            controller.relative_vertical_velocity = None;
+        }
+
+        let started_action = controller.action_flow_status.just_starting();
+        if let Some(stamina) = stamina.as_mut() {
+            let spent = if let Some((_, current_action)) = &controller.current_action {
+                let cost = current_action.stamina_cost();
+                let on_start = if started_action.is_some() {
+                    cost.on_start
+                } else {
+                    0.0
+                };
+                on_start + cost.per_second * frame_duration
+            } else {
+                0.0
+            };
+            if let Some(event) = stamina.update(entity, spent, frame_duration) {
+                events.push(event);
+            }
+        }
+        if let Some(action_name) = started_action {
+            controller.buffered_actions.remove(action_name);
+        }
+        controller.buffered_actions.retain(|_, buffered_entry| {
+            buffered_entry.expires_in.tick(delta);
+            !buffered_entry.expires_in.finished()
+        });
+        if let Some(action_name) = action_before {
+            if started_action.is_some() || controller.action_name().is_none() {
+                events.push(TnuaControllerEvent::ActionEnded {
+                    entity,
+                    action_name,
+                });
+            }
+        }
+        if let Some(action_name) = started_action {
+            events.push(TnuaControllerEvent::ActionStarted {
+                entity,
+                action_name,
+            });
+            if controller
+                .concrete_action::<crate::builtins::TnuaBuiltinJump>()
+                .is_some()
+            {
+                events.push(TnuaControllerEvent::Jumped {
+                    entity,
+                    action_name,
+                });
+            }
         }
 
         // Cycle actions_being_fed
@@ -588,7 +1887,7 @@ fn apply_controller_system(
             if fed_entry.fed_this_frame {
                 fed_entry.fed_this_frame = false;
                 if let Some(rescheduled_in) = &mut fed_entry.rescheduled_in {
-                    rescheduled_in.tick(time.delta());
+                    rescheduled_in.tick(delta);
                 }
                 true
             } else {
@@ -598,8 +1897,22 @@ fn apply_controller_system(
 
         if let Some((contender_name, ..)) = controller.contender_action {
             if !controller.actions_being_fed.contains_key(contender_name) {
-                controller.contender_action = None;
+                if let Some((_, abandoned_action, _)) = controller.contender_action.take() {
+                    controller.spare_boxes.recycle_action(abandoned_action);
+                }
             }
         }
+    });
+    // Which thread processed which character is not deterministic, so the order of the collected
+    // events isn't either. The sort is stable, so the events of each character keep their order.
+    sorted_events.extend(pending_events.drain());
+    sorted_events.sort_by_key(TnuaControllerEvent::entity);
+    event_writer.send_batch(sorted_events.drain(..));
+}
+
+fn blend_vel_changes(from: &TnuaVelChange, to: &TnuaVelChange, blend: Float) -> TnuaVelChange {
+    TnuaVelChange {
+        acceleration: from.acceleration.lerp(to.acceleration, blend),
+        boost: from.boost.lerp(to.boost, blend),
     }
 }
@@ -0,0 +1,104 @@
+//! Debug visualization for Tnua's sensors and motors.
+//!
+//! Requires the `debug_gizmos` feature.
+use bevy::color::palettes::css;
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::AsF32;
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker};
+
+pub struct TnuaDebugPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaDebugPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaDebugPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin for drawing Tnua's sensors and motors with [`Gizmos`].
+///
+/// This draws:
+///
+/// * The cast of every [`TnuaProximitySensor`] - gray when it does not detect anything, and green
+///   when it does. Only the ray is drawn, even if the sensor uses a shape cast.
+/// * The point and the normal of the detected surface.
+/// * For characters with [`TnuaBuiltinWalk`] - a circle at the float height, and the desired
+///   velocity (in blue) next to the actual velocity (in yellow).
+///
+/// The schedule should be the same one
+/// [`TnuaControllerPlugin`](crate::controller::TnuaControllerPlugin) uses.
+impl Plugin for TnuaDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            (draw_proximity_sensors, draw_walk_basis).after(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+fn draw_proximity_sensors(
+    query: Query<(&GlobalTransform, &TnuaProximitySensor)>,
+    mut gizmos: Gizmos,
+) {
+    for (transform, sensor) in query.iter() {
+        let origin = transform.transform_point(sensor.cast_origin.f32());
+        let direction = *sensor.cast_direction;
+        let end = origin + direction * sensor.cast_range.f32();
+        let Some(output) = &sensor.output else {
+            gizmos.line(origin, end, css::GRAY);
+            continue;
+        };
+        let hit_point = origin + direction * output.proximity.f32();
+        gizmos.line(origin, hit_point, css::LIME);
+        gizmos.line(hit_point, end, css::GRAY);
+        gizmos.sphere(Isometry3d::from_translation(hit_point), 0.05, css::LIME);
+        gizmos.arrow(hit_point, hit_point + *output.normal * 0.5, css::LIME);
+    }
+}
+
+fn draw_walk_basis(
+    query: Query<(
+        &GlobalTransform,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+    )>,
+    mut gizmos: Gizmos,
+) {
+    for (transform, controller, tracker, sensor) in query.iter() {
+        let Some((walk, _)) = controller.concrete_basis::<TnuaBuiltinWalk>() else {
+            continue;
+        };
+        let origin = transform.transform_point(sensor.cast_origin.f32());
+        let up = -*sensor.cast_direction;
+        gizmos.circle(
+            Isometry3d::new(
+                origin - up * walk.float_height.f32(),
+                Quat::from_rotation_arc(Vec3::Z, up),
+            ),
+            0.25,
+            css::WHITE,
+        );
+
+        let position = tracker.translation.f32();
+        gizmos.arrow(
+            position,
+            position + walk.desired_velocity.f32(),
+            css::DODGER_BLUE,
+        );
+        gizmos.arrow(position, position + tracker.velocity.f32(), css::YELLOW);
+    }
+}
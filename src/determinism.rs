@@ -0,0 +1,66 @@
+//! Making Tnua's simulation deterministic.
+//!
+//! Tnua itself does not use the wall clock or any randomness, and the logic of each character
+//! only depends on its own components - so the order in which the entities are iterated does not
+//! change the results. Running the same inputs on two machines will give the same trajectories
+//! as long as:
+//!
+//! * All the Tnua plugins, the physics backend and the player controls systems are registered in
+//!   a fixed timestep schedule (e.g. `FixedUpdate`), so that [`Time::delta`] is the same on every
+//!   tick. [`TnuaDeterminismPlugin`] warns when this is not the case.
+//! * The `libm` feature is enabled (on all the Tnua crates), so that the math functions whose
+//!   results are platform dependent - like the trigonometric functions - will use
+//!   [`libm`](https://docs.rs/libm) instead.
+//! * The physics backend itself is deterministic. Refer to its documentation for how to configure
+//!   that.
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::TnuaPipelineStages;
+
+pub struct TnuaDeterminismPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaDeterminismPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaDeterminismPlugin {
+    fn default() -> Self {
+        Self::new(FixedUpdate)
+    }
+}
+
+/// A plugin that verifies Tnua runs with a fixed timestep.
+///
+/// It logs a warning if the [`Time`] of the schedule Tnua runs in does not advance by the
+/// [`Time<Fixed>`] timestep - which means that the simulation depends on the frame rate.
+///
+/// The schedule should be the same one
+/// [`TnuaControllerPlugin`](crate::controller::TnuaControllerPlugin) uses.
+impl Plugin for TnuaDeterminismPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            check_fixed_timestep.before(TnuaPipelineStages::Sensors),
+        );
+    }
+}
+
+fn check_fixed_timestep(time: Res<Time>, fixed_time: Res<Time<Fixed>>, mut warned: Local<bool>) {
+    if *warned || time.delta() == fixed_time.timestep() {
+        return;
+    }
+    warn!(
+        "Tnua is running with a time delta of {:?} instead of the fixed timestep of {:?}, so its \
+         simulation will not be deterministic",
+        time.delta(),
+        fixed_time.timestep(),
+    );
+    *warned = true;
+}
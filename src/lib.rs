@@ -22,6 +22,15 @@
 //! defined by the physics backend - Bevy itself will still use single precision, and this is the
 //! precision the position and rotation will use.
 //!
+//! The `serde` flag can be added to make the builtin [basis](builtins) and action structs
+//! serializable - e.g. for loading the character's movement configuration from an asset file.
+//!
+//! The `debug_gizmos` flag adds the `debug` module, with a plugin for visualizing the sensors
+//! and the motors.
+//!
+//! The `libm` flag makes the math Tnua uses give the same results on all platforms. See the
+//! [`determinism`] module for what else is needed for a deterministic simulation.
+//!
 //! In addition to the physics integration plugin, the
 //! [`TnuaControllerPlugin`](prelude::TnuaControllerPlugin) should also be added.
 //!
@@ -33,6 +42,11 @@
 //! systems must also be registered under that same schedule (instead of under `Update`, which is
 //! where it should usually be registered)
 //!
+//! When running in `FixedUpdate` with a tick rate lower than the frame rate, the character may
+//! appear to stutter. To avoid that, the character's visual model can be placed on a separate
+//! entity with a [`TnuaVisualInterpolation`](crate::control_helpers::TnuaVisualInterpolation)
+//! component, which smoothly follows the character.
+//!
 //! A Tnua controlled character must have a dynamic rigid body, everything from
 //! `Tnua<physics-backend>IOBundle` (e.g. - for Rapier 3D, use `TnuaRapier3dIOBundle`), and a
 //! [`TnuaController`](prelude::TnuaController) (and its automatically added required component):
@@ -55,8 +69,8 @@
 //! ## Optional But Recommended
 //!
 //! * Tnua, by default, casts a single ray to the ground. This can be a problem when the character
-//!   stands on a ledge, because the ray may be past the ledge while the character's collider
-//!   isn't. To avoid that, use `Tnua<physics-backend>SensorShape` (e.g. - for Rapier 3D, use
+//!   stands on a ledge or on a narrow beam, because the ray may be past the ledge (or beside the
+//!   beam) while the character's collider isn't. To avoid that, use `Tnua<physics-backend>SensorShape` (e.g. - for Rapier 3D, use
 //!   `TnuaRapier3dSensorShape`) to replace the ray with a shape that resembles the collider. It is
 //!   better to use a shape a little bit smaller than the collider, so that when the character
 //!   presses against a wall Tnua won't think it should be lifted up when the casted shape hits
@@ -131,11 +145,17 @@ mod basis_action_traits;
 pub mod builtins;
 pub mod control_helpers;
 pub mod controller;
+#[cfg(feature = "debug_gizmos")]
+pub mod debug;
+pub mod determinism;
 pub mod util;
-pub use animating_helper::{TnuaAnimatingState, TnuaAnimatingStateDirective};
+pub use animating_helper::{
+    TnuaAnimatingState, TnuaAnimatingStateBlending, TnuaAnimatingStateDirective,
+};
 pub use basis_action_traits::{
     DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext, TnuaActionInitiationDirective,
     TnuaActionLifecycleDirective, TnuaActionLifecycleStatus, TnuaBasis, TnuaBasisContext,
+    TnuaMomentumPolicy, TnuaStaminaCost,
 };
 
 pub mod prelude {
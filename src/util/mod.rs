@@ -148,6 +148,55 @@ pub fn rotation_arc_around_axis(
     Some(rotation_to_set_forward.xyz().z)
 }
 
+/// A piecewise linear curve, for configuration values that depend on some other value.
+///
+/// The curve is defined by points, and sampling it between two points interpolates linearly
+/// between them. Sampling it before the first point or after the last point gives the value of
+/// that point.
+///
+/// ```
+/// # use bevy_tnua::util::TnuaCurve;
+/// let curve = TnuaCurve::new([(0.0, 10.0), (1.0, 20.0), (2.0, 0.0)]);
+/// assert_eq!(curve.sample(-1.0), 10.0);
+/// assert_eq!(curve.sample(0.5), 15.0);
+/// assert_eq!(curve.sample(1.5), 10.0);
+/// assert_eq!(curve.sample(3.0), 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaCurve {
+    points: Vec<(Float, Float)>,
+}
+
+impl TnuaCurve {
+    /// Create a curve from `(x, y)` points. The points do not need to be sorted.
+    pub fn new(points: impl IntoIterator<Item = (Float, Float)>) -> Self {
+        let mut points: Vec<_> = points.into_iter().collect();
+        points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self { points }
+    }
+
+    /// The points of the curve, sorted by their `x` values.
+    pub fn points(&self) -> &[(Float, Float)] {
+        &self.points
+    }
+
+    /// Get the value of the curve at `x`.
+    ///
+    /// An empty curve is `0.0` everywhere.
+    pub fn sample(&self, x: Float) -> Float {
+        let after = self.points.partition_point(|(point_x, _)| *point_x <= x);
+        match (
+            after.checked_sub(1).map(|i| self.points[i]),
+            self.points.get(after),
+        ) {
+            (None, None) => 0.0,
+            (Some((_, y)), None) | (None, Some(&(_, y))) => y,
+            (Some((x1, y1)), Some(&(x2, y2))) => y1 + (y2 - y1) * (x - x1) / (x2 - x1),
+        }
+    }
+}
+
 /// Temporary until we get an official release of the physics integration layer crate with
 /// `calc_boost` in it.
 pub(crate) fn calc_boost(